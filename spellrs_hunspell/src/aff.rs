@@ -0,0 +1,575 @@
+use std::{collections::HashMap, str::FromStr};
+
+use crate::{
+    affix::{Affix, AffixCondition, AffixEntry, AffixKind},
+    Flag, FlagMode, ParseError,
+};
+
+/// The parsed contents of a Hunspell `.aff` file.
+///
+/// See the [Hunspell docs](https://github.com/hunspell/hunspell/blob/master/docs/hunspell.5.md)
+/// for the meaning of each directive.
+#[derive(Debug, Default, Clone)]
+pub struct AffInfo {
+    /// `SET` - the character encoding of the `.aff` and `.dic` files.
+    pub set: Option<String>,
+    /// `FLAG` - how flags are written.
+    pub flag: FlagMode,
+    /// `TRY` - characters used for suggestions, most frequent first.
+    pub try_chars: Option<String>,
+    /// `KEY` - rows of neighboring keys on the keyboard.
+    pub key: Vec<String>,
+    /// `PFX` blocks, keyed by flag.
+    pub prefixes: HashMap<Flag, Affix>,
+    /// `SFX` blocks, keyed by flag.
+    pub suffixes: HashMap<Flag, Affix>,
+    /// `REP` - common misspellings as `(from, to)`. A `_` is replaced with a space.
+    pub rep: Vec<(String, String)>,
+    /// `MAP` - groups of related characters. Multi character items are written in parentheses.
+    pub map: Vec<Vec<String>>,
+    /// `MAXCPDSUGS` - the maximum number of compound words suggested.
+    pub max_cpd_sugs: Option<usize>,
+    /// `MAXNGRAMSUGS` - the maximum number of suggestions found by n-gram similarity.
+    pub max_ngram_sugs: Option<usize>,
+    /// `NOSPLITSUGS` - do not suggest splitting a word into two words.
+    pub no_split_sugs: bool,
+    /// `AF` - flag aliases. When present, flags in the `.dic` file are 1 based indexes into
+    /// this list.
+    pub aliases: Vec<Vec<Flag>>,
+    /// `NOSUGGEST` - words with this flag are not suggested.
+    pub nosuggest: Option<Flag>,
+    /// `FORBIDDENWORD` - words with this flag are forbidden.
+    pub forbiddenword: Option<Flag>,
+    /// `ONLYINCOMPOUND` - words or affixes with this flag only appear inside of compounds.
+    pub onlyincompound: Option<Flag>,
+    /// `NEEDAFFIX` - words with this flag are only valid with an affix applied.
+    pub needaffix: Option<Flag>,
+    /// `COMPOUND*` and `CHECKCOMPOUND*` directives.
+    pub compound: CompoundInfo,
+    /// Directives that are not interpreted by this parser, in the order they were found.
+    pub unknown: Vec<Directive>,
+    /// Problems that Hunspell only warns about, i.e. a flag with several `SFX` blocks.
+    pub warnings: Vec<ParseError>,
+}
+
+/// Settings used to build and check compound words.
+#[derive(Debug, Default, Clone)]
+pub struct CompoundInfo {
+    /// `COMPOUNDFLAG` - words with this flag can be anywhere in a compound.
+    pub flag: Option<Flag>,
+    /// `COMPOUNDBEGIN` - words with this flag can be the first part of a compound.
+    pub begin: Option<Flag>,
+    /// `COMPOUNDMIDDLE` - words with this flag can be in the middle of a compound.
+    pub middle: Option<Flag>,
+    /// `COMPOUNDEND` (or `COMPOUNDLAST`) - words with this flag can be the last part of a compound.
+    pub end: Option<Flag>,
+    /// `COMPOUNDPERMITFLAG` - affixes with this flag may be inside of a compound.
+    pub permit: Option<Flag>,
+    /// `COMPOUNDFORBIDFLAG` - affixes with this flag may not be part of a compound.
+    pub forbid: Option<Flag>,
+    /// `COMPOUNDROOT` - marks words that are already compounds.
+    pub root: Option<Flag>,
+    /// `COMPOUNDMIN` - the minimum length of a compound part.
+    pub min: Option<usize>,
+    /// `COMPOUNDWORDMAX` - the maximum number of parts in a compound.
+    pub word_max: Option<usize>,
+    /// `COMPOUNDRULE` - regular expressions over flags, i.e. `ABC*D?`.
+    pub rules: Vec<String>,
+    /// `CHECKCOMPOUNDDUP` - forbid duplicated parts, i.e. `foofoo`.
+    pub check_dup: bool,
+    /// `CHECKCOMPOUNDCASE` - forbid upper case characters at part boundaries.
+    pub check_case: bool,
+    /// `CHECKCOMPOUNDTRIPLE` - forbid triple letters at part boundaries.
+    pub check_triple: bool,
+    /// `CHECKCOMPOUNDREP` - forbid compounds that are a `REP` away from a dictionary word.
+    pub check_rep: bool,
+    /// `SIMPLIFIEDTRIPLE` - allow simplified triple letters, i.e. `Schiff` + `fahrt`.
+    pub simplified_triple: bool,
+    /// `COMPOUNDMORESUFFIXES` - allow twofold suffixes inside of compounds.
+    pub more_suffixes: bool,
+}
+
+/// A directive that was kept, but not interpreted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Directive {
+    pub line: usize,
+    pub name: String,
+    pub args: Vec<String>,
+}
+
+impl FromStr for AffInfo {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_aff(s)
+    }
+}
+
+impl AffInfo {
+    /// Decodes a flag set from the `.dic` file or an affix entry, resolving `AF` aliases.
+    pub fn decode_flags(&self, flags: &str) -> Result<Vec<Flag>, String> {
+        if self.aliases.is_empty() || flags.is_empty() {
+            return self.flag.parse_flags(flags);
+        }
+
+        let index: usize = flags
+            .parse()
+            .map_err(|_| format!("invalid flag alias `{flags}`"))?;
+
+        index
+            .checked_sub(1)
+            .and_then(|i| self.aliases.get(i))
+            .cloned()
+            .ok_or_else(|| format!("unknown flag alias `{flags}`"))
+    }
+
+    /// Looks up the affix block for a flag.
+    pub fn affix(&self, kind: AffixKind, flag: Flag) -> Option<&Affix> {
+        match kind {
+            AffixKind::Prefix => self.prefixes.get(&flag),
+            AffixKind::Suffix => self.suffixes.get(&flag),
+        }
+    }
+}
+
+struct Line<'a> {
+    number: usize,
+    name: &'a str,
+    args: Vec<&'a str>,
+}
+
+impl Line<'_> {
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError::new(self.number, message)
+    }
+
+    fn arg(&self, i: usize) -> Result<&str, ParseError> {
+        self.args
+            .get(i)
+            .copied()
+            .ok_or_else(|| self.error(format!("missing argument for {}", self.name)))
+    }
+
+    fn number(&self, i: usize) -> Result<usize, ParseError> {
+        let arg = self.arg(i)?;
+        arg.parse().map_err(|_| {
+            self.error(format!(
+                "expected a number for {}, found `{arg}`",
+                self.name
+            ))
+        })
+    }
+
+    fn flag(&self, mode: &FlagMode) -> Result<Flag, ParseError> {
+        mode.parse_flag(self.arg(0)?).map_err(|e| self.error(e))
+    }
+}
+
+fn significant_lines(src: &str) -> impl Iterator<Item = Line<'_>> {
+    src.trim_start_matches('\u{feff}')
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let mut fields = line.split_whitespace();
+            let name = fields.next()?;
+            match name.starts_with('#') {
+                true => None,
+                false => Some(Line {
+                    number: i + 1,
+                    name,
+                    args: fields.collect(),
+                }),
+            }
+        })
+}
+
+/// Parses the contents of a Hunspell `.aff` file.
+pub fn parse_aff(src: &str) -> Result<AffInfo, ParseError> {
+    let mut aff = AffInfo::default();
+    let mut lines = significant_lines(src);
+
+    while let Some(line) = lines.next() {
+        match line.name {
+            "SET" => aff.set = Some(line.arg(0)?.to_string()),
+            "FLAG" => aff.flag = line.arg(0)?.parse().map_err(|e| line.error(e))?,
+            "TRY" => aff.try_chars = Some(line.arg(0)?.to_string()),
+            "KEY" => aff.key = line.arg(0)?.split('|').map(String::from).collect(),
+            "PFX" | "SFX" => {
+                let affix = parse_affix(&line, &mut lines, &aff)?;
+                let affixes = match affix.kind {
+                    AffixKind::Prefix => &mut aff.prefixes,
+                    AffixKind::Suffix => &mut aff.suffixes,
+                };
+                // Like Hunspell, the entries of a repeated flag are added to the first block.
+                match affixes.get_mut(&affix.flag) {
+                    Some(existing) => {
+                        existing.entries.extend(affix.entries);
+                        aff.warnings.push(line.error(format!(
+                            "duplicate {} flag `{}`",
+                            line.name,
+                            line.arg(0)?
+                        )));
+                    }
+                    None => {
+                        affixes.insert(affix.flag, affix);
+                    }
+                }
+            }
+            "REP" => {
+                for entry in table(&line, &mut lines)? {
+                    let from = entry.arg(0)?.replace('_', " ");
+                    let to = entry.arg(1)?.replace('_', " ");
+                    aff.rep.push((from, to));
+                }
+            }
+            "MAP" => {
+                for entry in table(&line, &mut lines)? {
+                    aff.map
+                        .push(parse_map(entry.arg(0)?).map_err(|e| entry.error(e))?);
+                }
+            }
+            "AF" => {
+                for entry in table(&line, &mut lines)? {
+                    let flags = aff.flag.parse_flags(entry.arg(0)?);
+                    aff.aliases.push(flags.map_err(|e| entry.error(e))?);
+                }
+            }
+            "COMPOUNDRULE" => {
+                for entry in table(&line, &mut lines)? {
+                    aff.compound.rules.push(entry.arg(0)?.to_string());
+                }
+            }
+            "MAXCPDSUGS" => aff.max_cpd_sugs = Some(line.number(0)?),
+            "MAXNGRAMSUGS" => aff.max_ngram_sugs = Some(line.number(0)?),
+            "NOSPLITSUGS" => aff.no_split_sugs = true,
+            "NOSUGGEST" => aff.nosuggest = Some(line.flag(&aff.flag)?),
+            "FORBIDDENWORD" => aff.forbiddenword = Some(line.flag(&aff.flag)?),
+            "ONLYINCOMPOUND" => aff.onlyincompound = Some(line.flag(&aff.flag)?),
+            "NEEDAFFIX" => aff.needaffix = Some(line.flag(&aff.flag)?),
+            "COMPOUNDFLAG" => aff.compound.flag = Some(line.flag(&aff.flag)?),
+            "COMPOUNDBEGIN" => aff.compound.begin = Some(line.flag(&aff.flag)?),
+            "COMPOUNDMIDDLE" => aff.compound.middle = Some(line.flag(&aff.flag)?),
+            "COMPOUNDEND" | "COMPOUNDLAST" => aff.compound.end = Some(line.flag(&aff.flag)?),
+            "COMPOUNDPERMITFLAG" => aff.compound.permit = Some(line.flag(&aff.flag)?),
+            "COMPOUNDFORBIDFLAG" => aff.compound.forbid = Some(line.flag(&aff.flag)?),
+            "COMPOUNDROOT" => aff.compound.root = Some(line.flag(&aff.flag)?),
+            "COMPOUNDMIN" => aff.compound.min = Some(line.number(0)?),
+            "COMPOUNDWORDMAX" => aff.compound.word_max = Some(line.number(0)?),
+            "CHECKCOMPOUNDDUP" => aff.compound.check_dup = true,
+            "CHECKCOMPOUNDCASE" => aff.compound.check_case = true,
+            "CHECKCOMPOUNDTRIPLE" => aff.compound.check_triple = true,
+            "CHECKCOMPOUNDREP" => aff.compound.check_rep = true,
+            "SIMPLIFIEDTRIPLE" => aff.compound.simplified_triple = true,
+            "COMPOUNDMORESUFFIXES" => aff.compound.more_suffixes = true,
+            _ => aff.unknown.push(Directive {
+                line: line.number,
+                name: line.name.to_string(),
+                args: line.args.iter().map(|a| a.to_string()).collect(),
+            }),
+        }
+    }
+
+    Ok(aff)
+}
+
+/// Reads the entries of a table directive like `REP`, `MAP` or `AF`.
+///
+/// The header line holds the number of entries, i.e. `REP 2`, followed by that many lines
+/// starting with the same directive.
+fn table<'a>(
+    header: &Line,
+    lines: &mut impl Iterator<Item = Line<'a>>,
+) -> Result<Vec<Line<'a>>, ParseError> {
+    let count = header.number(0)?;
+    let mut entries = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        let entry = lines.next().ok_or_else(|| {
+            header.error(format!(
+                "expected {count} {} entries, found {}",
+                header.name,
+                entries.len()
+            ))
+        })?;
+
+        if entry.name != header.name {
+            return Err(entry.error(format!(
+                "expected a {} entry, found `{}`",
+                header.name, entry.name
+            )));
+        }
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+fn parse_affix<'a>(
+    header: &Line,
+    lines: &mut impl Iterator<Item = Line<'a>>,
+    aff: &AffInfo,
+) -> Result<Affix, ParseError> {
+    let kind = match header.name {
+        "PFX" => AffixKind::Prefix,
+        _ => AffixKind::Suffix,
+    };
+    let flag_str = header.arg(0)?;
+    let flag = header.flag(&aff.flag)?;
+    let cross_product = match header.arg(1)? {
+        "Y" => true,
+        "N" => false,
+        other => {
+            return Err(header.error(format!(
+                "expected `Y` or `N` for cross product, found `{other}`"
+            )))
+        }
+    };
+    let count = header.number(2)?;
+    let mut entries = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        let line = lines.next().ok_or_else(|| {
+            header.error(format!(
+                "expected {count} {} {flag_str} entries, found {}",
+                header.name,
+                entries.len()
+            ))
+        })?;
+
+        if line.name != header.name || line.args.first() != Some(&flag_str) {
+            return Err(line.error(format!(
+                "expected a {} {flag_str} entry, found `{} {}`",
+                header.name,
+                line.name,
+                line.args.first().unwrap_or(&"")
+            )));
+        }
+
+        let strip = match line.arg(1)? {
+            "0" => "",
+            strip => strip,
+        };
+        let (append, flags) = match line.arg(2)?.split_once('/') {
+            Some((append, flags)) => (append, aff.decode_flags(flags).map_err(|e| line.error(e))?),
+            None => (line.arg(2)?, vec![]),
+        };
+        let append = match append {
+            "0" => "",
+            append => append,
+        };
+        let condition = AffixCondition::new(line.args.get(3).copied().unwrap_or("."), kind)
+            .map_err(|e| line.error(e))?;
+
+        entries.push(AffixEntry {
+            strip: strip.to_string(),
+            append: append.to_string(),
+            flags,
+            condition,
+            morph: line.args.iter().skip(4).map(|m| m.to_string()).collect(),
+        });
+    }
+
+    Ok(Affix {
+        kind,
+        flag,
+        cross_product,
+        entries,
+    })
+}
+
+/// Splits a `MAP` entry into its items. `ß(ss)` becomes `["ß", "ss"]`.
+fn parse_map(entry: &str) -> Result<Vec<String>, String> {
+    let mut items = vec![];
+    let mut chars = entry.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '(' => {
+                let item: String = chars.by_ref().take_while(|&c| c != ')').collect();
+                items.push(item);
+            }
+            c => items.push(c.to_string()),
+        }
+    }
+
+    match entry.matches('(').count() == entry.matches(')').count() {
+        true => Ok(items),
+        false => Err(format!("unbalanced parentheses in MAP entry `{entry}`")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EN_AFF: &str = "
+# A small sample based upon en_US
+SET UTF-8
+TRY esianrtolcdugmphbyfvkwzESIANRTOLCDUGMPHBYFVKWZ'
+KEY qwertyuiop|asdfghjkl|zxcvbnm
+NOSUGGEST !
+FORBIDDENWORD *
+ONLYINCOMPOUND c
+NEEDAFFIX n
+WORDCHARS 0123456789'
+
+PFX A Y 1
+PFX A   0     re         .
+
+SFX D Y 4
+SFX D   0     d          e
+SFX D   y     ied        [^aeiou]y
+SFX D   0     ed         [^ey]
+SFX D   0     ed/X       [aeiou]y   po:verb
+
+REP 2
+REP f ph
+REP alot a_lot
+
+MAP 2
+MAP aáâ
+MAP ß(ss)
+
+COMPOUNDFLAG Y
+COMPOUNDMIN 1
+COMPOUNDRULE 1
+COMPOUNDRULE n*1t
+CHECKCOMPOUNDDUP
+
+MAXCPDSUGS 1
+MAXNGRAMSUGS 2
+NOSPLITSUGS
+";
+
+    fn flag(c: char) -> Flag {
+        Flag(c as u32)
+    }
+
+    #[test]
+    fn test_parse_aff() {
+        let aff: AffInfo = EN_AFF.parse().unwrap();
+
+        assert_eq!(Some("UTF-8".to_string()), aff.set);
+        assert_eq!(FlagMode::Short, aff.flag);
+        assert!(aff.try_chars.unwrap().starts_with("esianrt"));
+        assert_eq!(vec!["qwertyuiop", "asdfghjkl", "zxcvbnm"], aff.key);
+        assert_eq!(Some(flag('!')), aff.nosuggest);
+        assert_eq!(Some(flag('*')), aff.forbiddenword);
+        assert_eq!(Some(flag('c')), aff.onlyincompound);
+        assert_eq!(Some(flag('n')), aff.needaffix);
+
+        let pfx = &aff.prefixes[&flag('A')];
+        assert_eq!(AffixKind::Prefix, pfx.kind);
+        assert!(pfx.cross_product);
+        assert_eq!(1, pfx.entries.len());
+        assert_eq!("re", pfx.entries[0].append);
+        assert_eq!("", pfx.entries[0].strip);
+
+        let sfx = &aff.suffixes[&flag('D')];
+        assert_eq!(4, sfx.entries.len());
+        assert_eq!("y", sfx.entries[1].strip);
+        assert_eq!("ied", sfx.entries[1].append);
+        assert_eq!("[^aeiou]y", sfx.entries[1].condition.as_str());
+        assert_eq!(vec![flag('X')], sfx.entries[3].flags);
+        assert_eq!(vec!["po:verb"], sfx.entries[3].morph);
+
+        assert_eq!(
+            vec![
+                ("f".to_string(), "ph".to_string()),
+                ("alot".to_string(), "a lot".to_string())
+            ],
+            aff.rep
+        );
+        assert_eq!(vec![vec!["a", "á", "â"], vec!["ß", "ss"]], aff.map);
+
+        assert_eq!(Some(flag('Y')), aff.compound.flag);
+        assert_eq!(Some(1), aff.compound.min);
+        assert_eq!(vec!["n*1t"], aff.compound.rules);
+        assert!(aff.compound.check_dup);
+
+        assert_eq!(Some(1), aff.max_cpd_sugs);
+        assert_eq!(Some(2), aff.max_ngram_sugs);
+        assert!(aff.no_split_sugs);
+
+        assert_eq!(
+            vec![Directive {
+                line: 10,
+                name: "WORDCHARS".into(),
+                args: vec!["0123456789'".into()]
+            }],
+            aff.unknown
+        );
+    }
+
+    #[test]
+    fn test_parse_flag_modes() {
+        let aff: AffInfo = "FLAG long\nNOSUGGEST Ns\nSFX Aa N 1\nSFX Aa 0 s/BbCc .\n"
+            .parse()
+            .unwrap();
+        let long = |s: &str| FlagMode::Long.parse_flag(s).unwrap();
+        assert_eq!(Some(long("Ns")), aff.nosuggest);
+        assert_eq!(
+            vec![long("Bb"), long("Cc")],
+            aff.suffixes[&long("Aa")].entries[0].flags
+        );
+
+        let aff: AffInfo = "FLAG num\nFORBIDDENWORD 101\nPFX 7 N 1\nPFX 7 0 un .\n"
+            .parse()
+            .unwrap();
+        assert_eq!(Some(Flag(101)), aff.forbiddenword);
+        assert!(aff.prefixes.contains_key(&Flag(7)));
+
+        let aff: AffInfo = "FLAG UTF-8\nNEEDAFFIX ü\n".parse().unwrap();
+        assert_eq!(FlagMode::Utf8, aff.flag);
+        assert_eq!(Some(flag('ü')), aff.needaffix);
+    }
+
+    #[test]
+    fn test_parse_aliases() {
+        let aff: AffInfo = "AF 2\nAF AB\nAF C\nSFX C N 1\nSFX C 0 s/1 .\n"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            vec![vec![flag('A'), flag('B')], vec![flag('C')]],
+            aff.aliases
+        );
+        assert_eq!(vec![flag('C')], aff.decode_flags("2").unwrap());
+        assert_eq!(
+            vec![flag('A'), flag('B')],
+            aff.suffixes[&flag('C')].entries[0].flags
+        );
+        assert!(aff.decode_flags("3").is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let cases = [
+            ("FLAG bogus", 1),
+            ("SET UTF-8\n\nSFX D Y 2\nSFX D 0 d e\n", 3),
+            ("# comment\nPFX A X 1\nPFX A 0 re .", 2),
+            ("REP 2\nREP a b\nTRY abc\n", 3),
+            ("SFX D Y 1\nSFX E 0 d e\n", 2),
+            ("SFX D Y 1\nSFX D 0 d [ae\n", 2),
+            ("COMPOUNDMIN x", 1),
+            ("FLAG long\nNOSUGGEST abc", 2),
+        ];
+
+        for (src, line) in cases {
+            let result = parse_aff(src).unwrap_err();
+            assert_eq!(line, result.line, "{src:?} -> {result}");
+        }
+    }
+
+    #[test]
+    fn test_parse_duplicate_affix() {
+        let aff = parse_aff("SFX D Y 1\nSFX D 0 d .\nSFX D N 1\nSFX D 0 s .\n").unwrap();
+        let suffix = &aff.suffixes[&flag('D')];
+        let appends: Vec<&str> = suffix.entries.iter().map(|e| e.append.as_str()).collect();
+        assert_eq!(vec!["d", "s"], appends);
+        assert!(suffix.cross_product);
+        assert_eq!(
+            vec![ParseError::new(3, "duplicate SFX flag `D`")],
+            aff.warnings
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use regex::Regex;
+
+use crate::Flag;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AffixKind {
+    Prefix,
+    Suffix,
+}
+
+/// A `PFX` or `SFX` block from the `.aff` file.
+///
+/// ```text
+/// SFX D Y 2
+/// SFX D   0     d          e
+/// SFX D   y     ied        [^aeiou]y
+/// ```
+#[derive(Debug, Clone)]
+pub struct Affix {
+    pub kind: AffixKind,
+    pub flag: Flag,
+    /// `Y` in the header, the affix can be combined with affixes of the other kind.
+    pub cross_product: bool,
+    pub entries: Vec<AffixEntry>,
+}
+
+/// A single rule line of an affix block.
+#[derive(Debug, Clone)]
+pub struct AffixEntry {
+    /// Characters removed from the root before appending. `0` in the file means empty.
+    pub strip: String,
+    /// Characters added to the root. `0` in the file means empty.
+    pub append: String,
+    /// Continuation flags, written after a `/` in the append field.
+    pub flags: Vec<Flag>,
+    pub condition: AffixCondition,
+    /// Optional morphological fields, i.e. `po:noun`.
+    pub morph: Vec<String>,
+}
+
+/// The condition a root must match for an affix entry to be applied.
+///
+/// Hunspell conditions are a tiny subset of regular expressions: literal characters,
+/// `.` and bracketed character classes like `[^aeiou]`.
+#[derive(Debug, Clone)]
+pub struct AffixCondition {
+    source: String,
+    /// `None` when the condition matches everything.
+    regex: Option<Regex>,
+}
+
+impl AffixCondition {
+    pub fn new(source: &str, kind: AffixKind) -> Result<Self, String> {
+        let regex = match source {
+            "." | "" => None,
+            _ => {
+                let pattern = condition_to_regex(source)?;
+                let pattern = match kind {
+                    AffixKind::Prefix => format!("^(?:{pattern})"),
+                    AffixKind::Suffix => format!("(?:{pattern})$"),
+                };
+                Some(
+                    Regex::new(&pattern)
+                        .map_err(|e| format!("invalid condition `{source}`: {e}"))?,
+                )
+            }
+        };
+
+        Ok(Self {
+            source: source.to_string(),
+            regex,
+        })
+    }
+
+    /// The condition as written in the `.aff` file.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Checks if the condition matches the root word.
+    pub fn matches(&self, word: &str) -> bool {
+        match &self.regex {
+            Some(re) => re.is_match(word),
+            None => true,
+        }
+    }
+}
+
+impl AffixEntry {
+    /// Applies the entry to `word` if it matches the condition and strip characters.
+    pub fn apply(&self, word: &str, kind: AffixKind) -> Option<String> {
+        if !self.condition.matches(word) {
+            return None;
+        }
+
+        match kind {
+            AffixKind::Prefix => {
+                let rest = word.strip_prefix(self.strip.as_str())?;
+                Some(format!("{}{}", self.append, rest))
+            }
+            AffixKind::Suffix => {
+                let rest = word.strip_suffix(self.strip.as_str())?;
+                Some(format!("{}{}", rest, self.append))
+            }
+        }
+    }
+}
+
+fn condition_to_regex(condition: &str) -> Result<String, String> {
+    let mut pattern = String::new();
+    let mut in_class = false;
+
+    for c in condition.chars() {
+        match c {
+            '[' if !in_class => {
+                in_class = true;
+                pattern.push('[');
+            }
+            ']' if in_class => {
+                in_class = false;
+                pattern.push(']');
+            }
+            '^' if in_class && pattern.ends_with('[') => pattern.push('^'),
+            '.' if !in_class => pattern.push('.'),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    match in_class {
+        true => Err(format!("unterminated character class in `{condition}`")),
+        false => Ok(pattern),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entry(strip: &str, append: &str, condition: &str, kind: AffixKind) -> AffixEntry {
+        AffixEntry {
+            strip: strip.into(),
+            append: append.into(),
+            flags: vec![],
+            condition: AffixCondition::new(condition, kind).unwrap(),
+            morph: vec![],
+        }
+    }
+
+    #[test]
+    fn test_condition() {
+        let cases = [
+            (".", AffixKind::Suffix, "anything", true),
+            ("y", AffixKind::Suffix, "happy", true),
+            ("[^aeiou]y", AffixKind::Suffix, "happy", true),
+            ("[^aeiou]y", AffixKind::Suffix, "play", false),
+            ("[^y]", AffixKind::Suffix, "play", false),
+            ("e", AffixKind::Prefix, "elect", true),
+            ("e", AffixKind::Prefix, "select", false),
+            ("a.c", AffixKind::Prefix, "abc", true),
+            ("a+", AffixKind::Prefix, "a+b", true),
+            ("a+", AffixKind::Prefix, "aab", false),
+        ];
+
+        for (condition, kind, word, expected) in cases {
+            let result = AffixCondition::new(condition, kind).unwrap().matches(word);
+            assert_eq!(expected, result, "{condition} {word}");
+        }
+    }
+
+    #[test]
+    fn test_condition_errors() {
+        assert!(AffixCondition::new("[abc", AffixKind::Suffix).is_err());
+    }
+
+    #[test]
+    fn test_apply() {
+        let cases = [
+            (
+                entry("y", "ied", "[^aeiou]y", AffixKind::Suffix),
+                AffixKind::Suffix,
+                "happy",
+                Some("happied"),
+            ),
+            (
+                entry("y", "ied", "[^aeiou]y", AffixKind::Suffix),
+                AffixKind::Suffix,
+                "play",
+                None,
+            ),
+            (
+                entry("", "re", ".", AffixKind::Prefix),
+                AffixKind::Prefix,
+                "do",
+                Some("redo"),
+            ),
+            (
+                entry("a", "b", ".", AffixKind::Prefix),
+                AffixKind::Prefix,
+                "do",
+                None,
+            ),
+        ];
+
+        for (entry, kind, word, expected) in cases {
+            assert_eq!(expected.map(String::from), entry.apply(word, kind));
+        }
+    }
+}
@@ -0,0 +1,246 @@
+mod sort;
+
+use std::{
+    error::Error,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use clap::{Args, Parser, Subcommand};
+use sort::{ExternalSorter, DEFAULT_CHUNK_SIZE};
+use spellrs_hunspell::{ExpandOptions, HunspellDictionary};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "hunspell",
+    about = "A tool for reading Hunspell dictionary files"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Output all the words in the <hunspell.dic> file.
+    Words(WordsArgs),
+}
+
+#[derive(Debug, Args)]
+struct WordsArgs {
+    /// output file - defaults to stdout
+    #[arg(short, long, value_name = "file")]
+    output: Option<PathBuf>,
+
+    /// sort the list of words
+    #[arg(short, long)]
+    sort: bool,
+
+    /// make sure the words are unique.
+    #[arg(short, long)]
+    unique: bool,
+
+    /// used with --unique and --sort
+    #[arg(short = 'i', long = "ignore_case")]
+    ignore_case: bool,
+
+    /// output in lower case
+    #[arg(short = 'l', long = "lower_case")]
+    lower_case: bool,
+
+    /// Do not apply the prefix and suffix transforms.  Root words only.
+    #[arg(short = 'T', long = "no-transform")]
+    no_transform: bool,
+
+    #[arg(value_name = "hunspell_dic_file")]
+    dic: PathBuf,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Command::Words(args) => words(&args),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("hunspell: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn words(args: &WordsArgs) -> Result<(), Box<dyn Error>> {
+    let dict = HunspellDictionary::from_dic_file(&args.dic)?;
+    let options = ExpandOptions {
+        transform: !args.no_transform,
+        ..Default::default()
+    };
+    let words = dict.words_with_options(options);
+
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    write_words(words, args, DEFAULT_CHUNK_SIZE, &mut out)?;
+    out.flush()?;
+    Ok(())
+}
+
+fn write_words(
+    words: impl Iterator<Item = String>,
+    args: &WordsArgs,
+    chunk_size: usize,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let words = words.map(|word| match args.lower_case {
+        true => word.to_lowercase(),
+        false => word,
+    });
+    let key = |word: &str| match args.ignore_case {
+        true => word.to_lowercase(),
+        false => word.to_string(),
+    };
+
+    match (args.sort, args.unique) {
+        (false, false) => {
+            for word in words {
+                writeln!(out, "{word}")?;
+            }
+        }
+        (true, unique) => {
+            // With `--ignore_case` records are `key\0word`, so words sort by key first.
+            let mut sorter = ExternalSorter::new(chunk_size);
+            for word in words {
+                match args.ignore_case {
+                    true => sorter.push(format!("{}\0{word}", key(&word)))?,
+                    false => sorter.push(word)?,
+                }
+            }
+
+            let mut last_key: Option<String> = None;
+            for record in sorter.finish()? {
+                let record = record?;
+                let (key, word) = record
+                    .split_once('\0')
+                    .unwrap_or((record.as_str(), record.as_str()));
+                if unique && last_key.as_deref() == Some(key) {
+                    continue;
+                }
+                writeln!(out, "{word}")?;
+                last_key = Some(key.to_string());
+            }
+        }
+        (false, true) => {
+            // Keep the first occurrence of each key without holding all the words in memory:
+            // sort by `key\0index`, drop duplicate keys, then sort the survivors by index.
+            let mut by_key = ExternalSorter::new(chunk_size);
+            for (i, word) in words.enumerate() {
+                by_key.push(format!("{}\0{i:016x}\0{word}", key(&word)))?;
+            }
+
+            let mut by_index = ExternalSorter::new(chunk_size);
+            let mut last_key: Option<String> = None;
+            for record in by_key.finish()? {
+                let record = record?;
+                let mut parts = record.splitn(3, '\0');
+                let (key, index, word) = (
+                    parts.next().unwrap_or_default(),
+                    parts.next().unwrap_or_default(),
+                    parts.next().unwrap_or_default(),
+                );
+                if last_key.as_deref() == Some(key) {
+                    continue;
+                }
+                by_index.push(format!("{index}\0{word}"))?;
+                last_key = Some(key.to_string());
+            }
+
+            for record in by_index.finish()? {
+                let record = record?;
+                let (_, word) = record.split_once('\0').unwrap_or_default();
+                writeln!(out, "{word}")?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn run(flags: &[&str], words: &[&str]) -> Vec<String> {
+        let args = ["hunspell", "words"]
+            .iter()
+            .chain(flags)
+            .chain(&["en.dic"])
+            .copied();
+        let Command::Words(args) = Cli::parse_from(args).command;
+
+        let mut results = vec![];
+        for chunk_size in [4, DEFAULT_CHUNK_SIZE] {
+            let mut out = vec![];
+            let words = words.iter().map(|w| w.to_string());
+            write_words(words, &args, chunk_size, &mut out).unwrap();
+            results.push(String::from_utf8(out).unwrap());
+        }
+
+        assert_eq!(results[0], results[1], "{flags:?}");
+        results[0].lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_write_words() {
+        let words = ["pear", "Apple", "fig", "apple", "pear", "Fig", "banana"];
+        let cases: [(&[&str], Vec<&str>); 7] = [
+            (&[], words.to_vec()),
+            (
+                &["-s"],
+                vec!["Apple", "Fig", "apple", "banana", "fig", "pear", "pear"],
+            ),
+            (
+                &["-s", "-u"],
+                vec!["Apple", "Fig", "apple", "banana", "fig", "pear"],
+            ),
+            (&["-s", "-u", "-i"], vec!["Apple", "banana", "Fig", "pear"]),
+            (
+                &["-u"],
+                vec!["pear", "Apple", "fig", "apple", "Fig", "banana"],
+            ),
+            (&["-u", "-i"], vec!["pear", "Apple", "fig", "banana"]),
+            (&["-u", "-l"], vec!["pear", "apple", "fig", "banana"]),
+        ];
+
+        for (flags, expected) in cases {
+            assert_eq!(expected, run(flags, &words), "{flags:?}");
+        }
+    }
+
+    #[test]
+    fn test_cli_options() {
+        let cli = Cli::parse_from([
+            "hunspell",
+            "words",
+            "-o",
+            "out.txt",
+            "--sort",
+            "--unique",
+            "--ignore_case",
+            "--lower_case",
+            "--no-transform",
+            "en_US.dic",
+        ]);
+        let Command::Words(args) = cli.command;
+        assert_eq!(Some(PathBuf::from("out.txt")), args.output);
+        assert!(args.sort && args.unique && args.ignore_case && args.lower_case);
+        assert!(args.no_transform);
+        assert_eq!(PathBuf::from("en_US.dic"), args.dic);
+    }
+}
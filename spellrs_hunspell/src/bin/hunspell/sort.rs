@@ -0,0 +1,142 @@
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Lines, Seek, SeekFrom, Write},
+    mem, vec,
+};
+
+/// The number of bytes of records held in memory before a sorted run is written to disk.
+pub const DEFAULT_CHUNK_SIZE: usize = 64 * 1024 * 1024;
+
+/// Sorts newline free records that may not fit into memory.
+///
+/// Records are collected until `chunk_size` bytes are buffered. The buffer is then sorted and
+/// written to a temporary file. When finished, the sorted runs are merged back together.
+pub struct ExternalSorter {
+    chunk: Vec<String>,
+    chunk_bytes: usize,
+    chunk_size: usize,
+    runs: Vec<File>,
+}
+
+impl ExternalSorter {
+    pub fn new(chunk_size: usize) -> Self {
+        Self {
+            chunk: vec![],
+            chunk_bytes: 0,
+            chunk_size,
+            runs: vec![],
+        }
+    }
+
+    pub fn push(&mut self, record: String) -> io::Result<()> {
+        self.chunk_bytes += record.len();
+        self.chunk.push(record);
+
+        if self.chunk_bytes >= self.chunk_size {
+            self.spill()?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the records in sorted order.
+    pub fn finish(mut self) -> io::Result<Sorted> {
+        if self.runs.is_empty() {
+            self.chunk.sort_unstable();
+            return Ok(Sorted::Memory(mem::take(&mut self.chunk).into_iter()));
+        }
+
+        self.spill()?;
+        let mut runs: Vec<Lines<BufReader<File>>> = self
+            .runs
+            .into_iter()
+            .map(|file| BufReader::new(file).lines())
+            .collect();
+
+        let mut heap = BinaryHeap::with_capacity(runs.len());
+        for (i, run) in runs.iter_mut().enumerate() {
+            if let Some(line) = run.next() {
+                heap.push(Reverse((line?, i)));
+            }
+        }
+
+        Ok(Sorted::Merge { runs, heap })
+    }
+
+    fn spill(&mut self) -> io::Result<()> {
+        if self.chunk.is_empty() {
+            return Ok(());
+        }
+
+        self.chunk.sort_unstable();
+        let mut file = tempfile::tempfile()?;
+        {
+            let mut writer = BufWriter::new(&mut file);
+            for record in self.chunk.drain(..) {
+                writer.write_all(record.as_bytes())?;
+                writer.write_all(b"\n")?;
+            }
+            writer.flush()?;
+        }
+        file.seek(SeekFrom::Start(0))?;
+
+        self.runs.push(file);
+        self.chunk_bytes = 0;
+        Ok(())
+    }
+}
+
+/// The sorted records of an [`ExternalSorter`].
+pub enum Sorted {
+    Memory(vec::IntoIter<String>),
+    Merge {
+        runs: Vec<Lines<BufReader<File>>>,
+        heap: BinaryHeap<Reverse<(String, usize)>>,
+    },
+}
+
+impl Iterator for Sorted {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Sorted::Memory(records) => records.next().map(Ok),
+            Sorted::Merge { runs, heap } => {
+                let Reverse((record, i)) = heap.pop()?;
+                match runs[i].next() {
+                    Some(Ok(line)) => heap.push(Reverse((line, i))),
+                    Some(Err(e)) => return Some(Err(e)),
+                    None => {}
+                }
+                Some(Ok(record))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sort(records: &[&str], chunk_size: usize) -> Vec<String> {
+        let mut sorter = ExternalSorter::new(chunk_size);
+        for record in records {
+            sorter.push(record.to_string()).unwrap();
+        }
+        sorter.finish().unwrap().map(Result::unwrap).collect()
+    }
+
+    #[test]
+    fn test_external_sort() {
+        let records = ["pear", "apple", "fig", "banana", "apple", "cherry", "date"];
+        let mut expected: Vec<String> = records.iter().map(|r| r.to_string()).collect();
+        expected.sort();
+
+        for chunk_size in [1, 5, 12, DEFAULT_CHUNK_SIZE] {
+            assert_eq!(expected, sort(&records, chunk_size), "{chunk_size}");
+        }
+        assert!(sort(&[], 1).is_empty());
+    }
+}
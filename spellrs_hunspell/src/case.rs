@@ -0,0 +1,107 @@
+/// The capitalization of a word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    /// `hello`
+    Lower,
+    /// `Hello`
+    Title,
+    /// `HELLO`
+    Upper,
+    /// `iPhone` or `McDonald`
+    Mixed,
+}
+
+impl Case {
+    pub fn of(word: &str) -> Self {
+        let mut chars = word.chars();
+        let first_upper = chars.next().is_some_and(char::is_uppercase);
+        let rest_lower = chars.clone().all(|c| !c.is_uppercase());
+        let rest_upper = chars.clone().any(char::is_alphabetic) && chars.all(|c| !c.is_lowercase());
+
+        match (first_upper, rest_lower, rest_upper) {
+            (false, true, _) => Case::Lower,
+            (true, _, true) => Case::Upper,
+            (true, true, _) => Case::Title,
+            _ => Case::Mixed,
+        }
+    }
+
+    /// Changes the case of a lower case word, i.e. a suggestion. Words with capitals, i.e.
+    /// `Paris` or `iPhone`, are only made upper case.
+    pub fn apply(&self, word: &str) -> String {
+        let is_lower = word.chars().all(|c| !c.is_uppercase());
+        match self {
+            Case::Upper => word.to_uppercase(),
+            Case::Title if is_lower => {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|c| c.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+            _ => word.to_string(),
+        }
+    }
+}
+
+/// The spellings to look up for a word: the word itself, then lower case forms for capitalized
+/// and all caps words, i.e. `HELLO`, `Hello` and `hello` for `HELLO`.
+pub fn case_variants(word: &str) -> Vec<String> {
+    let lower = word.to_lowercase();
+    match Case::of(word) {
+        Case::Upper => vec![word.to_string(), Case::Title.apply(&lower), lower],
+        Case::Title => vec![word.to_string(), lower],
+        Case::Lower | Case::Mixed => vec![word.to_string()],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_case_of() {
+        let cases = [
+            ("hello", Case::Lower),
+            ("42", Case::Lower),
+            ("Hello", Case::Title),
+            ("A", Case::Title),
+            ("HELLO", Case::Upper),
+            ("HELLO2", Case::Upper),
+            ("iPhone", Case::Mixed),
+        ];
+
+        for (word, expected) in cases {
+            assert_eq!(expected, Case::of(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn test_case_apply() {
+        let cases = [
+            (Case::Title, "the", "The"),
+            (Case::Title, "iPhone", "iPhone"),
+            (Case::Upper, "Paris", "PARIS"),
+            (Case::Lower, "the", "the"),
+            (Case::Mixed, "the", "the"),
+        ];
+
+        for (case, word, expected) in cases {
+            assert_eq!(expected, case.apply(word), "{case:?} {word}");
+        }
+    }
+
+    #[test]
+    fn test_case_variants() {
+        let cases = [
+            ("hello", vec!["hello"]),
+            ("Hello", vec!["Hello", "hello"]),
+            ("HELLO", vec!["HELLO", "Hello", "hello"]),
+            ("iPhone", vec!["iPhone"]),
+        ];
+
+        for (word, expected) in cases {
+            assert_eq!(expected, case_variants(word), "{word}");
+        }
+    }
+}
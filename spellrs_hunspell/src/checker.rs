@@ -0,0 +1,764 @@
+use std::collections::HashMap;
+
+use crate::{case_variants, AffixEntry, AffixKind, DicEntry, Flag, FlagMode, HunspellDictionary};
+
+/// Checks words against a Hunspell dictionary without expanding it.
+///
+/// Works like Hunspell itself: affixes are stripped from the word to find candidate roots, and
+/// words that are not found are split into compound parts.
+#[derive(Debug)]
+pub struct HunspellChecker {
+    dict: HunspellDictionary,
+    /// Root word to the indexes of its entries. A word can have more than one entry (homonyms).
+    roots: HashMap<String, Vec<usize>>,
+    prefixes: Vec<AffixItem>,
+    suffixes: Vec<AffixItem>,
+    /// Affix append string to indexes into `prefixes` or `suffixes`.
+    prefix_index: HashMap<String, Vec<usize>>,
+    suffix_index: HashMap<String, Vec<usize>>,
+    compound_rules: Vec<Vec<RuleToken>>,
+}
+
+/// A single affix entry with the attributes of its block.
+#[derive(Debug)]
+struct AffixItem {
+    flag: Flag,
+    kind: AffixKind,
+    cross_product: bool,
+    entry: AffixEntry,
+}
+
+impl AffixItem {
+    /// Reverses the affix, returning the root it was applied to.
+    fn strip_from(&self, word: &str) -> Option<String> {
+        let root = match self.kind {
+            AffixKind::Prefix => format!(
+                "{}{}",
+                self.entry.strip,
+                word.strip_prefix(self.entry.append.as_str())?
+            ),
+            AffixKind::Suffix => format!(
+                "{}{}",
+                word.strip_suffix(self.entry.append.as_str())?,
+                self.entry.strip
+            ),
+        };
+
+        match !root.is_empty() && self.entry.condition.matches(&root) {
+            true => Some(root),
+            false => None,
+        }
+    }
+}
+
+/// One way a word can be derived from a root.
+#[derive(Debug)]
+struct Analysis<'a> {
+    entry: &'a DicEntry,
+    /// Continuation flags of the applied affixes.
+    affix_flags: Vec<Flag>,
+    prefixed: bool,
+    suffixed: bool,
+    affix_count: usize,
+}
+
+impl Analysis<'_> {
+    fn has(&self, flag: Option<Flag>) -> bool {
+        self.entry.has_flag(flag) || self.has_affix_flag(flag)
+    }
+
+    fn has_affix_flag(&self, flag: Option<Flag>) -> bool {
+        flag.is_some_and(|f| self.affix_flags.contains(&f))
+    }
+
+    /// A `NEEDAFFIX` root or affix that is missing the affix it needs.
+    fn needs_affix(&self, needaffix: Option<Flag>) -> bool {
+        match self.affix_count {
+            0 => self.entry.has_flag(needaffix),
+            1 => self.has_affix_flag(needaffix),
+            _ => false,
+        }
+    }
+}
+
+/// How a word was found in the dictionary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lookup {
+    Valid,
+    /// Valid as a compound of other words.
+    Compound,
+    Forbidden,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Position {
+    First,
+    Middle,
+    Last,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Quantifier {
+    One,
+    Optional,
+    Any,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RuleToken {
+    flag: Flag,
+    quantifier: Quantifier,
+}
+
+impl HunspellChecker {
+    pub fn new(dict: HunspellDictionary) -> Self {
+        let mut roots: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, entry) in dict.dic.entries.iter().enumerate() {
+            roots.entry(entry.word.clone()).or_default().push(i);
+        }
+
+        let (prefixes, prefix_index) = index_affixes(&dict, AffixKind::Prefix);
+        let (suffixes, suffix_index) = index_affixes(&dict, AffixKind::Suffix);
+        let compound_rules = dict
+            .aff
+            .compound
+            .rules
+            .iter()
+            .filter_map(|rule| parse_compound_rule(rule, &dict.aff.flag))
+            .collect();
+
+        Self {
+            dict,
+            roots,
+            prefixes,
+            suffixes,
+            prefix_index,
+            suffix_index,
+            compound_rules,
+        }
+    }
+
+    pub fn dictionary(&self) -> &HunspellDictionary {
+        &self.dict
+    }
+
+    /// Checks if a word is spelled correctly.
+    ///
+    /// Capitalized and all caps words are also checked in lower case, so `Hello` and `HELLO`
+    /// match `hello`. The opposite is not true, `paris` does not match `Paris`.
+    pub fn check(&self, word: &str) -> bool {
+        matches!(self.find(word), Lookup::Valid | Lookup::Compound)
+    }
+
+    /// Checks if a word is explicitly forbidden with the `FORBIDDENWORD` flag.
+    pub fn is_forbidden(&self, word: &str) -> bool {
+        self.find(word) == Lookup::Forbidden
+    }
+
+    /// Looks up a word like [`HunspellChecker::check`], telling apart forbidden and unknown
+    /// words. The first of the [`case_variants`] that is found or forbidden decides.
+    pub fn find(&self, word: &str) -> Lookup {
+        if word.is_empty() {
+            return Lookup::Valid;
+        }
+
+        case_variants(word)
+            .iter()
+            .map(|variant| self.lookup(variant, false))
+            .find(|lookup| *lookup != Lookup::Unknown)
+            .unwrap_or(Lookup::Unknown)
+    }
+
+    /// Looks up a word with its exact case. When `suggesting`, words with the `NOSUGGEST` flag
+    /// are not found, neither alone nor as parts of compounds.
+    pub(crate) fn lookup(&self, word: &str, suggesting: bool) -> Lookup {
+        let aff = &self.dict.aff;
+        let analyses = self.analyze(word);
+
+        if analyses.iter().any(|a| a.has(aff.forbiddenword)) {
+            return Lookup::Forbidden;
+        }
+
+        let valid = analyses.iter().any(|a| {
+            !a.has(aff.onlyincompound)
+                && !a.needs_affix(aff.needaffix)
+                && (!suggesting || !a.has(aff.nosuggest))
+        });
+
+        if valid {
+            return Lookup::Valid;
+        }
+
+        match self.check_compound(word, suggesting) {
+            true => Lookup::Compound,
+            false => Lookup::Unknown,
+        }
+    }
+
+    fn root_entries(&self, word: &str) -> impl Iterator<Item = &DicEntry> {
+        self.roots
+            .get(word)
+            .into_iter()
+            .flatten()
+            .map(|&i| &self.dict.dic.entries[i])
+    }
+
+    /// Finds the affixes whose append string is at the start or end of `word`.
+    fn matching_affixes(&self, word: &str, kind: AffixKind) -> Vec<&AffixItem> {
+        let (items, index) = match kind {
+            AffixKind::Prefix => (&self.prefixes, &self.prefix_index),
+            AffixKind::Suffix => (&self.suffixes, &self.suffix_index),
+        };
+
+        word.char_indices()
+            .map(|(i, _)| i)
+            .chain([word.len()])
+            .filter_map(|i| match kind {
+                AffixKind::Prefix => index.get(&word[..i]),
+                AffixKind::Suffix => index.get(&word[i..]),
+            })
+            .flatten()
+            .map(|&i| &items[i])
+            .collect()
+    }
+
+    /// Finds every root and affix combination that produces `word`.
+    fn analyze(&self, word: &str) -> Vec<Analysis<'_>> {
+        let mut analyses: Vec<Analysis> = self
+            .root_entries(word)
+            .map(|entry| Analysis {
+                entry,
+                affix_flags: vec![],
+                prefixed: false,
+                suffixed: false,
+                affix_count: 0,
+            })
+            .collect();
+
+        self.analyze_suffixes(word, None, &mut analyses);
+
+        for pfx in self.matching_affixes(word, AffixKind::Prefix) {
+            let Some(root) = pfx.strip_from(word) else {
+                continue;
+            };
+
+            for entry in self.root_entries(&root) {
+                if entry.flags.contains(&pfx.flag) {
+                    analyses.push(Analysis {
+                        entry,
+                        affix_flags: pfx.entry.flags.clone(),
+                        prefixed: true,
+                        suffixed: false,
+                        affix_count: 1,
+                    });
+                }
+            }
+
+            self.analyze_suffixes(&root, Some(pfx), &mut analyses);
+        }
+
+        analyses
+    }
+
+    /// Strips one or two suffixes from `word`. When `pfx` is given, the root must also accept
+    /// the prefix, either as a cross product or through the continuation flags of the suffix.
+    fn analyze_suffixes<'a>(
+        &'a self,
+        word: &str,
+        pfx: Option<&'a AffixItem>,
+        analyses: &mut Vec<Analysis<'a>>,
+    ) {
+        let accepts_prefix = |entry: &DicEntry, sfx: &AffixItem| match pfx {
+            Some(pfx) => {
+                (entry.flags.contains(&pfx.flag) && pfx.cross_product && sfx.cross_product)
+                    || sfx.entry.flags.contains(&pfx.flag)
+            }
+            None => true,
+        };
+        let affix_flags = |flags: &[Flag]| {
+            let mut flags = flags.to_vec();
+            if let Some(pfx) = pfx {
+                flags.extend(&pfx.entry.flags);
+            }
+            flags
+        };
+
+        for sfx in self.matching_affixes(word, AffixKind::Suffix) {
+            let Some(root) = sfx.strip_from(word) else {
+                continue;
+            };
+
+            for entry in self.root_entries(&root) {
+                if entry.flags.contains(&sfx.flag) && accepts_prefix(entry, sfx) {
+                    analyses.push(Analysis {
+                        entry,
+                        affix_flags: affix_flags(&sfx.entry.flags),
+                        prefixed: pfx.is_some(),
+                        suffixed: true,
+                        affix_count: 1 + pfx.is_some() as usize,
+                    });
+                }
+            }
+
+            // `sfx` may be the outer suffix of a two-level suffix.
+            for inner in self.matching_affixes(&root, AffixKind::Suffix) {
+                if !inner.entry.flags.contains(&sfx.flag) {
+                    continue;
+                }
+                let Some(inner_root) = inner.strip_from(&root) else {
+                    continue;
+                };
+
+                for entry in self.root_entries(&inner_root) {
+                    if entry.flags.contains(&inner.flag) && accepts_prefix(entry, inner) {
+                        analyses.push(Analysis {
+                            entry,
+                            affix_flags: affix_flags(&sfx.entry.flags),
+                            prefixed: pfx.is_some(),
+                            suffixed: true,
+                            affix_count: 2 + pfx.is_some() as usize,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    fn has_compounding(&self) -> bool {
+        let compound = &self.dict.aff.compound;
+        compound.flag.is_some()
+            || compound.begin.is_some()
+            || compound.middle.is_some()
+            || compound.end.is_some()
+            || !self.compound_rules.is_empty()
+    }
+
+    fn check_compound(&self, word: &str, suggesting: bool) -> bool {
+        if !self.has_compounding() {
+            return false;
+        }
+
+        let bounds: Vec<usize> = word
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain([word.len()])
+            .collect();
+
+        self.compound_from(word, &bounds, 0, &mut vec![], suggesting)
+            || (!self.compound_rules.is_empty()
+                && self.rule_compound_from(word, &bounds, 0, &mut vec![], suggesting))
+    }
+
+    /// The valid lengths in characters of the next compound part starting at `start`.
+    fn part_ends(&self, bounds: &[usize], start: usize) -> impl Iterator<Item = usize> {
+        let min = self.dict.aff.compound.min.unwrap_or(3).max(1);
+        let len = bounds.len() - 1;
+        (start + min..=len).filter(move |&end| end == len || len - end >= min)
+    }
+
+    /// Splits `word` into parts allowed by the `COMPOUNDFLAG`, `COMPOUNDBEGIN`, `COMPOUNDMIDDLE`
+    /// and `COMPOUNDEND` flags.
+    fn compound_from<'w>(
+        &self,
+        word: &'w str,
+        bounds: &[usize],
+        start: usize,
+        parts: &mut Vec<&'w str>,
+        suggesting: bool,
+    ) -> bool {
+        let compound = &self.dict.aff.compound;
+        if compound.word_max.is_some_and(|max| parts.len() >= max) {
+            return false;
+        }
+
+        let len = bounds.len() - 1;
+        for end in self.part_ends(bounds, start) {
+            let is_last = end == len;
+            if is_last && parts.is_empty() {
+                continue;
+            }
+
+            let part = &word[bounds[start]..bounds[end]];
+            let position = match (parts.is_empty(), is_last) {
+                (true, _) => Position::First,
+                (false, true) => Position::Last,
+                (false, false) => Position::Middle,
+            };
+
+            if let Some(prev) = parts.last() {
+                if (compound.check_dup && *prev == part) || !self.check_boundary(prev, part) {
+                    continue;
+                }
+            }
+
+            if !self.is_compound_part(part, position, suggesting) {
+                continue;
+            }
+
+            if is_last {
+                return true;
+            }
+
+            parts.push(part);
+            if self.compound_from(word, bounds, end, parts, suggesting) {
+                return true;
+            }
+            parts.pop();
+        }
+
+        false
+    }
+
+    fn is_compound_part(&self, part: &str, position: Position, suggesting: bool) -> bool {
+        let aff = &self.dict.aff;
+        let compound = &aff.compound;
+
+        self.analyze(part).iter().any(|a| {
+            let position_flag = match position {
+                Position::First => compound.begin,
+                Position::Middle => compound.middle,
+                Position::Last => compound.end,
+            };
+            let permitted = |affixed: bool, allowed: bool| {
+                !affixed || allowed || a.has_affix_flag(compound.permit)
+            };
+
+            !a.has(aff.forbiddenword)
+                && (!suggesting || !a.has(aff.nosuggest))
+                && !a.needs_affix(aff.needaffix)
+                && !a.has_affix_flag(compound.forbid)
+                && permitted(a.prefixed, position == Position::First)
+                && permitted(a.suffixed, position == Position::Last)
+                && (a.has(compound.flag) || a.has(position_flag))
+        })
+    }
+
+    /// Checks the `CHECKCOMPOUNDTRIPLE` and `CHECKCOMPOUNDCASE` rules at the boundary of two parts.
+    fn check_boundary(&self, prev: &str, next: &str) -> bool {
+        let compound = &self.dict.aff.compound;
+        let mut tail = prev.chars().rev();
+        let mut head = next.chars();
+        let (a, b) = (tail.next(), tail.next());
+        let (c, d) = (head.next(), head.next());
+
+        if compound.check_triple && a == c && (a == b || c == d) {
+            return false;
+        }
+
+        let is_upper = |c: Option<char>| c.is_some_and(|c| c.is_uppercase());
+        !(compound.check_case && (is_upper(a) || is_upper(c)))
+    }
+
+    /// Splits `word` into root words whose flags match one of the `COMPOUNDRULE`s.
+    fn rule_compound_from(
+        &self,
+        word: &str,
+        bounds: &[usize],
+        start: usize,
+        parts: &mut Vec<Vec<Flag>>,
+        suggesting: bool,
+    ) -> bool {
+        let len = bounds.len() - 1;
+        if start == len {
+            return parts.len() > 1
+                && self
+                    .compound_rules
+                    .iter()
+                    .any(|rule| match_rule(rule, parts));
+        }
+
+        for end in self.part_ends(bounds, start) {
+            let part = &word[bounds[start]..bounds[end]];
+            let aff = &self.dict.aff;
+            let flags: Vec<Flag> = self
+                .root_entries(part)
+                .filter(|entry| !entry.has_flag(aff.forbiddenword))
+                .filter(|entry| !suggesting || !entry.has_flag(aff.nosuggest))
+                .flat_map(|entry| entry.flags.iter().copied())
+                .collect();
+
+            if flags.is_empty() {
+                continue;
+            }
+
+            parts.push(flags);
+            if self.rule_compound_from(word, bounds, end, parts, suggesting) {
+                return true;
+            }
+            parts.pop();
+        }
+
+        false
+    }
+}
+
+fn index_affixes(
+    dict: &HunspellDictionary,
+    kind: AffixKind,
+) -> (Vec<AffixItem>, HashMap<String, Vec<usize>>) {
+    let affixes = match kind {
+        AffixKind::Prefix => &dict.aff.prefixes,
+        AffixKind::Suffix => &dict.aff.suffixes,
+    };
+
+    let mut items = vec![];
+    let mut index: HashMap<String, Vec<usize>> = HashMap::new();
+    for affix in affixes.values() {
+        for entry in &affix.entries {
+            index
+                .entry(entry.append.clone())
+                .or_default()
+                .push(items.len());
+            items.push(AffixItem {
+                flag: affix.flag,
+                kind,
+                cross_product: affix.cross_product,
+                entry: entry.clone(),
+            });
+        }
+    }
+
+    (items, index)
+}
+
+/// Parses a `COMPOUNDRULE` like `ABC*D?`. Long and numeric flags are written in parentheses,
+/// i.e. `(aa)(bb)*`.
+fn parse_compound_rule(rule: &str, mode: &FlagMode) -> Option<Vec<RuleToken>> {
+    let mut tokens: Vec<RuleToken> = vec![];
+    let mut chars = rule.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' | '?' => {
+                let token = tokens.last_mut()?;
+                token.quantifier = match c {
+                    '*' => Quantifier::Any,
+                    _ => Quantifier::Optional,
+                };
+            }
+            '(' => {
+                let flag: String = chars.by_ref().take_while(|&c| c != ')').collect();
+                tokens.push(RuleToken {
+                    flag: mode.parse_flag(&flag).ok()?,
+                    quantifier: Quantifier::One,
+                });
+            }
+            c => tokens.push(RuleToken {
+                flag: Flag(c as u32),
+                quantifier: Quantifier::One,
+            }),
+        }
+    }
+
+    Some(tokens)
+}
+
+fn match_rule(rule: &[RuleToken], parts: &[Vec<Flag>]) -> bool {
+    let Some(token) = rule.first() else {
+        return parts.is_empty();
+    };
+    let first_matches = parts.first().is_some_and(|p| p.contains(&token.flag));
+
+    match token.quantifier {
+        Quantifier::One => first_matches && match_rule(&rule[1..], &parts[1..]),
+        Quantifier::Optional => {
+            match_rule(&rule[1..], parts) || (first_matches && match_rule(&rule[1..], &parts[1..]))
+        }
+        Quantifier::Any => {
+            match_rule(&rule[1..], parts) || (first_matches && match_rule(rule, &parts[1..]))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{parse_aff, parse_dic};
+
+    fn checker(aff: &str, dic: &str) -> HunspellChecker {
+        let aff = parse_aff(aff).unwrap();
+        let dic = parse_dic(dic, &aff).unwrap();
+        HunspellChecker::new(HunspellDictionary::new(aff, dic))
+    }
+
+    const AFF: &str = "
+FORBIDDENWORD *
+NEEDAFFIX n
+ONLYINCOMPOUND c
+
+PFX A Y 1
+PFX A   0     re         .
+
+PFX N N 1
+PFX N   0     non        .
+
+PFX U Y 1
+PFX U   0     un         .
+
+SFX D Y 2
+SFX D   y     ied        [^aeiou]y
+SFX D   0     ed         [^y]
+
+SFX S Y 1
+SFX S   0     s/X        .
+
+SFX X N 1
+SFX X   0     'x         .
+
+SFX L Y 1
+SFX L   0     ly/U       .
+
+SFX F Y 1
+SFX F   0     ful/n      .
+
+SFX M Y 1
+SFX M   0     ness       .
+";
+
+    #[test]
+    fn test_check() {
+        let dic = "9\nhello\ntry/D\nwork/AD\nform/ND\ncat/S\nkind/L\nhope/F\nfix/nD\nParis\n";
+        let checker = checker(AFF, dic);
+        let cases = [
+            ("hello", true),
+            ("Hello", true),
+            ("HELLO", true),
+            ("hEllo", false),
+            ("tried", true),
+            ("tryed", false),
+            ("reworked", true),
+            ("worked", true),
+            ("nonform", true),
+            // `N` does not allow cross products.
+            ("nonformed", false),
+            ("cats", true),
+            ("cats'x", true),
+            ("cat'x", false),
+            ("unkindly", true),
+            ("unkind", false),
+            ("hopeful", false),
+            ("fix", false),
+            ("fixed", true),
+            ("Paris", true),
+            ("PARIS", true),
+            ("paris", false),
+            ("", true),
+        ];
+
+        for (word, expected) in cases {
+            assert_eq!(expected, checker.check(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn test_forbidden() {
+        let dic = "3\nalot/*\nbad/D\nbaded/*\n";
+        let checker = checker(AFF, dic);
+        let cases = [
+            ("alot", false, true),
+            ("Alot", false, true),
+            ("bad", true, false),
+            ("baded", false, true),
+            ("unknown", false, false),
+        ];
+
+        for (word, check, forbidden) in cases {
+            assert_eq!(check, checker.check(word), "{word}");
+            assert_eq!(forbidden, checker.is_forbidden(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn test_compound_flags() {
+        let aff = format!("{AFF}\nCOMPOUNDFLAG Y\nCOMPOUNDMIN 2\nCHECKCOMPOUNDDUP\n");
+        let dic = "5\nfoo/AY\nbar/YS\nbaz\nqux/cY\nzz/*Y\n";
+        let checker = checker(&aff, dic);
+        let cases = [
+            ("foobar", true),
+            ("barfoo", true),
+            ("foobarfoo", true),
+            ("foobars", true),
+            // Prefixes are only allowed on the first part.
+            ("refoobar", true),
+            ("barrefoo", false),
+            // `baz` has no compound flag.
+            ("foobaz", false),
+            ("foofoo", false),
+            ("quxfoo", true),
+            ("qux", false),
+            ("foozz", false),
+        ];
+
+        for (word, expected) in cases {
+            assert_eq!(expected, checker.check(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn test_compound_positions() {
+        let aff =
+            "COMPOUNDBEGIN B\nCOMPOUNDMIDDLE M\nCOMPOUNDEND E\nCOMPOUNDMIN 1\nCOMPOUNDWORDMAX 3\n";
+        let dic = "4\nsun/B\nflower/BE\nbed/E\nmid/M\n";
+        let checker = checker(aff, dic);
+        let cases = [
+            ("sunflower", true),
+            ("flowerbed", true),
+            ("sunmidbed", true),
+            ("sunmidmidbed", false),
+            ("bedsun", false),
+            ("midbed", false),
+            ("sunflowerbed", false),
+        ];
+
+        for (word, expected) in cases {
+            assert_eq!(expected, checker.check(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn test_compound_min() {
+        let aff = "COMPOUNDFLAG Y\n";
+        let dic = "3\nab/Y\nabc/Y\nxyz/Y\n";
+        let checker = checker(aff, dic);
+        let cases = [("abcxyz", true), ("abxyz", false)];
+
+        for (word, expected) in cases {
+            assert_eq!(expected, checker.check(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn test_compound_rules() {
+        let aff = "ONLYINCOMPOUND c\nCOMPOUNDMIN 1\nCOMPOUNDRULE 2\nCOMPOUNDRULE n*1t\nCOMPOUNDRULE n*mp\n";
+        let dic = "14\n0/nm\n1/n1\n2/nm\n3/nm\n4/nm\n5/nm\n6/nm\n7/nm\n8/nm\n9/nm\n0th/pt\n1st/p\n1th/tc\n2nd/p\n";
+        let checker = checker(aff, dic);
+        let cases = [
+            ("1st", true),
+            ("11th", true),
+            ("21st", true),
+            ("22nd", true),
+            ("10th", true),
+            ("1th", false),
+            ("21th", false),
+            ("12nd", false),
+            ("nd", false),
+        ];
+
+        for (word, expected) in cases {
+            assert_eq!(expected, checker.check(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn test_compound_long_flag_rules() {
+        let aff = "FLAG long\nCOMPOUNDMIN 1\nCOMPOUNDRULE 1\nCOMPOUNDRULE (aa)(bb)*(cc)\n";
+        let dic = "3\nfoo/aa\nbar/bb\nbaz/cc\n";
+        let checker = checker(aff, dic);
+        let cases = [("foobaz", true), ("foobarbarbaz", true), ("barbaz", false)];
+
+        for (word, expected) in cases {
+            assert_eq!(expected, checker.check(word), "{word}");
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use crate::{AffInfo, Flag, ParseError};
+
+/// The parsed contents of a Hunspell `.dic` file.
+#[derive(Debug, Default, Clone)]
+pub struct Dic {
+    /// The approximate number of entries, taken from the first line of the file.
+    pub count: usize,
+    pub entries: Vec<DicEntry>,
+}
+
+/// A root word from the `.dic` file.
+///
+/// ```text
+/// work/ADJGSZ po:verb st:work
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DicEntry {
+    pub word: String,
+    pub flags: Vec<Flag>,
+    /// Morphological fields, i.e. `po:verb`.
+    pub morph: Vec<String>,
+}
+
+impl DicEntry {
+    pub fn has_flag(&self, flag: Option<Flag>) -> bool {
+        flag.is_some_and(|flag| self.flags.contains(&flag))
+    }
+
+    /// Returns the values of a morphological field, i.e. `st` for `st:work`.
+    pub fn morph_field<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.morph.iter().filter_map(move |field| {
+            field
+                .strip_prefix(name)
+                .and_then(|value| value.strip_prefix(':'))
+        })
+    }
+}
+
+/// Parses the contents of a Hunspell `.dic` file. The flags are decoded with the `FLAG` mode and
+/// `AF` aliases of `aff`.
+pub fn parse_dic(src: &str, aff: &AffInfo) -> Result<Dic, ParseError> {
+    let mut lines = src
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let count = match lines.next() {
+        Some((number, line)) => {
+            let first = line.split_whitespace().next().unwrap_or_default();
+            first.parse().map_err(|_| {
+                ParseError::new(number, format!("expected the word count, found `{first}`"))
+            })?
+        }
+        None => return Err(ParseError::new(1, "missing the word count")),
+    };
+
+    let mut entries = Vec::with_capacity(count);
+    for (number, line) in lines {
+        entries.push(parse_entry(line, aff).map_err(|e| ParseError::new(number, e))?);
+    }
+
+    Ok(Dic { count, entries })
+}
+
+fn parse_entry(line: &str, aff: &AffInfo) -> Result<DicEntry, String> {
+    let (word_part, morph) = split_morph(line);
+    let word_part = word_part.trim();
+
+    let (word, flags) = match find_flag_separator(word_part) {
+        Some(i) => (&word_part[..i], &word_part[i + 1..]),
+        None => (word_part, ""),
+    };
+
+    Ok(DicEntry {
+        word: word.replace("\\/", "/"),
+        flags: aff.decode_flags(flags)?,
+        morph: morph.split_whitespace().map(String::from).collect(),
+    })
+}
+
+/// Splits a line into the word with its flags and the morphological fields.
+///
+/// Like Hunspell, the fields start at the first tab, or at the first space that is followed by a
+/// field name, i.e. ` po:`. This allows words to contain spaces.
+fn split_morph(line: &str) -> (&str, &str) {
+    if let Some((word, morph)) = line.split_once('\t') {
+        return (word, morph);
+    }
+
+    let bytes = line.as_bytes();
+    let field_start = (0..bytes.len()).find(|&i| {
+        bytes[i] == b' '
+            && bytes.len() > i + 3
+            && bytes[i + 1].is_ascii_alphanumeric()
+            && bytes[i + 2].is_ascii_alphanumeric()
+            && bytes[i + 3] == b':'
+    });
+
+    match field_start {
+        Some(i) => (&line[..i], &line[i + 1..]),
+        None => (line, ""),
+    }
+}
+
+/// Finds the first `/` that is not escaped with a `\`. A leading `/` is part of the word.
+fn find_flag_separator(word: &str) -> Option<usize> {
+    let bytes = word.as_bytes();
+    (1..bytes.len()).find(|&i| bytes[i] == b'/' && bytes[i - 1] != b'\\')
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FlagMode;
+
+    fn flags(s: &str) -> Vec<Flag> {
+        s.chars().map(|c| Flag(c as u32)).collect()
+    }
+
+    #[test]
+    fn test_parse_dic() {
+        let aff = AffInfo::default();
+        let src = "5\nhello\nwork/ADG po:verb st:work\n1\\/2/N\nice cream/S\tpo:noun\nfoo bar\n";
+        let dic = parse_dic(src, &aff).unwrap();
+
+        assert_eq!(5, dic.count);
+        let expected = [
+            ("hello", "", vec![]),
+            ("work", "ADG", vec!["po:verb", "st:work"]),
+            ("1/2", "N", vec![]),
+            ("ice cream", "S", vec!["po:noun"]),
+            ("foo bar", "", vec![]),
+        ];
+
+        for (entry, (word, f, morph)) in dic.entries.iter().zip(expected) {
+            assert_eq!(word, entry.word);
+            assert_eq!(flags(f), entry.flags);
+            assert_eq!(morph, entry.morph);
+        }
+        assert_eq!(5, dic.entries.len());
+        assert_eq!(
+            vec!["work"],
+            dic.entries[1].morph_field("st").collect::<Vec<_>>()
+        );
+        assert!(dic.entries[1].has_flag(Some(Flag('D' as u32))));
+        assert!(!dic.entries[1].has_flag(None));
+    }
+
+    #[test]
+    fn test_parse_dic_flag_modes() {
+        let aff = AffInfo {
+            flag: FlagMode::Long,
+            ..Default::default()
+        };
+        let dic = parse_dic("1\nword/AaBb\n", &aff).unwrap();
+        assert_eq!(
+            FlagMode::Long.parse_flags("AaBb").unwrap(),
+            dic.entries[0].flags
+        );
+
+        let aff = AffInfo {
+            flag: FlagMode::Num,
+            ..Default::default()
+        };
+        let dic = parse_dic("1\nword/1,22\n", &aff).unwrap();
+        assert_eq!(vec![Flag(1), Flag(22)], dic.entries[0].flags);
+
+        let aff: AffInfo = "AF 1\nAF XY\n".parse().unwrap();
+        let dic = parse_dic("1\nword/1\n", &aff).unwrap();
+        assert_eq!(flags("XY"), dic.entries[0].flags);
+    }
+
+    #[test]
+    fn test_parse_dic_errors() {
+        let long = AffInfo {
+            flag: FlagMode::Long,
+            ..Default::default()
+        };
+        let cases = [
+            ("", AffInfo::default(), 1),
+            ("\nwords\n", AffInfo::default(), 2),
+            ("2\nok/Aa\nbad/ABC\n", long, 3),
+        ];
+
+        for (src, aff, line) in cases {
+            let result = parse_dic(src, &aff).unwrap_err();
+            assert_eq!(line, result.line, "{src:?} -> {result}");
+        }
+    }
+}
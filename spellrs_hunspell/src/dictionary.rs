@@ -0,0 +1,149 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{decode_aff, parse_aff, parse_dic, AffInfo, AffixCondition, Dic, Error};
+
+/// A Hunspell dictionary made up of the `.aff` and `.dic` files.
+#[derive(Debug, Default, Clone)]
+pub struct HunspellDictionary {
+    pub aff: AffInfo,
+    pub dic: Dic,
+}
+
+impl HunspellDictionary {
+    pub fn new(aff: AffInfo, dic: Dic) -> Self {
+        Self { aff, dic }
+    }
+
+    /// Loads a dictionary from a `.dic` file. The `.aff` file is expected to be in the same
+    /// directory with the same name, i.e. `en_US.dic` and `en_US.aff`.
+    pub fn from_dic_file(dic_path: impl AsRef<Path>) -> Result<Self, Error> {
+        let dic_path = dic_path.as_ref();
+        let aff_path = dic_path.with_extension("aff");
+        Self::from_files(&aff_path, dic_path)
+    }
+
+    /// Loads a dictionary from an `.aff` and `.dic` file.
+    ///
+    /// Both files are decoded according to the `SET` directive of the `.aff` file.
+    pub fn from_files(aff_path: &Path, dic_path: &Path) -> Result<Self, Error> {
+        let (aff_src, encoding) =
+            decode_aff(&read_file(aff_path)?).map_err(|message| Error::Encoding {
+                path: aff_path.to_path_buf(),
+                message,
+            })?;
+        let aff = parse_aff(&aff_src).map_err(|source| Error::Parse {
+            path: aff_path.to_path_buf(),
+            source,
+        })?;
+
+        let dic_src = encoding
+            .decode(&read_file(dic_path)?)
+            .ok_or_else(|| Error::Encoding {
+                path: dic_path.to_path_buf(),
+                message: format!("the file is not valid {}", encoding.name()),
+            })?;
+        let dic = parse_dic(&dic_src, &aff).map_err(|source| Error::Parse {
+            path: dic_path.to_path_buf(),
+            source,
+        })?;
+
+        Ok(Self { aff, dic })
+    }
+
+    /// Copies the dictionary with `fold` applied to the roots and to the strings and conditions
+    /// of the affixes, i.e. to check words regardless of case. Only the roots are copied, the
+    /// word forms are never expanded.
+    ///
+    /// A condition that is no longer valid after folding is kept as it was.
+    pub fn folded(&self, fold: impl Fn(&str) -> String) -> Self {
+        let mut dict = self.clone();
+        for entry in &mut dict.dic.entries {
+            entry.word = fold(&entry.word);
+        }
+        for affix in dict
+            .aff
+            .prefixes
+            .values_mut()
+            .chain(dict.aff.suffixes.values_mut())
+        {
+            for entry in &mut affix.entries {
+                entry.strip = fold(&entry.strip);
+                entry.append = fold(&entry.append);
+                if let Ok(condition) =
+                    AffixCondition::new(&fold(entry.condition.as_str()), affix.kind)
+                {
+                    entry.condition = condition;
+                }
+            }
+        }
+        dict
+    }
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, Error> {
+    fs::read(path).map_err(|source| Error::Io {
+        path: PathBuf::from(path),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Flag;
+
+    fn testdata(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("testdata")
+            .join(name)
+    }
+
+    #[test]
+    fn test_from_dic_file() {
+        let dict = HunspellDictionary::from_dic_file(testdata("en_sample.dic")).unwrap();
+        assert_eq!(Some("UTF-8"), dict.aff.set.as_deref());
+        assert_eq!(8, dict.dic.count);
+        assert_eq!("hello", dict.dic.entries[0].word);
+    }
+
+    #[test]
+    fn test_from_dic_file_legacy_encoding() {
+        let dict = HunspellDictionary::from_dic_file(testdata("fr_latin1.dic")).unwrap();
+        let words: Vec<String> = dict.words().collect();
+        assert_eq!(vec!["été", "café", "cafés", "garçon", "garçons"], words);
+
+        let dict = HunspellDictionary::from_dic_file(testdata("ru_koi8.dic")).unwrap();
+        let words: Vec<String> = dict.words().collect();
+        assert_eq!(vec!["кот", "коты"], words);
+    }
+
+    #[test]
+    fn test_folded() {
+        let aff = "SFX S Y 1\nSFX S 0 S [^É]\n".parse().unwrap();
+        let dic = parse_dic("2\nCAFÉ/S\nParis\n", &aff).unwrap();
+        let dict = HunspellDictionary::new(aff, dic).folded(|w| w.to_lowercase());
+
+        assert_eq!(2, dict.dic.entries.len());
+        let words: Vec<String> = dict.words().collect();
+        assert_eq!(vec!["café", "paris"], words);
+        let suffix = &dict.aff.suffixes[&Flag('S' as u32)].entries[0];
+        assert_eq!(
+            ("s", "[^é]"),
+            (suffix.append.as_str(), suffix.condition.as_str())
+        );
+    }
+
+    #[test]
+    fn test_from_dic_file_errors() {
+        let result = HunspellDictionary::from_dic_file(testdata("missing.dic")).unwrap_err();
+        assert!(matches!(result, Error::Io { ref path, .. } if path.ends_with("missing.aff")));
+
+        let result = HunspellDictionary::from_dic_file(testdata("bad_encoding.dic")).unwrap_err();
+        assert!(
+            matches!(result, Error::Encoding { ref path, .. } if path.ends_with("bad_encoding.dic"))
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use encoding_rs::{Encoding, UTF_8};
+
+/// The character encoding of a Hunspell dictionary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DicEncoding {
+    /// ISO-8859-1 maps every byte to the code point with the same value.
+    Latin1,
+    Other(&'static Encoding),
+}
+
+impl DicEncoding {
+    /// Maps the value of a `SET` directive to an encoding.
+    ///
+    /// Hunspell uses its own names, i.e. `ISO8859-15` or `microsoft-cp1251`. These are mapped to
+    /// the WHATWG labels before looking them up.
+    pub fn for_set(set: &str) -> Option<Self> {
+        let name = set.trim().to_ascii_lowercase();
+        let label = match name.as_str() {
+            "iso8859-1" | "iso-8859-1" | "latin1" => return Some(Self::Latin1),
+            "tis620-2533" => "tis-620".to_string(),
+            _ => match (
+                name.strip_prefix("iso8859-"),
+                name.strip_prefix("microsoft-cp"),
+            ) {
+                (Some(n), _) => format!("iso-8859-{n}"),
+                (_, Some(n)) => format!("windows-{n}"),
+                _ => name,
+            },
+        };
+
+        Encoding::for_label_no_replacement(label.as_bytes()).map(Self::Other)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DicEncoding::Latin1 => "ISO-8859-1",
+            DicEncoding::Other(encoding) => encoding.name(),
+        }
+    }
+
+    /// Decodes `bytes` into UTF-8. A leading byte order mark is removed.
+    ///
+    /// Returns `None` if the bytes are not valid in this encoding.
+    pub fn decode(&self, bytes: &[u8]) -> Option<String> {
+        match self {
+            DicEncoding::Latin1 => Some(bytes.iter().map(|&b| b as char).collect()),
+            DicEncoding::Other(encoding) => {
+                let bytes = match *encoding == UTF_8 {
+                    true => bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes),
+                    false => bytes,
+                };
+                encoding
+                    .decode_without_bom_handling_and_without_replacement(bytes)
+                    .map(|s| s.into_owned())
+            }
+        }
+    }
+}
+
+/// Finds the value of the `SET` directive in the raw bytes of an `.aff` file.
+pub fn find_set(aff: &[u8]) -> Option<String> {
+    aff.split(|&b| b == b'\n').find_map(|line| {
+        let line = line.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(line);
+        let mut fields = line
+            .split(|b| b.is_ascii_whitespace())
+            .filter(|f| !f.is_empty());
+
+        match fields.next() {
+            Some(b"SET") => fields
+                .next()
+                .map(|set| String::from_utf8_lossy(set).into_owned()),
+            _ => None,
+        }
+    })
+}
+
+/// Decodes the contents of an `.aff` file according to its `SET` directive.
+///
+/// Without a `SET` directive the file is read as UTF-8, falling back to ISO-8859-1, which is
+/// the Hunspell default.
+pub fn decode_aff(aff: &[u8]) -> Result<(String, DicEncoding), String> {
+    let encoding = match find_set(aff) {
+        Some(set) => {
+            DicEncoding::for_set(&set).ok_or_else(|| format!("unsupported encoding `{set}`"))?
+        }
+        None => match std::str::from_utf8(aff) {
+            Ok(_) => DicEncoding::Other(UTF_8),
+            Err(_) => DicEncoding::Latin1,
+        },
+    };
+
+    let src = encoding
+        .decode(aff)
+        .ok_or_else(|| format!("the file is not valid {}", encoding.name()))?;
+
+    Ok((src, encoding))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_for_set() {
+        let cases = [
+            ("UTF-8", Some("UTF-8")),
+            ("ISO8859-1", Some("ISO-8859-1")),
+            ("ISO8859-2", Some("ISO-8859-2")),
+            ("ISO8859-15", Some("ISO-8859-15")),
+            ("KOI8-R", Some("KOI8-R")),
+            ("KOI8-U", Some("KOI8-U")),
+            ("microsoft-cp1251", Some("windows-1251")),
+            ("TIS620-2533", Some("windows-874")),
+            ("ISCII-DEVANAGARI", None),
+        ];
+
+        for (set, expected) in cases {
+            let result = DicEncoding::for_set(set).map(|e| e.name());
+            assert_eq!(expected, result, "{set}");
+        }
+    }
+
+    #[test]
+    fn test_decode_aff() {
+        let cases: [(&[u8], &str, &str); 5] = [
+            (b"SET ISO8859-1\nTRY \xe9a\n", "ISO-8859-1", "TRY éa"),
+            (b"SET ISO8859-15\nTRY \xa4\n", "ISO-8859-15", "TRY €"),
+            (b"SET KOI8-R\nTRY \xc1\xc2\n", "KOI8-R", "TRY аб"),
+            (b"SET microsoft-cp1251\nTRY \xe0\n", "windows-1251", "TRY а"),
+            (b"\xEF\xBB\xBFSET UTF-8\nTRY \xc3\xa9\n", "UTF-8", "TRY é"),
+        ];
+
+        for (bytes, name, line) in cases {
+            let (src, encoding) = decode_aff(bytes).unwrap();
+            assert_eq!(name, encoding.name());
+            assert_eq!(Some(line), src.lines().nth(1), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_decode_aff_without_set() {
+        let (_, encoding) = decode_aff("TRY é\n".as_bytes()).unwrap();
+        assert_eq!("UTF-8", encoding.name());
+
+        let (src, encoding) = decode_aff(b"TRY \xe9\n").unwrap();
+        assert_eq!("ISO-8859-1", encoding.name());
+        assert_eq!("TRY é\n", src);
+    }
+
+    #[test]
+    fn test_decode_aff_errors() {
+        assert!(decode_aff(b"SET BOGUS-1\n").is_err());
+        assert!(decode_aff(b"SET UTF-8\nTRY \xe9\n").is_err());
+    }
+}
@@ -0,0 +1,294 @@
+use std::{collections::VecDeque, slice};
+
+use crate::{AffInfo, AffixKind, DicEntry, Flag, HunspellDictionary};
+
+/// Options used when expanding the roots of a dictionary into words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExpandOptions {
+    /// Apply the prefix and suffix transforms. When `false` only the roots are returned.
+    pub transform: bool,
+    /// Skip roots and affixed forms flagged with `FORBIDDENWORD`.
+    pub skip_forbidden: bool,
+    /// Skip roots and affixed forms flagged with `NEEDAFFIX`. Their affixed forms are kept.
+    pub skip_need_affix: bool,
+}
+
+impl Default for ExpandOptions {
+    fn default() -> Self {
+        Self {
+            transform: true,
+            skip_forbidden: true,
+            skip_need_affix: true,
+        }
+    }
+}
+
+/// An iterator over all the word forms of a dictionary. See [`HunspellDictionary::words`].
+#[derive(Debug)]
+pub struct Words<'a> {
+    aff: &'a AffInfo,
+    entries: slice::Iter<'a, DicEntry>,
+    options: ExpandOptions,
+    pending: VecDeque<String>,
+}
+
+impl Iterator for Words<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(word) = self.pending.pop_front() {
+                return Some(word);
+            }
+
+            let entry = self.entries.next()?;
+            self.pending
+                .extend(expand_entry(self.aff, entry, &self.options));
+        }
+    }
+}
+
+impl HunspellDictionary {
+    /// Iterates over every word form of the dictionary using the default [`ExpandOptions`].
+    pub fn words(&self) -> Words<'_> {
+        self.words_with_options(ExpandOptions::default())
+    }
+
+    /// Iterates over every word form of the dictionary. The roots are expanded one at a time, so
+    /// the full word list is never held in memory.
+    pub fn words_with_options(&self, options: ExpandOptions) -> Words<'_> {
+        Words {
+            aff: &self.aff,
+            entries: self.dic.entries.iter(),
+            options,
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+/// A word form with the flags that still apply to it.
+struct Form {
+    word: String,
+    /// Continuation flags of the last affix applied.
+    flags: Vec<Flag>,
+    /// All the affixes applied allow cross products.
+    cross_product: bool,
+}
+
+/// Generates all the forms of a single root.
+///
+/// - Suffixes are applied to the root. Continuation flags on a suffix entry allow a second
+///   suffix to be applied on top of the first (two-level suffixes).
+/// - Prefixes are applied to the root, and to every suffixed form when both the prefix and
+///   the suffixes allow cross products.
+/// - Prefixes listed in the continuation flags of a suffix are applied to that suffixed form.
+pub fn expand_entry(aff: &AffInfo, entry: &DicEntry, options: &ExpandOptions) -> Vec<String> {
+    let mut words = vec![];
+    let skip = |flags: &[Flag]| {
+        let has = |flag: Option<Flag>| flag.is_some_and(|f| flags.contains(&f));
+        (options.skip_forbidden && has(aff.forbiddenword))
+            || (options.skip_need_affix && has(aff.needaffix))
+    };
+
+    if options.skip_forbidden && entry.has_flag(aff.forbiddenword) {
+        return words;
+    }
+
+    if !skip(&entry.flags) {
+        words.push(entry.word.clone());
+    }
+
+    if !options.transform {
+        return words;
+    }
+
+    let suffixed = apply_suffixes(aff, entry);
+    for form in &suffixed {
+        if !skip(&form.flags) {
+            words.push(form.word.clone());
+        }
+    }
+
+    for pfx in entry.flags.iter().filter_map(|f| aff.prefixes.get(f)) {
+        for pe in &pfx.entries {
+            if let Some(word) = pe.apply(&entry.word, AffixKind::Prefix) {
+                if !skip(&pe.flags) {
+                    words.push(word);
+                }
+            }
+
+            if !pfx.cross_product {
+                continue;
+            }
+
+            for form in suffixed.iter().filter(|form| form.cross_product) {
+                if let Some(word) = pe.apply(&form.word, AffixKind::Prefix) {
+                    let forbidden = [&pe.flags, &form.flags];
+                    if !forbidden.iter().any(|f| skip_forbidden(aff, options, f)) {
+                        words.push(word);
+                    }
+                }
+            }
+        }
+    }
+
+    for form in &suffixed {
+        for pfx in form.flags.iter().filter_map(|f| aff.prefixes.get(f)) {
+            for pe in &pfx.entries {
+                if let Some(word) = pe.apply(&form.word, AffixKind::Prefix) {
+                    let forbidden = [&pe.flags, &form.flags];
+                    if !forbidden.iter().any(|f| skip_forbidden(aff, options, f)) {
+                        words.push(word);
+                    }
+                }
+            }
+        }
+    }
+
+    words
+}
+
+fn skip_forbidden(aff: &AffInfo, options: &ExpandOptions, flags: &[Flag]) -> bool {
+    options.skip_forbidden && aff.forbiddenword.is_some_and(|f| flags.contains(&f))
+}
+
+fn apply_suffixes(aff: &AffInfo, entry: &DicEntry) -> Vec<Form> {
+    let mut forms = vec![];
+
+    for sfx in entry.flags.iter().filter_map(|f| aff.suffixes.get(f)) {
+        for se in &sfx.entries {
+            let Some(word) = se.apply(&entry.word, AffixKind::Suffix) else {
+                continue;
+            };
+
+            let mut inner = vec![];
+            for sfx2 in se.flags.iter().filter_map(|f| aff.suffixes.get(f)) {
+                for se2 in &sfx2.entries {
+                    if let Some(word) = se2.apply(&word, AffixKind::Suffix) {
+                        inner.push(Form {
+                            word,
+                            flags: se2.flags.clone(),
+                            cross_product: sfx.cross_product && sfx2.cross_product,
+                        });
+                    }
+                }
+            }
+
+            forms.push(Form {
+                word,
+                flags: se.flags.clone(),
+                cross_product: sfx.cross_product,
+            });
+            forms.extend(inner);
+        }
+    }
+
+    forms
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parse_dic;
+
+    const AFF: &str = "
+NEEDAFFIX n
+FORBIDDENWORD *
+
+PFX A Y 1
+PFX A   0     re         .
+
+PFX N N 1
+PFX N   0     non        .
+
+PFX U Y 1
+PFX U   0     un         .
+
+SFX D Y 2
+SFX D   y     ied        [^aeiou]y
+SFX D   0     ed         [^y]
+
+SFX S Y 1
+SFX S   0     s/X        .
+
+SFX X N 1
+SFX X   0     'x         .
+
+SFX L Y 1
+SFX L   0     ly/U       .
+
+SFX F Y 1
+SFX F   0     ful/n      .
+
+SFX B Y 1
+SFX B   0     ish/*      .
+";
+
+    fn expand(dic: &str, options: ExpandOptions) -> Vec<String> {
+        let aff: AffInfo = AFF.parse().unwrap();
+        let dic = parse_dic(dic, &aff).unwrap();
+        let dict = HunspellDictionary::new(aff, dic);
+        dict.words_with_options(options).collect()
+    }
+
+    #[test]
+    fn test_words() {
+        let cases = [
+            ("1\nhello", vec!["hello"]),
+            ("1\ntry/D", vec!["try", "tried"]),
+            ("1\nwork/AD", vec!["work", "worked", "rework", "reworked"]),
+            // `N` does not allow cross products.
+            ("1\nform/ND", vec!["form", "formed", "nonform"]),
+            // `X` is a two-level suffix enabled by `S`.
+            ("1\ncat/S", vec!["cat", "cats", "cats'x"]),
+            // `L` enables the prefix `U`.
+            ("1\nkind/L", vec!["kind", "kindly", "unkindly"]),
+            ("2\nfoo\nbar", vec!["foo", "bar"]),
+        ];
+
+        for (dic, expected) in cases {
+            let result = expand(dic, ExpandOptions::default());
+            assert_eq!(expected, result, "{dic:?}");
+        }
+    }
+
+    #[test]
+    fn test_words_options() {
+        let all = ExpandOptions {
+            transform: true,
+            skip_forbidden: false,
+            skip_need_affix: false,
+        };
+        let roots = ExpandOptions {
+            transform: false,
+            ..Default::default()
+        };
+
+        let cases = [
+            ("1\nwork/AD", roots, vec!["work"]),
+            ("1\nbad/*D", ExpandOptions::default(), vec![]),
+            ("1\nbad/*D", all, vec!["bad", "baded"]),
+            ("1\nfix/nD", ExpandOptions::default(), vec!["fixed"]),
+            ("1\nfix/nD", all, vec!["fix", "fixed"]),
+            // `F` entries are flagged with `NEEDAFFIX`, `B` entries with `FORBIDDENWORD`.
+            ("1\nhope/FB", ExpandOptions::default(), vec!["hope"]),
+            ("1\nhope/FB", all, vec!["hope", "hopeful", "hopeish"]),
+            // A forbidden suffixed form stays forbidden with a cross product prefix.
+            (
+                "1\nhope/AB",
+                ExpandOptions::default(),
+                vec!["hope", "rehope"],
+            ),
+            (
+                "1\nhope/AB",
+                all,
+                vec!["hope", "hopeish", "rehope", "rehopeish"],
+            ),
+        ];
+
+        for (dic, options, expected) in cases {
+            let result = expand(dic, options);
+            assert_eq!(expected, result, "{dic:?} {options:?}");
+        }
+    }
+}
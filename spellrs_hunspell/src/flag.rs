@@ -0,0 +1,147 @@
+use std::str::FromStr;
+
+/// A single affix or attribute flag.
+///
+/// Flags are stored as numbers regardless of the `FLAG` mode of the dictionary:
+/// - `short` and `UTF-8` flags use the code point of the character.
+/// - `long` flags pack both characters, `(first << 16) | second`.
+/// - `num` flags use the decimal value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Flag(pub u32);
+
+/// How flags are written in the `.aff` and `.dic` files. Set by the `FLAG` directive.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FlagMode {
+    /// One character per flag. This is the Hunspell default.
+    #[default]
+    Short,
+    /// Two characters per flag.
+    Long,
+    /// Comma separated decimal numbers.
+    Num,
+    /// One Unicode character per flag.
+    Utf8,
+}
+
+impl FromStr for FlagMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "short" => Ok(Self::Short),
+            "long" => Ok(Self::Long),
+            "num" => Ok(Self::Num),
+            "utf-8" | "utf8" => Ok(Self::Utf8),
+            _ => Err(format!("unknown FLAG type `{s}`")),
+        }
+    }
+}
+
+impl FlagMode {
+    /// Parses a single flag, e.g. the argument of `NOSUGGEST`.
+    pub fn parse_flag(&self, s: &str) -> Result<Flag, String> {
+        let flags = self.parse_flags(s)?;
+        match flags.as_slice() {
+            [flag] => Ok(*flag),
+            _ => Err(format!("expected a single flag, found `{s}`")),
+        }
+    }
+
+    /// Parses a flag set, e.g. the part after the `/` in a `.dic` entry.
+    pub fn parse_flags(&self, s: &str) -> Result<Vec<Flag>, String> {
+        match self {
+            FlagMode::Short | FlagMode::Utf8 => Ok(s.chars().map(|c| Flag(c as u32)).collect()),
+            FlagMode::Long => {
+                let chars: Vec<char> = s.chars().collect();
+                if !chars.len().is_multiple_of(2) {
+                    return Err(format!("long flags must have an even length, found `{s}`"));
+                }
+
+                chars
+                    .chunks(2)
+                    .map(|pair| {
+                        let (a, b) = (pair[0] as u32, pair[1] as u32);
+                        match a <= 0xFFFF && b <= 0xFFFF {
+                            true => Ok(Flag((a << 16) | b)),
+                            false => Err(format!("invalid long flag `{}{}`", pair[0], pair[1])),
+                        }
+                    })
+                    .collect()
+            }
+            FlagMode::Num => s
+                .split(',')
+                .filter(|n| !n.is_empty())
+                .map(|n| {
+                    n.trim()
+                        .parse::<u16>()
+                        .map(|n| Flag(n as u32))
+                        .map_err(|_| format!("invalid numeric flag `{n}`"))
+                })
+                .collect(),
+        }
+    }
+
+    /// Formats a flag the way it is written in the dictionary files.
+    pub fn format_flag(&self, flag: Flag) -> String {
+        let to_char = |n: u32| char::from_u32(n).unwrap_or(char::REPLACEMENT_CHARACTER);
+        match self {
+            FlagMode::Short | FlagMode::Utf8 => to_char(flag.0).to_string(),
+            FlagMode::Long => [to_char(flag.0 >> 16), to_char(flag.0 & 0xFFFF)]
+                .iter()
+                .collect(),
+            FlagMode::Num => flag.0.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_flags() {
+        let cases = [
+            (
+                FlagMode::Short,
+                "ABc",
+                vec!['A' as u32, 'B' as u32, 'c' as u32],
+            ),
+            (FlagMode::Utf8, "Ü€", vec!['Ü' as u32, '€' as u32]),
+            (
+                FlagMode::Long,
+                "AaBb",
+                vec![
+                    ('A' as u32) << 16 | 'a' as u32,
+                    ('B' as u32) << 16 | 'b' as u32,
+                ],
+            ),
+            (FlagMode::Num, "1,200,65000", vec![1, 200, 65000]),
+        ];
+
+        for (mode, input, expected) in cases {
+            let result = mode.parse_flags(input).unwrap();
+            let expected: Vec<Flag> = expected.into_iter().map(Flag).collect();
+            assert_eq!(expected, result, "{mode:?} {input}");
+        }
+    }
+
+    #[test]
+    fn test_parse_flags_errors() {
+        assert!(FlagMode::Long.parse_flags("ABC").is_err());
+        assert!(FlagMode::Num.parse_flags("1,x").is_err());
+        assert!(FlagMode::Num.parse_flags("70000").is_err());
+        assert!(FlagMode::Short.parse_flag("AB").is_err());
+    }
+
+    #[test]
+    fn test_format_flag() {
+        for (mode, input) in [
+            (FlagMode::Short, "A"),
+            (FlagMode::Long, "Zx"),
+            (FlagMode::Num, "123"),
+        ] {
+            let flag = mode.parse_flag(input).unwrap();
+            assert_eq!(input, mode.format_flag(flag));
+        }
+    }
+}
@@ -0,0 +1,22 @@
+mod aff;
+mod affix;
+mod case;
+mod checker;
+mod dic;
+mod dictionary;
+mod encoding;
+mod error;
+mod expand;
+mod flag;
+mod suggest;
+
+pub use aff::{parse_aff, AffInfo, CompoundInfo, Directive};
+pub use affix::{Affix, AffixCondition, AffixEntry, AffixKind};
+pub use case::{case_variants, Case};
+pub use checker::{HunspellChecker, Lookup};
+pub use dic::{parse_dic, Dic, DicEntry};
+pub use dictionary::HunspellDictionary;
+pub use encoding::{decode_aff, find_set, DicEncoding};
+pub use error::{Error, ParseError};
+pub use expand::{expand_entry, ExpandOptions, Words};
+pub use flag::{Flag, FlagMode};
@@ -0,0 +1,582 @@
+use std::time::Instant;
+
+use crate::{expand_entry, Case, ExpandOptions, HunspellChecker, Lookup};
+
+/// The maximum number of suggestions returned by [`HunspellChecker::suggest`].
+const MAX_SUGGESTIONS: usize = 15;
+/// Used when the `.aff` file has no `MAXCPDSUGS`.
+const DEFAULT_MAX_CPD_SUGS: usize = 3;
+/// Used when the `.aff` file has no `MAXNGRAMSUGS`.
+const DEFAULT_MAX_NGRAM_SUGS: usize = 4;
+/// The number of most similar roots that are expanded when looking for n-gram suggestions.
+const MAX_NGRAM_ROOTS: usize = 100;
+/// The lowest [`ngram_similarity`] of an n-gram suggestion.
+const MIN_NGRAM_SIMILARITY: f64 = 0.5;
+/// Limits the number of `MAP` candidates, which grows exponentially with the word length.
+const MAX_MAP_CANDIDATES: usize = 1000;
+/// The farthest apart two characters can be to be swapped or moved.
+const MAX_SWAP_DISTANCE: usize = 4;
+
+/// Collects the suggestions that are words in the dictionary.
+struct Suggestions<'a> {
+    checker: &'a HunspellChecker,
+    word: &'a str,
+    words: Vec<String>,
+    compounds: usize,
+    max_compounds: usize,
+    deadline: Option<Instant>,
+}
+
+impl Suggestions<'_> {
+    /// Whether no more suggestions should be looked for, because there are enough or the
+    /// deadline has passed.
+    fn is_done(&self) -> bool {
+        self.words.len() >= MAX_SUGGESTIONS || self.deadline.is_some_and(|d| Instant::now() >= d)
+    }
+
+    /// Adds `candidate` if it is a word that can be suggested. Lower case candidates are also
+    /// tried capitalized, so `paris` can suggest `Paris`.
+    fn add(&mut self, candidate: &str) {
+        if candidate.is_empty() || candidate == self.word || self.is_done() {
+            return;
+        }
+
+        let found = match candidate.contains(' ') {
+            true => candidate
+                .split(' ')
+                .all(|part| self.checker.lookup(part, true) == Lookup::Valid)
+                .then(|| (candidate.to_string(), Lookup::Valid)),
+            false => [candidate.to_string(), Case::Title.apply(candidate)]
+                .into_iter()
+                .map(|c| {
+                    let lookup = self.checker.lookup(&c, true);
+                    (c, lookup)
+                })
+                .find(|(_, lookup)| matches!(lookup, Lookup::Valid | Lookup::Compound)),
+        };
+
+        let Some((word, lookup)) = found else {
+            return;
+        };
+        if self.words.contains(&word) {
+            return;
+        }
+
+        if lookup == Lookup::Compound {
+            if self.compounds >= self.max_compounds {
+                return;
+            }
+            self.compounds += 1;
+        }
+        self.words.push(word);
+    }
+}
+
+impl HunspellChecker {
+    /// Suggests corrections for a misspelled word, best first.
+    ///
+    /// Candidates are made by applying the `REP` and `MAP` tables, replacing characters with
+    /// their `KEY` neighbors, and inserting, removing, swapping or replacing `TRY` characters.
+    /// When the `REP` and `MAP` tables don't find anything, similar words are found by comparing
+    /// n-grams. Words with the `NOSUGGEST` flag are never suggested.
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        self.suggest_with_deadline(word, None)
+    }
+
+    /// Like [`HunspellChecker::suggest`], but stops looking for more suggestions once
+    /// `deadline` has passed and returns the ones found so far.
+    pub fn suggest_until(&self, word: &str, deadline: Instant) -> Vec<String> {
+        self.suggest_with_deadline(word, Some(deadline))
+    }
+
+    fn suggest_with_deadline(&self, word: &str, deadline: Option<Instant>) -> Vec<String> {
+        let aff = &self.dictionary().aff;
+        let case = Case::of(word);
+        let base = match case {
+            Case::Title | Case::Upper => word.to_lowercase(),
+            Case::Lower | Case::Mixed => word.to_string(),
+        };
+        let mut sugs = Suggestions {
+            checker: self,
+            word: &base,
+            words: vec![],
+            compounds: 0,
+            max_compounds: aff.max_cpd_sugs.unwrap_or(DEFAULT_MAX_CPD_SUGS),
+            deadline,
+        };
+
+        // Wrong capitalization, i.e. `iphone` or `hEllo`.
+        sugs.add(&word.to_lowercase());
+        sugs.add(&word.to_uppercase());
+        for candidate in rep_candidates(&base, &aff.rep) {
+            sugs.add(&candidate);
+        }
+        for candidate in map_candidates(&base, &aff.map) {
+            sugs.add(&candidate);
+        }
+        let found_good = !sugs.words.is_empty();
+        let edits = sugs.words.len();
+
+        let chars: Vec<char> = base.chars().collect();
+        let try_chars: Vec<char> = aff.try_chars.as_deref().unwrap_or("").chars().collect();
+        for candidate in swap_candidates(&chars)
+            .chain(key_candidates(&chars, &aff.key))
+            .chain(delete_candidates(&chars))
+            .chain(insert_candidates(&chars, &try_chars))
+            .chain(move_candidates(&chars))
+            .chain(replace_candidates(&chars, &try_chars))
+            .chain(doubled_candidates(&chars))
+        {
+            if sugs.is_done() {
+                break;
+            }
+            sugs.add(&candidate);
+        }
+        sugs.words[edits..]
+            .sort_by(|a, b| ngram_similarity(&base, b).total_cmp(&ngram_similarity(&base, a)));
+
+        if !aff.no_split_sugs && !sugs.is_done() {
+            for i in 1..chars.len() {
+                let (left, right) = chars.split_at(i);
+                sugs.add(&format!(
+                    "{} {}",
+                    left.iter().collect::<String>(),
+                    right.iter().collect::<String>()
+                ));
+            }
+        }
+
+        if !found_good && !sugs.is_done() {
+            let max = aff.max_ngram_sugs.unwrap_or(DEFAULT_MAX_NGRAM_SUGS);
+            for candidate in self.ngram_suggestions(&base, max) {
+                sugs.add(&candidate);
+            }
+        }
+
+        let mut words: Vec<String> = vec![];
+        for sug in sugs.words {
+            let sug = match sug.chars().any(char::is_uppercase) {
+                true => sug,
+                false => case.apply(&sug),
+            };
+            if !words.contains(&sug) {
+                words.push(sug);
+            }
+        }
+        words
+    }
+
+    /// Finds the dictionary words that share the most n-grams with `word`.
+    ///
+    /// The roots are compared first, then the most similar roots are expanded and their forms
+    /// are compared.
+    fn ngram_suggestions(&self, word: &str, max: usize) -> Vec<String> {
+        if max == 0 {
+            return vec![];
+        }
+
+        let dict = self.dictionary();
+        let aff = &dict.aff;
+        let mut roots: Vec<(f64, usize)> = dict
+            .dic
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| {
+                !e.has_flag(aff.forbiddenword)
+                    && !e.has_flag(aff.nosuggest)
+                    && !e.has_flag(aff.onlyincompound)
+            })
+            .map(|(i, e)| (ngram_similarity(word, &e.word.to_lowercase()), i))
+            .collect();
+        roots.sort_by(|a, b| b.0.total_cmp(&a.0));
+        roots.truncate(MAX_NGRAM_ROOTS);
+
+        let options = ExpandOptions::default();
+        let mut forms: Vec<(f64, String)> = roots
+            .iter()
+            .flat_map(|&(_, i)| expand_entry(aff, &dict.dic.entries[i], &options))
+            .map(|form| (ngram_similarity(word, &form.to_lowercase()), form))
+            .filter(|(score, _)| *score >= MIN_NGRAM_SIMILARITY)
+            .collect();
+        forms.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let mut words: Vec<String> = vec![];
+        for (_, form) in forms {
+            if words.len() >= max {
+                break;
+            }
+            if !words.contains(&form) && self.lookup(&form, true) == Lookup::Valid {
+                words.push(form);
+            }
+        }
+        words
+    }
+}
+
+/// Applies each `REP` pattern at every position it matches. A leading `^` or trailing `$`
+/// anchors the pattern to the start or end of the word.
+fn rep_candidates(word: &str, rep: &[(String, String)]) -> Vec<String> {
+    let mut candidates = vec![];
+
+    for (from, to) in rep {
+        let (start, from) = match from.strip_prefix('^') {
+            Some(from) => (true, from),
+            None => (false, from.as_str()),
+        };
+        let (end, from) = match from.strip_suffix('$') {
+            Some(from) => (true, from),
+            None => (false, from),
+        };
+        if from.is_empty() {
+            continue;
+        }
+
+        for (i, _) in word.match_indices(from) {
+            if (start && i != 0) || (end && i + from.len() != word.len()) {
+                continue;
+            }
+            candidates.push(format!("{}{to}{}", &word[..i], &word[i + from.len()..]));
+        }
+    }
+
+    candidates
+}
+
+/// Replaces characters with the other members of their `MAP` group, in every combination.
+fn map_candidates(word: &str, map: &[Vec<String>]) -> Vec<String> {
+    let mut candidates = vec![];
+    if !map.is_empty() {
+        map_related(word, String::new(), false, map, &mut candidates);
+    }
+    candidates
+}
+
+fn map_related(
+    rest: &str,
+    done: String,
+    changed: bool,
+    map: &[Vec<String>],
+    candidates: &mut Vec<String>,
+) {
+    if candidates.len() >= MAX_MAP_CANDIDATES {
+        return;
+    }
+    let Some(c) = rest.chars().next() else {
+        if changed {
+            candidates.push(done);
+        }
+        return;
+    };
+
+    for group in map {
+        for item in group.iter().filter(|item| rest.starts_with(item.as_str())) {
+            for other in group.iter().filter(|other| *other != item) {
+                map_related(
+                    &rest[item.len()..],
+                    format!("{done}{other}"),
+                    true,
+                    map,
+                    candidates,
+                );
+            }
+        }
+    }
+
+    map_related(
+        &rest[c.len_utf8()..],
+        format!("{done}{c}"),
+        changed,
+        map,
+        candidates,
+    );
+}
+
+/// Swaps two characters, neighbors first.
+fn swap_candidates(chars: &[char]) -> impl Iterator<Item = String> + '_ {
+    (1..=MAX_SWAP_DISTANCE).flat_map(move |distance| {
+        (0..chars.len().saturating_sub(distance)).filter_map(move |i| {
+            let mut swapped = chars.to_vec();
+            swapped.swap(i, i + distance);
+            (swapped[i] != swapped[i + distance]).then(|| swapped.into_iter().collect())
+        })
+    })
+}
+
+/// Replaces each character with its neighbors on the keyboard.
+fn key_candidates<'a>(chars: &'a [char], key: &'a [String]) -> impl Iterator<Item = String> + 'a {
+    (0..chars.len()).flat_map(move |i| {
+        key.iter()
+            .flat_map(move |row| {
+                let row: Vec<char> = row.chars().collect();
+                let neighbors: Vec<char> = row
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &c)| c == chars[i])
+                    .flat_map(|(j, _)| [j.checked_sub(1), Some(j + 1)])
+                    .flatten()
+                    .filter_map(|j| row.get(j).copied())
+                    .collect();
+                neighbors
+            })
+            .map(move |c| replace_at(chars, i, c))
+    })
+}
+
+/// Removes one character.
+fn delete_candidates(chars: &[char]) -> impl Iterator<Item = String> + '_ {
+    (0..chars.len()).map(move |i| chars[..i].iter().chain(&chars[i + 1..]).collect::<String>())
+}
+
+/// Inserts a `TRY` character at every position.
+fn insert_candidates<'a>(
+    chars: &'a [char],
+    try_chars: &'a [char],
+) -> impl Iterator<Item = String> + 'a {
+    try_chars.iter().flat_map(move |&c| {
+        (0..=chars.len()).map(move |i| {
+            chars[..i]
+                .iter()
+                .chain([&c])
+                .chain(&chars[i..])
+                .collect::<String>()
+        })
+    })
+}
+
+/// Moves one character a few places to the left or right.
+fn move_candidates(chars: &[char]) -> impl Iterator<Item = String> + '_ {
+    (0..chars.len()).flat_map(move |from| {
+        (2..=MAX_SWAP_DISTANCE)
+            .flat_map(move |distance| [from.checked_sub(distance), Some(from + distance)])
+            .flatten()
+            .filter(|&to| to < chars.len())
+            .map(move |to| {
+                let mut moved = chars.to_vec();
+                let c = moved.remove(from);
+                moved.insert(to, c);
+                moved.into_iter().collect()
+            })
+    })
+}
+
+/// Replaces each character with the `TRY` characters.
+fn replace_candidates<'a>(
+    chars: &'a [char],
+    try_chars: &'a [char],
+) -> impl Iterator<Item = String> + 'a {
+    try_chars.iter().flat_map(move |&c| {
+        (0..chars.len())
+            .filter(move |&i| chars[i] != c)
+            .map(move |i| replace_at(chars, i, c))
+    })
+}
+
+/// Removes a repeated pair of characters, i.e. `vacacation`.
+fn doubled_candidates(chars: &[char]) -> impl Iterator<Item = String> + '_ {
+    (0..chars.len().saturating_sub(3))
+        .filter(|&i| chars[i..i + 2] == chars[i + 2..i + 4])
+        .map(move |i| chars[..i].iter().chain(&chars[i + 2..]).collect())
+}
+
+fn replace_at(chars: &[char], i: usize, c: char) -> String {
+    chars
+        .iter()
+        .enumerate()
+        .map(|(j, &old)| match i == j {
+            true => c,
+            false => old,
+        })
+        .collect()
+}
+
+/// Compares two words by the character n-grams (of up to three characters) they have in
+/// common. Returns a value from `0` (nothing in common) to `1` (equal).
+fn ngram_similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut common = 0;
+    let mut total = 0;
+
+    for n in 1..=3 {
+        let a_grams: Vec<&[char]> = a.windows(n).collect();
+        let mut b_grams: Vec<&[char]> = b.windows(n).collect();
+        total += a_grams.len() + b_grams.len();
+
+        for gram in a_grams {
+            if let Some(i) = b_grams.iter().position(|g| *g == gram) {
+                b_grams.swap_remove(i);
+                common += 2;
+            }
+        }
+    }
+
+    match total {
+        0 => 0.0,
+        _ => common as f64 / total as f64,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{parse_aff, parse_dic, HunspellDictionary};
+    use std::time::Duration;
+
+    fn checker(aff: &str, dic: &str) -> HunspellChecker {
+        let aff = parse_aff(aff).unwrap();
+        let dic = parse_dic(dic, &aff).unwrap();
+        HunspellChecker::new(HunspellDictionary::new(aff, dic))
+    }
+
+    const AFF: &str = "
+TRY esianrtolcdugmphbyfvkwz
+KEY qwertyuiop|asdfghjkl|zxcvbnm
+NOSUGGEST !
+FORBIDDENWORD *
+COMPOUNDFLAG Y
+
+SFX S Y 1
+SFX S   0     s          .
+
+REP 2
+REP f ph
+REP ^alot$ a_lot
+
+MAP 1
+MAP uü
+";
+
+    const DIC: &str = "15
+hello
+help
+phone/S
+a
+lot
+Paris
+über
+the
+cat
+damn/!
+dam
+alot/*
+sun/Y
+flower/Y
+moon/Y!
+";
+
+    #[test]
+    fn test_suggest() {
+        let checker = checker(AFF, DIC);
+        let cases = [
+            ("helo", vec!["hello", "help"]),
+            ("hlelo", vec!["hello"]),
+            ("fones", vec!["phones"]),
+            ("alot", vec!["a lot"]),
+            ("uber", vec!["über"]),
+            ("paris", vec!["Paris"]),
+            ("teh", vec!["the"]),
+            ("Teh", vec!["The"]),
+            ("TEH", vec!["THE"]),
+            ("thecat", vec!["the cat"]),
+            ("hellp", vec!["help", "hello"]),
+        ];
+
+        for (word, expected) in cases {
+            let sugs = checker.suggest(word);
+            for sug in &expected {
+                assert!(sugs.contains(&sug.to_string()), "{word}: {sugs:?}");
+            }
+            assert_eq!(expected[0], sugs[0], "{word}: {sugs:?}");
+        }
+    }
+
+    #[test]
+    fn test_suggest_nosuggest() {
+        let checker = checker(AFF, DIC);
+        assert!(checker.check("damn"));
+
+        let sugs = checker.suggest("damm");
+        assert!(!sugs.contains(&"damn".to_string()), "{sugs:?}");
+        assert!(sugs.contains(&"dam".to_string()), "{sugs:?}");
+
+        assert!(!checker.suggest("alott").contains(&"alot".to_string()));
+
+        // Compounds with a `NOSUGGEST` part are valid, but not suggested.
+        assert!(checker.check("sunmoon"));
+        let sugs = checker.suggest("sunmon");
+        assert!(!sugs.contains(&"sunmoon".to_string()), "{sugs:?}");
+        assert!(checker
+            .suggest("sunflowr")
+            .contains(&"sunflower".to_string()));
+    }
+
+    #[test]
+    fn test_suggest_limits() {
+        let aff = format!("{AFF}\nMAXCPDSUGS 0\nMAXNGRAMSUGS 1\nNOSPLITSUGS\n");
+        let checker = checker(&aff, DIC);
+        assert!(checker.check("sunflower"));
+        assert!(!checker
+            .suggest("sunflowr")
+            .contains(&"sunflower".to_string()));
+        assert!(!checker.suggest("thecat").contains(&"the cat".to_string()));
+        assert_eq!(vec!["phones"], checker.suggest("phonnnes"));
+
+        let checker = self::checker(AFF, DIC);
+        assert_eq!("sunflower", checker.suggest("sunflowr")[0]);
+    }
+
+    #[test]
+    fn test_suggest_until() {
+        let checker = checker(AFF, DIC);
+        let later = Instant::now() + Duration::from_secs(60);
+        assert_eq!(
+            checker.suggest("helo"),
+            checker.suggest_until("helo", later)
+        );
+        assert!(checker.suggest_until("helo", Instant::now()).is_empty());
+    }
+
+    #[test]
+    fn test_rep_candidates() {
+        let rep = [
+            ("f".to_string(), "ph".to_string()),
+            ("^a".to_string(), "e".to_string()),
+            ("s$".to_string(), "z".to_string()),
+        ];
+        let cases = [
+            ("fifa", vec!["phifa", "fipha"]),
+            ("aaa", vec!["eaa"]),
+            ("ss", vec!["sz"]),
+        ];
+
+        for (word, expected) in cases {
+            assert_eq!(expected, rep_candidates(word, &rep), "{word}");
+        }
+    }
+
+    #[test]
+    fn test_map_candidates() {
+        let map = vec![
+            vec!["a".to_string(), "á".to_string()],
+            vec!["ß".to_string(), "ss".to_string()],
+        ];
+        let cases = [
+            ("ab", vec!["áb"]),
+            ("aa", vec!["áá", "áa", "aá"]),
+            ("mass", vec!["máß", "máss", "maß"]),
+            ("xyz", vec![]),
+        ];
+
+        for (word, expected) in cases {
+            assert_eq!(expected, map_candidates(word, &map), "{word}");
+        }
+    }
+
+    #[test]
+    fn test_ngram_similarity() {
+        assert_eq!(1.0, ngram_similarity("hello", "hello"));
+        assert_eq!(0.0, ngram_similarity("abc", "xyz"));
+        assert!(ngram_similarity("helo", "hello") > ngram_similarity("helo", "yellow"));
+    }
+}
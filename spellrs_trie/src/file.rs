@@ -0,0 +1,112 @@
+use std::{
+    fs,
+    io::{self, Read, Write},
+    path::Path,
+};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+
+use crate::{parse_trie, parse_word_list, serialize_trie, Error, ExportOptions, Trie};
+
+/// The first bytes of a gzip file.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+impl Trie {
+    /// Loads a `.trie` file. Files compressed with gzip, i.e. `.trie.gz`, are decompressed.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        parse_trie(&read_text(path)?).map_err(|source| Error::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Loads a cspell word list, i.e. `words.txt` or `words.txt.gz`. See [`parse_word_list`].
+    pub fn from_word_list_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Ok(Trie::from_words(parse_word_list(&read_text(
+            path.as_ref(),
+        )?)))
+    }
+
+    /// Writes a `.trie` file. The file is compressed with gzip when the path ends with `.gz`.
+    pub fn write_file(&self, path: impl AsRef<Path>, options: &ExportOptions) -> Result<(), Error> {
+        let path = path.as_ref();
+        let src = serialize_trie(self, options);
+        let result = match path.extension().is_some_and(|ext| ext == "gz") {
+            true => fs::File::create(path).and_then(|file| {
+                let mut encoder = GzEncoder::new(file, Compression::default());
+                encoder.write_all(src.as_bytes())?;
+                encoder.finish().map(|_| ())
+            }),
+            false => fs::write(path, src),
+        };
+
+        result.map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+/// Reads a text file, decompressing it if it is compressed with gzip.
+fn read_text(path: &Path) -> Result<String, Error> {
+    let io_error = |source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    let bytes = fs::read(path).map_err(io_error)?;
+    match bytes.starts_with(&GZIP_MAGIC) {
+        true => {
+            let mut src = String::new();
+            GzDecoder::new(bytes.as_slice())
+                .read_to_string(&mut src)
+                .map_err(io_error)?;
+            Ok(src)
+        }
+        false => String::from_utf8(bytes)
+            .map_err(|e| io_error(io::Error::new(io::ErrorKind::InvalidData, e))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_read_write_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let trie = Trie::from_words(["hello", "help", "world"]);
+
+        for name in ["words.trie", "words.trie.gz"] {
+            let path = dir.path().join(name);
+            trie.write_file(&path, &ExportOptions::default()).unwrap();
+            assert_eq!(trie, Trie::from_file(&path).unwrap(), "{name}");
+        }
+
+        let compressed = fs::read(dir.path().join("words.trie.gz")).unwrap();
+        assert!(compressed.starts_with(&GZIP_MAGIC));
+
+        let missing = Trie::from_file(dir.path().join("missing.trie"));
+        assert!(matches!(missing, Err(Error::Io { .. })));
+
+        let path = dir.path().join("bad.trie");
+        fs::write(&path, "TrieVersion: 3\n__DATA__\n#0;").unwrap();
+        let message = Trie::from_file(&path).unwrap_err().to_string();
+        assert!(message.ends_with("bad.trie:3: reference without a character"));
+    }
+
+    #[test]
+    fn test_word_list_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("words.txt.gz");
+        let mut encoder = GzEncoder::new(fs::File::create(&path).unwrap(), Compression::default());
+        encoder
+            .write_all(b"# Project words\nspellrs\nRust\n!alot\n")
+            .unwrap();
+        encoder.finish().unwrap();
+
+        let trie = Trie::from_word_list_file(&path).unwrap();
+        assert_eq!(vec!["!alot", "Rust", "spellrs", "~rust"], trie.words());
+    }
+}
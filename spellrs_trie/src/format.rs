@@ -0,0 +1,558 @@
+use crate::{trie::ROOT, ParseError, Trie, TrieNode};
+
+/// Separates the header from the data.
+const DATA: &str = "__DATA__";
+/// Marks the end of a word and moves up the tree.
+const EOW: char = '$';
+/// Moves up the tree.
+const BACK: char = '<';
+/// Starts a reference to an earlier node, i.e. `#1f;`.
+const REF: char = '#';
+/// Starts a reference through an index, which is not used by version 3.
+const REF_REL: char = '@';
+/// Ends a reference.
+const EOR: char = ';';
+const ESCAPE: char = '\\';
+/// Characters that have to be escaped in the data.
+const SPECIAL_CHARACTERS: &str = "$<\n#@;\\\r0123456789`~!@#$%^&*()_-+=[]{};:'\"<>,./?|";
+/// Words per line when writing, so that changes to a dictionary make small diffs.
+const WORDS_PER_LINE: usize = 20;
+
+/// Options for [`serialize_trie`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportOptions {
+    /// The radix of the references, between 10 and 36.
+    pub base: u32,
+    /// Written as `#` comments in the header.
+    pub comment: String,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            base: 32,
+            comment: String::new(),
+        }
+    }
+}
+
+/// Parses a trie in the cspell text format, version 3.
+///
+/// The format starts with a header that ends with `__DATA__`. The data walks the trie depth
+/// first: a character moves down, `<` moves up, `$` ends a word and moves up and `#n;` reuses a
+/// node that was already written. A digit after `<` or `$` moves up several levels at once.
+pub fn parse_trie(src: &str) -> Result<Trie, ParseError> {
+    let mut lines = src.lines().enumerate();
+    let mut header = vec![];
+    for (_, line) in lines.by_ref() {
+        let line = line.trim();
+        if line == DATA {
+            break;
+        }
+        if !line.is_empty() && !line.starts_with('#') {
+            header.push(line);
+        }
+    }
+
+    let version = header_value(&header, "TrieVersion:");
+    if version != Some("3") {
+        let message = match version {
+            Some(version) => format!("unsupported trie version {version}"),
+            None => "not a cspell trie file".to_string(),
+        };
+        return Err(ParseError::new(1, message));
+    }
+    let radix = match header_value(&header, "base=").map(str::parse::<u32>) {
+        Some(Ok(radix)) if (2..=36).contains(&radix) => radix,
+        Some(_) => return Err(ParseError::new(1, "invalid base")),
+        None => 10,
+    };
+
+    let mut parser = Parser::new(radix);
+    for (i, line) in lines {
+        for c in line.chars() {
+            parser
+                .next(c)
+                .map_err(|message| ParseError::new(i + 1, message))?;
+        }
+    }
+    match parser.state {
+        State::Ref(_) => Err(ParseError::new(
+            src.lines().count(),
+            "unterminated reference",
+        )),
+        _ => Ok(parser.trie.compact()),
+    }
+}
+
+fn header_value<'a>(header: &[&'a str], key: &str) -> Option<&'a str> {
+    header
+        .iter()
+        .find_map(|line| line.strip_prefix(key))
+        .map(str::trim)
+}
+
+enum State {
+    Main,
+    /// After `<` or `$`, where digits move up more levels.
+    Back,
+    /// After `\`. The flag is set after a second `\`, i.e. `\\n`.
+    Escape(bool),
+    Ref(String),
+}
+
+struct Parser {
+    trie: Trie,
+    radix: u32,
+    state: State,
+    /// The path from the root to the current node, with the character of each node.
+    stack: Vec<(usize, char)>,
+    /// The nodes that can be referenced, in the order they were written.
+    refs: Vec<usize>,
+    /// The node shared by all the words that end without children.
+    eow: usize,
+}
+
+impl Parser {
+    fn new(radix: u32) -> Self {
+        let mut trie = Trie::default();
+        let eow = trie.push(TrieNode {
+            end: true,
+            children: vec![],
+        });
+
+        Self {
+            trie,
+            radix,
+            state: State::Main,
+            stack: vec![(ROOT, '\0')],
+            refs: vec![ROOT],
+            eow,
+        }
+    }
+
+    fn next(&mut self, c: char) -> Result<(), String> {
+        match std::mem::replace(&mut self.state, State::Main) {
+            State::Main => self.main(c),
+            State::Back => match c {
+                BACK => self.back(1),
+                '2'..='9' => self.back(c as usize - '1' as usize),
+                _ => self.main(c),
+            },
+            State::Escape(false) if c == ESCAPE => {
+                self.state = State::Escape(true);
+                Ok(())
+            }
+            State::Escape(false) => self.push_char(c),
+            State::Escape(true) => self.push_char(match c {
+                'n' => '\n',
+                'r' => '\r',
+                _ => c,
+            }),
+            State::Ref(mut digits) => match c {
+                EOR => self.reference(&digits),
+                _ if c.is_digit(self.radix) => {
+                    digits.push(c);
+                    self.state = State::Ref(digits);
+                    Ok(())
+                }
+                _ => Err(format!("invalid character `{c}` in reference")),
+            },
+        }
+    }
+
+    fn main(&mut self, c: char) -> Result<(), String> {
+        match c {
+            EOW => self.end_of_word(),
+            BACK => self.back(1),
+            REF => {
+                self.state = State::Ref(String::new());
+                Ok(())
+            }
+            ESCAPE => {
+                self.state = State::Escape(false);
+                Ok(())
+            }
+            REF_REL => Err("indexed references are not supported".to_string()),
+            '\r' => Ok(()),
+            _ => self.push_char(c),
+        }
+    }
+
+    fn push_char(&mut self, c: char) -> Result<(), String> {
+        let (parent, _) = self.stack[self.stack.len() - 1];
+        let node = self.trie.push(TrieNode::default());
+        self.trie.node_mut(parent).set_child(c, node);
+        self.stack.push((node, c));
+        self.refs.push(node);
+        Ok(())
+    }
+
+    fn end_of_word(&mut self) -> Result<(), String> {
+        let (node, c) = self.pop()?;
+        self.trie.node_mut(node).end = true;
+        if self.trie.node(node).children.is_empty() {
+            let (parent, _) = self.stack[self.stack.len() - 1];
+            self.trie.node_mut(parent).set_child(c, self.eow);
+            self.refs.pop();
+        }
+        self.state = State::Back;
+        Ok(())
+    }
+
+    fn back(&mut self, levels: usize) -> Result<(), String> {
+        for _ in 0..levels {
+            self.pop()?;
+        }
+        self.state = State::Back;
+        Ok(())
+    }
+
+    fn reference(&mut self, digits: &str) -> Result<(), String> {
+        if self.stack.len() < 2 {
+            return Err("reference without a character".to_string());
+        }
+        let index = usize::from_str_radix(digits, self.radix).map_err(|e| e.to_string())?;
+        // The node of the character before the reference is replaced, so it can't be referenced.
+        self.refs.pop();
+        let node = *self
+            .refs
+            .get(index)
+            .ok_or_else(|| format!("reference to unknown node {digits}"))?;
+
+        let (_, c) = self.stack[self.stack.len() - 1];
+        let (parent, _) = self.stack[self.stack.len() - 2];
+        self.trie.node_mut(parent).set_child(c, node);
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<(usize, char), String> {
+        match self.stack.len() {
+            0 | 1 => Err("moved up past the root".to_string()),
+            _ => Ok(self.stack.pop().unwrap()),
+        }
+    }
+}
+
+/// Writes a trie in the cspell text format, version 3. See [`parse_trie`].
+pub fn serialize_trie(trie: &Trie, options: &ExportOptions) -> String {
+    let radix = options.base.clamp(10, 36);
+    let mut out = String::from("#!/usr/bin/env cspell-trie reader\nTrieVersion: 3\n");
+    out += &format!("base={radix}\n");
+    for line in options.comment.lines() {
+        out += &format!("# {}\n", line.trim_end());
+    }
+    out += &format!("# Data:\n{DATA}\n");
+
+    let mut writer = Writer {
+        out,
+        radix,
+        refs: vec![None; trie.node_count()],
+        count: 0,
+        backs: 0,
+        eow: false,
+        eol: false,
+        words: 0,
+    };
+    writer.walk(trie, ROOT, 0);
+    writer.flush();
+    if !writer.out.ends_with('\n') {
+        writer.out.push('\n');
+    }
+    writer.out
+}
+
+struct Writer {
+    out: String,
+    radix: u32,
+    /// The reference number of each node that was written.
+    refs: Vec<Option<usize>>,
+    count: usize,
+    /// The number of levels to move up before the next character.
+    backs: usize,
+    eow: bool,
+    eol: bool,
+    /// Words written on the current line.
+    words: usize,
+}
+
+impl Writer {
+    fn walk(&mut self, trie: &Trie, node: usize, depth: usize) {
+        if let Some(index) = self.refs[node] {
+            if self.words >= WORDS_PER_LINE {
+                self.eol = true;
+            }
+            self.flush();
+            self.out.push(REF);
+            self.out += &to_radix(index, self.radix);
+            self.out.push(EOR);
+            self.words += 1;
+            return;
+        }
+
+        let trie_node = trie.node(node);
+        if !trie_node.children.is_empty() {
+            self.refs[node] = Some(self.count);
+            self.count += 1;
+        }
+        // Sorted like JavaScript strings, by UTF-16 code units, as cspell does.
+        let mut children = trie_node.children.clone();
+        children.sort_by(|(a, _), (b, _)| {
+            a.encode_utf16(&mut [0; 2])
+                .cmp(&b.encode_utf16(&mut [0; 2]))
+        });
+        for (c, child) in children {
+            if self.words >= WORDS_PER_LINE {
+                self.eol = true;
+            }
+            self.flush();
+            self.push_char(c);
+            self.walk(trie, child, depth + 1);
+            self.backs += 1;
+            if depth == 0 {
+                self.eol = true;
+            }
+        }
+
+        // Written after the children, so that `$` also moves up from the node.
+        if trie_node.end {
+            self.flush();
+            self.eow = true;
+            self.words += 1;
+        }
+    }
+
+    fn push_char(&mut self, c: char) {
+        match c {
+            '\n' => self.out += "\\\\n",
+            '\r' => self.out += "\\\\r",
+            '\\' => self.out += "\\\\\\",
+            _ if SPECIAL_CHARACTERS.contains(c) => {
+                self.out.push(ESCAPE);
+                self.out.push(c);
+            }
+            _ => self.out.push(c),
+        }
+    }
+
+    /// Writes the pending end of word and moves up.
+    ///
+    /// Like cspell, up to 9 levels are written at once as `$` or `<` followed by the number of
+    /// levels, i.e. `$2` ends a word and moves up twice and `$9<3` moves up 12 levels.
+    fn flush(&mut self) {
+        let mut last = match self.eow {
+            true => EOW,
+            false => BACK,
+        };
+        let mut backs = self.backs;
+        while backs > 0 {
+            let levels = backs.min(9);
+            self.out.push(last);
+            if levels > 1 {
+                self.out += &levels.to_string();
+            }
+            last = BACK;
+            backs -= levels;
+        }
+
+        if self.eol {
+            self.out.push('\n');
+            self.words = 0;
+        }
+        self.backs = 0;
+        self.eow = false;
+        self.eol = false;
+    }
+}
+
+fn to_radix(mut n: usize, radix: u32) -> String {
+    let mut digits = vec![];
+    loop {
+        digits.push(std::char::from_digit((n % radix as usize) as u32, radix).unwrap());
+        n /= radix as usize;
+        if n == 0 {
+            break;
+        }
+    }
+    digits.iter().rev().collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        fs,
+        io::Read,
+        path::{Path, PathBuf},
+    };
+
+    use flate2::read::GzDecoder;
+
+    use super::*;
+
+    fn header(base: u32) -> String {
+        format!(
+            "#!/usr/bin/env cspell-trie reader\nTrieVersion: 3\nbase={base}\n# Data:\n__DATA__\n"
+        )
+    }
+
+    #[test]
+    fn test_parse_trie() {
+        let cases = [
+            ("cat$<", vec!["cat"]),
+            ("cat$3", vec!["cat"]),
+            ("cat$<<\nat$2", vec!["at", "cat"]),
+            ("cats$$3\ndog$<<", vec!["cat", "cats", "dog"]),
+            (
+                "walked$<$4\nt#1;<",
+                vec!["talk", "talked", "walk", "walked"],
+            ),
+            ("a\\$$<\\\\\\$", vec!["\\", "a$"]),
+            ("a\\\\n$<", vec!["a\n"]),
+            ("\\1st$3\n", vec!["1st"]),
+        ];
+
+        for (data, expected) in cases {
+            let trie = parse_trie(&(header(32) + data)).unwrap();
+            assert_eq!(expected, trie.words(), "{data}");
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let cases = [
+            ("", "line 1: not a cspell trie file"),
+            (
+                "TrieVersion: 4\n__DATA__\n",
+                "line 1: unsupported trie version 4",
+            ),
+            (
+                "TrieVersion: 3\nbase=99\n__DATA__\n",
+                "line 1: invalid base",
+            ),
+            (
+                "TrieVersion: 3\n__DATA__\na$<<",
+                "line 3: moved up past the root",
+            ),
+            (
+                "TrieVersion: 3\n__DATA__\nab#5;",
+                "line 3: reference to unknown node 5",
+            ),
+            (
+                "TrieVersion: 3\n__DATA__\nab#1",
+                "line 3: unterminated reference",
+            ),
+            (
+                "TrieVersion: 3\n__DATA__\n#0;",
+                "line 3: reference without a character",
+            ),
+        ];
+
+        for (src, expected) in cases {
+            assert_eq!(expected, parse_trie(src).unwrap_err().to_string(), "{src}");
+        }
+    }
+
+    #[test]
+    fn test_serialize_trie() {
+        let trie = Trie::from_words(["walk", "walked", "talk", "talked", "a"]);
+        let options = ExportOptions {
+            base: 10,
+            comment: "Sample".to_string(),
+        };
+        let expected = "#!/usr/bin/env cspell-trie reader\nTrieVersion: 3\nbase=10\n# Sample\n\
+            # Data:\n__DATA__\na$\ntalked$2$4\nw#1;<\n";
+        assert_eq!(expected, serialize_trie(&trie, &options));
+    }
+
+    /// The words of `testdata/sample.trie.gz`.
+    const SAMPLE_WORDS: [&str; 16] = [
+        "!helo",
+        "+do",
+        "1st",
+        "Paris",
+        "café",
+        "don't",
+        "e-mail",
+        "hello",
+        "internationalization",
+        "talk",
+        "talked",
+        "un+",
+        "walk",
+        "walked",
+        "~cafe",
+        "~paris",
+    ];
+
+    /// `testdata/sample.trie.gz` was written by hand from cspell-trie-lib's format, with base
+    /// 32, escapes, references, forbidden `!`, compound `+` and `~` case insensitive entries,
+    /// and more than 9 levels to move up. It was not made by cspell-tools, so it only shows
+    /// that the reader and writer agree with each other on these cases.
+    fn sample_path() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("testdata")
+            .join("sample.trie.gz")
+    }
+
+    #[test]
+    fn test_parse_sample() {
+        let trie = Trie::from_file(sample_path()).unwrap();
+        assert_eq!(SAMPLE_WORDS.to_vec(), trie.words());
+    }
+
+    /// Reading the file and writing the trie with the options of its header gives back the
+    /// same bytes.
+    #[test]
+    fn test_sample_round_trip() {
+        let mut expected = String::new();
+        GzDecoder::new(fs::File::open(sample_path()).unwrap())
+            .read_to_string(&mut expected)
+            .unwrap();
+        let header = |prefix: &str| {
+            expected
+                .lines()
+                .find_map(|line| line.strip_prefix(prefix))
+                .unwrap()
+                .to_string()
+        };
+        let options = ExportOptions {
+            base: header("base=").parse().unwrap(),
+            comment: header("# "),
+        };
+
+        let trie = Trie::from_file(sample_path()).unwrap();
+        assert_eq!(expected, serialize_trie(&trie, &options));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let words = [
+            "walk",
+            "walked",
+            "walking",
+            "talk",
+            "talked",
+            "talking",
+            "$special<#;",
+            "back\\slash",
+            "new\nline",
+            "2nd",
+            "café",
+            "naïve",
+            "😀",
+        ];
+        let mut many: Vec<String> = (0..500).map(|i| format!("word{i}")).collect();
+        many.extend(words.iter().map(|w| w.to_string()));
+        let trie = Trie::from_words(&many);
+
+        for base in [10, 16, 32, 36] {
+            let options = ExportOptions {
+                base,
+                ..Default::default()
+            };
+            let parsed = parse_trie(&serialize_trie(&trie, &options)).unwrap();
+            assert_eq!(trie.words(), parsed.words(), "{base}");
+            assert_eq!(trie.node_count(), parsed.node_count(), "{base}");
+        }
+    }
+}
@@ -0,0 +1,20 @@
+mod error;
+mod file;
+mod format;
+mod suggest;
+mod trie;
+mod weights;
+mod word_list;
+
+pub use error::{Error, ParseError};
+pub use format::{parse_trie, serialize_trie, ExportOptions};
+pub use suggest::{edit_distance, SuggestOptions, Suggestion};
+pub use trie::{Trie, TrieNode, ROOT};
+pub use weights::{
+    EditCosts, WeightMap, ACCENT_COST, BASE_COST, CASE_COST, DOUBLE_LETTER_COST, KEYBOARD_COST,
+    SWAP_COST,
+};
+pub use word_list::{
+    case_insensitive_form, parse_word_list, CASE_INSENSITIVE_PREFIX, COMPOUND_FIX, FORBID_PREFIX,
+    OPTIONAL_COMPOUND_FIX,
+};
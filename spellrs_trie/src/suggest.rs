@@ -0,0 +1,311 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    trie::ROOT, Trie, WeightMap, BASE_COST, CASE_INSENSITIVE_PREFIX, COMPOUND_FIX, FORBID_PREFIX,
+};
+
+/// How often the time limit is checked while walking the trie, in nodes.
+const TIMEOUT_CHECK_INTERVAL: usize = 1000;
+
+/// Options for the suggestions, named like the cspell settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuggestOptions {
+    /// The maximum number of suggestions, `numSuggestions`.
+    pub num_suggestions: usize,
+    /// The maximum number of edits, `suggestionNumChanges`. Each edit costs up to [`BASE_COST`].
+    pub num_changes: usize,
+    /// Stops looking for suggestions after this time, `suggestionsTimeout`.
+    pub timeout: Duration,
+}
+
+impl Default for SuggestOptions {
+    fn default() -> Self {
+        Self {
+            num_suggestions: 10,
+            num_changes: 3,
+            timeout: Duration::from_millis(500),
+        }
+    }
+}
+
+impl SuggestOptions {
+    /// The highest cost of a suggestion.
+    pub fn max_cost(&self) -> u32 {
+        self.num_changes as u32 * BASE_COST
+    }
+}
+
+/// A suggested word and the cost of the edits to get it. Lower is better.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Suggestion {
+    pub cost: u32,
+    pub word: String,
+}
+
+/// The weighted Damerau-Levenshtein distance between two words.
+pub fn edit_distance(a: &str, b: &str, weights: &WeightMap) -> u32 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![first_row(&a, weights)];
+    for j in 1..=b.len() {
+        let row = next_row(&rows, &a, &b[..j], weights);
+        rows.push(row);
+    }
+    rows[b.len()][a.len()]
+}
+
+/// The costs of deleting the first `i` characters of `word`.
+fn first_row(word: &[char], weights: &WeightMap) -> Vec<u32> {
+    let mut row = Vec::with_capacity(word.len() + 1);
+    row.push(0);
+    for i in 0..word.len() {
+        row.push(row[i] + delete_cost(word, i + 1, weights));
+    }
+    row
+}
+
+/// Computes the row of the edit distance table for `candidate`, where `rows` has the rows for
+/// all the shorter prefixes of `candidate`. `row[i]` is the cost of changing the first `i`
+/// characters of `word` into `candidate`.
+fn next_row(rows: &[Vec<u32>], word: &[char], candidate: &[char], weights: &WeightMap) -> Vec<u32> {
+    let j = candidate.len();
+    let c = candidate[j - 1];
+    let prev = &rows[j - 1];
+    let insert = match j >= 2 && candidate[j - 2] == c {
+        true => weights.double_letter_cost(c),
+        false => weights.ins_del_cost(c),
+    };
+    let mut row = Vec::with_capacity(word.len() + 1);
+    row.push(prev[0] + insert);
+
+    for i in 1..=word.len() {
+        let w = word[i - 1];
+        let mut cost = (prev[i - 1] + weights.replace_cost(w, c))
+            .min(prev[i] + insert)
+            .min(row[i - 1] + delete_cost(word, i, weights));
+
+        if i >= 2 && j >= 2 && w == candidate[j - 2] && word[i - 2] == c && w != c {
+            cost = cost.min(rows[j - 2][i - 2] + weights.swap_cost(word[i - 2], w));
+        }
+        for rule in weights.rules() {
+            let (from, to) = (rule.from.len(), rule.to.len());
+            if from <= i
+                && to <= j
+                && word[i - from..i] == rule.from
+                && candidate[j - to..] == rule.to
+            {
+                let before = match to {
+                    0 => &row,
+                    _ => &rows[j - to],
+                };
+                cost = cost.min(before[i - from] + rule.cost);
+            }
+        }
+
+        row.push(cost);
+    }
+    row
+}
+
+/// The cost of deleting the character before `i` from `word`.
+fn delete_cost(word: &[char], i: usize, weights: &WeightMap) -> u32 {
+    match i >= 2 && word[i - 2] == word[i - 1] {
+        true => weights.double_letter_cost(word[i - 1]),
+        false => weights.ins_del_cost(word[i - 1]),
+    }
+}
+
+struct Search<'a> {
+    trie: &'a Trie,
+    word: Vec<char>,
+    weights: &'a WeightMap,
+    num_suggestions: usize,
+    max_cost: u32,
+    deadline: Instant,
+    visited: usize,
+    candidate: Vec<char>,
+    rows: Vec<Vec<u32>>,
+    results: Vec<Suggestion>,
+}
+
+impl Search<'_> {
+    /// Walks the trie below `node`, skipping the branches that cost more than `max_cost`.
+    fn walk(&mut self, node: usize) -> bool {
+        if self.visited.is_multiple_of(TIMEOUT_CHECK_INTERVAL) && Instant::now() >= self.deadline {
+            return false;
+        }
+        self.visited += 1;
+
+        let cost = self.rows[self.candidate.len()][self.word.len()];
+        if self.trie.node(node).end && cost <= self.max_cost && node != ROOT {
+            self.add(cost);
+        }
+
+        for &(c, child) in &self.trie.node(node).children {
+            let is_marker = match node {
+                ROOT => [FORBID_PREFIX, CASE_INSENSITIVE_PREFIX, COMPOUND_FIX].contains(&c),
+                _ => c == COMPOUND_FIX,
+            };
+            if is_marker {
+                continue;
+            }
+
+            self.candidate.push(c);
+            let row = next_row(&self.rows, &self.word, &self.candidate, self.weights);
+            let min = row.iter().min().copied().unwrap_or_default();
+            self.rows.push(row);
+            let done = min > self.max_cost || self.walk(child);
+            self.rows.pop();
+            self.candidate.pop();
+            if !done {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn add(&mut self, cost: u32) {
+        self.results.push(Suggestion {
+            cost,
+            word: self.candidate.iter().collect(),
+        });
+
+        // Once there are enough suggestions, only cheaper ones are of interest.
+        if self.results.len() >= self.num_suggestions * 2 {
+            self.results.sort();
+            self.results.truncate(self.num_suggestions);
+            self.max_cost = self.results[self.num_suggestions - 1].cost;
+        }
+    }
+}
+
+impl Trie {
+    /// Finds the words closest to `word`, ranked by their weighted edit distance.
+    ///
+    /// Forbidden words, case-insensitive forms and compound parts are not suggested.
+    pub fn suggest(
+        &self,
+        word: &str,
+        options: &SuggestOptions,
+        weights: &WeightMap,
+    ) -> Vec<Suggestion> {
+        let word: Vec<char> = word.chars().collect();
+        if word.is_empty() || options.num_suggestions == 0 {
+            return vec![];
+        }
+
+        let mut search = Search {
+            trie: self,
+            rows: vec![first_row(&word, weights)],
+            word,
+            weights,
+            num_suggestions: options.num_suggestions,
+            max_cost: options.max_cost(),
+            deadline: Instant::now() + options.timeout,
+            visited: 0,
+            candidate: vec![],
+            results: vec![],
+        };
+        search.walk(ROOT);
+
+        let mut results = search.results;
+        results.sort();
+        results.truncate(options.num_suggestions);
+        results
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{EditCosts, ACCENT_COST, CASE_COST, DOUBLE_LETTER_COST, KEYBOARD_COST, SWAP_COST};
+
+    #[test]
+    fn test_edit_distance() {
+        let weights = WeightMap::new(&[EditCosts {
+            map: "(ph)f".into(),
+            replace: Some(20),
+            ..Default::default()
+        }]);
+        let cases = [
+            ("hello", "hello", 0),
+            ("hello", "Hello", CASE_COST),
+            ("cafe", "café", ACCENT_COST),
+            ("teh", "the", SWAP_COST),
+            ("tge", "the", KEYBOARD_COST),
+            ("helo", "hello", DOUBLE_LETTER_COST),
+            ("helllo", "hello", DOUBLE_LETTER_COST),
+            ("hllo", "hello", BASE_COST),
+            ("hello", "", 4 * BASE_COST + DOUBLE_LETTER_COST),
+            ("fone", "phone", 20),
+            ("phonetik", "fonetic", 20 + BASE_COST),
+            ("kitten", "sitting", 3 * BASE_COST),
+        ];
+
+        for (a, b, expected) in cases {
+            assert_eq!(expected, edit_distance(a, b, &weights), "{a} {b}");
+        }
+    }
+
+    #[test]
+    fn test_suggest() {
+        let trie = Trie::from_words([
+            "the", "then", "they", "ten", "tea", "hello", "help", "café", "Paris", "~paris",
+            "!teh", "un+", "+do", "walking",
+        ]);
+        let weights = WeightMap::default();
+        let options = SuggestOptions {
+            num_suggestions: 3,
+            num_changes: 2,
+            ..Default::default()
+        };
+
+        let cases = [
+            ("teh", vec!["the", "ten", "tea"]),
+            ("helo", vec!["hello", "help"]),
+            ("cafe", vec!["café"]),
+            ("paris", vec!["Paris"]),
+            ("undo", vec![]),
+            ("wlakign", vec!["walking"]),
+        ];
+
+        for (word, expected) in cases {
+            let suggestions: Vec<String> = trie
+                .suggest(word, &options, &weights)
+                .into_iter()
+                .map(|s| s.word)
+                .collect();
+            assert_eq!(expected, suggestions, "{word}");
+        }
+
+        let costs: Vec<u32> = trie
+            .suggest("teh", &options, &weights)
+            .iter()
+            .map(|s| s.cost)
+            .collect();
+        assert_eq!(vec![SWAP_COST, KEYBOARD_COST, BASE_COST], costs);
+    }
+
+    #[test]
+    fn test_suggest_timeout() {
+        let mut words: Vec<String> = (0..20000).map(|i| format!("word{i}")).collect();
+        words.push("zord".to_string());
+        let trie = Trie::from_words(&words);
+        let has_zord = |options: &SuggestOptions| {
+            trie.suggest("word", options, &WeightMap::default())
+                .iter()
+                .any(|s| s.word == "zord")
+        };
+
+        let options = SuggestOptions {
+            num_suggestions: 20,
+            ..Default::default()
+        };
+        assert!(has_zord(&options));
+        // The search stops in the `word` branch, before it gets to `zord`.
+        assert!(!has_zord(&SuggestOptions {
+            timeout: Duration::ZERO,
+            ..options
+        }));
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+
+use crate::COMPOUND_FIX;
+
+/// The index of the root node.
+pub const ROOT: usize = 0;
+
+/// A node of a [`Trie`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct TrieNode {
+    /// A word ends at this node.
+    pub end: bool,
+    /// The child nodes by character, sorted by character.
+    pub children: Vec<(char, usize)>,
+}
+
+impl TrieNode {
+    /// Finds the child for a character.
+    pub fn child(&self, c: char) -> Option<usize> {
+        self.children
+            .binary_search_by_key(&c, |&(k, _)| k)
+            .ok()
+            .map(|i| self.children[i].1)
+    }
+
+    pub(crate) fn set_child(&mut self, c: char, node: usize) {
+        match self.children.binary_search_by_key(&c, |&(k, _)| k) {
+            Ok(i) => self.children[i].1 = node,
+            Err(i) => self.children.insert(i, (c, node)),
+        }
+    }
+}
+
+/// A set of words stored as a graph of characters, where identical endings are shared.
+///
+/// This is the in-memory form of the cspell `.trie` dictionary files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trie {
+    nodes: Vec<TrieNode>,
+}
+
+impl Default for Trie {
+    fn default() -> Self {
+        Self {
+            nodes: vec![TrieNode::default()],
+        }
+    }
+}
+
+impl Trie {
+    /// Builds a trie from a list of words. Empty words are skipped.
+    pub fn from_words<I>(words: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut trie = Trie::default();
+        for word in words {
+            let word = word.as_ref();
+            if word.is_empty() {
+                continue;
+            }
+
+            let mut node = ROOT;
+            for c in word.chars() {
+                node = match trie.nodes[node].child(c) {
+                    Some(child) => child,
+                    None => {
+                        let child = trie.push(TrieNode::default());
+                        trie.nodes[node].set_child(c, child);
+                        child
+                    }
+                };
+            }
+            trie.nodes[node].end = true;
+        }
+
+        trie.compact()
+    }
+
+    pub(crate) fn push(&mut self, node: TrieNode) -> usize {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    pub(crate) fn node_mut(&mut self, index: usize) -> &mut TrieNode {
+        &mut self.nodes[index]
+    }
+
+    /// Returns a node by index. The root is [`ROOT`].
+    pub fn node(&self, index: usize) -> &TrieNode {
+        &self.nodes[index]
+    }
+
+    /// The number of nodes. Shared endings are only counted once.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Follows the characters of `prefix` from the root.
+    pub fn find(&self, prefix: &str) -> Option<usize> {
+        prefix
+            .chars()
+            .try_fold(ROOT, |node, c| self.nodes[node].child(c))
+    }
+
+    /// Checks if the trie has the word.
+    pub fn has(&self, word: &str) -> bool {
+        self.find(word).is_some_and(|node| self.nodes[node].end)
+    }
+
+    /// Checks if the trie has the word, either whole or as a compound of parts marked with `+`.
+    ///
+    /// The first part of a compound is stored as `part+`, the middle parts as `+part+` and the
+    /// last part as `+part`, so `un+` and `+do` make `undo`.
+    pub fn has_compound(&self, word: &str) -> bool {
+        let chars: Vec<char> = word.chars().collect();
+        !chars.is_empty() && self.find_compound(ROOT, &chars)
+    }
+
+    fn find_compound(&self, mut node: usize, chars: &[char]) -> bool {
+        let next_part = self.nodes[ROOT].child(COMPOUND_FIX);
+        for (i, &c) in chars.iter().enumerate() {
+            let ends_part = self.nodes[node]
+                .child(COMPOUND_FIX)
+                .is_some_and(|fix| self.nodes[fix].end);
+            if i > 0 && ends_part && next_part.is_some_and(|n| self.find_compound(n, &chars[i..])) {
+                return true;
+            }
+
+            match self.nodes[node].child(c) {
+                Some(child) => node = child,
+                None => return false,
+            }
+        }
+
+        self.nodes[node].end
+    }
+
+    /// Returns all the words in sorted order.
+    pub fn words(&self) -> Vec<String> {
+        let mut words = vec![];
+        let mut word = String::new();
+        self.collect_words(ROOT, &mut word, &mut words);
+        words
+    }
+
+    fn collect_words(&self, node: usize, word: &mut String, words: &mut Vec<String>) {
+        if self.nodes[node].end {
+            words.push(word.clone());
+        }
+        for &(c, child) in &self.nodes[node].children {
+            word.push(c);
+            self.collect_words(child, word, words);
+            word.pop();
+        }
+    }
+
+    /// Merges identical nodes and drops the nodes that can no longer be reached.
+    pub(crate) fn compact(&self) -> Trie {
+        let mut compacted = Trie::default();
+        let mut unique: HashMap<TrieNode, usize> = HashMap::new();
+        let mut mapped: HashMap<usize, usize> = HashMap::new();
+        compacted.nodes[ROOT] = self.compact_node(ROOT, &mut compacted, &mut unique, &mut mapped);
+        compacted
+    }
+
+    /// Copies a node into `compacted`, after its children, and returns the copy.
+    fn compact_node(
+        &self,
+        node: usize,
+        compacted: &mut Trie,
+        unique: &mut HashMap<TrieNode, usize>,
+        mapped: &mut HashMap<usize, usize>,
+    ) -> TrieNode {
+        let mut children = Vec::with_capacity(self.nodes[node].children.len());
+        for &(c, child) in &self.nodes[node].children {
+            let index = match mapped.get(&child) {
+                Some(&index) => index,
+                None => {
+                    let copy = self.compact_node(child, compacted, unique, mapped);
+                    let index = match unique.get(&copy) {
+                        Some(&index) => index,
+                        None => {
+                            let index = compacted.push(copy.clone());
+                            unique.insert(copy, index);
+                            index
+                        }
+                    };
+                    mapped.insert(child, index);
+                    index
+                }
+            };
+            children.push((c, index));
+        }
+
+        TrieNode {
+            end: self.nodes[node].end,
+            children,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_words() {
+        let trie = Trie::from_words(["walk", "walked", "talk", "talked", "walking", "", "a"]);
+        let cases = [
+            ("walk", true),
+            ("walked", true),
+            ("talked", true),
+            ("walking", true),
+            ("talking", false),
+            ("wal", false),
+            ("a", true),
+            ("", false),
+        ];
+
+        for (word, expected) in cases {
+            assert_eq!(expected, trie.has(word), "{word}");
+        }
+
+        assert_eq!(
+            vec!["a", "talk", "talked", "walk", "walked", "walking"],
+            trie.words()
+        );
+    }
+
+    #[test]
+    fn test_has_compound() {
+        let trie = Trie::from_words([
+            "un+", "+do", "+able", "+do+", "work", "work+", "+work", "+s",
+        ]);
+        let cases = [
+            ("undo", true),
+            ("undoable", true),
+            ("work", true),
+            ("workwork", true),
+            ("works", true),
+            ("workworks", false),
+            ("unwork", true),
+            ("un", false),
+            ("do", false),
+            ("doable", false),
+            ("unable", true),
+            ("undone", false),
+            ("", false),
+        ];
+
+        for (word, expected) in cases {
+            assert_eq!(expected, trie.has_compound(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn test_compact() {
+        let trie = Trie::from_words(["walk", "talk", "walked", "talked"]);
+        // `talk` and `talked` share all the nodes of `walk` and `walked`.
+        assert_eq!(
+            Trie::from_words(["walk", "walked"]).node_count(),
+            trie.node_count()
+        );
+        assert_eq!(7, trie.node_count());
+
+        let trie = Trie::from_words(["a"]);
+        assert_eq!(2, trie.node_count());
+        assert_eq!(Trie::default(), Trie::from_words([""]));
+    }
+}
@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// The cost of an edit that has no cheaper cost, i.e. replacing `a` with `z`.
+pub const BASE_COST: u32 = 100;
+/// Changing the case of a letter, i.e. `paris` to `Paris`.
+pub const CASE_COST: u32 = 1;
+/// Adding, removing or changing an accent, i.e. `cafe` to `café`.
+pub const ACCENT_COST: u32 = 10;
+/// Replacing a letter with its neighbour on a QWERTY keyboard, i.e. `tge` to `the`.
+pub const KEYBOARD_COST: u32 = 90;
+/// Swapping two letters next to each other, i.e. `teh` to `the`.
+pub const SWAP_COST: u32 = 75;
+/// Adding or removing a doubled letter, i.e. `helo` to `hello`.
+pub const DOUBLE_LETTER_COST: u32 = 50;
+const KEYBOARD_ROWS: [&str; 3] = ["qwertyuiop", "asdfghjkl", "zxcvbnm"];
+
+/// The costs of the edits between a group of characters, like cspell's `suggestionEditCosts`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EditCosts {
+    /// Groups of characters separated by `|`. Sequences of characters are put in parentheses,
+    /// i.e. `aeiou|(ph)f` makes `ph` and `f` a group.
+    pub map: String,
+    /// Replacing a character with another of the same group.
+    pub replace: Option<u32>,
+    /// Inserting or deleting a character of a group.
+    pub ins_del: Option<u32>,
+    /// Swapping two characters of the same group.
+    pub swap: Option<u32>,
+    /// Added to each of the costs.
+    pub penalty: Option<u32>,
+}
+
+/// Replaces a sequence of characters with another, i.e. `ph` with `f`. One of them can be empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Rule {
+    pub from: Vec<char>,
+    pub to: Vec<char>,
+    pub cost: u32,
+}
+
+/// The costs of the edits used to rank suggestions.
+///
+/// By default every edit costs [`BASE_COST`], except for changes of case or accents, swaps,
+/// doubled letters and replacing neighbours on the keyboard. [`EditCosts`] add cheaper edits.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WeightMap {
+    replace: HashMap<(char, char), u32>,
+    ins_del: HashMap<char, u32>,
+    swap: HashMap<(char, char), u32>,
+    rules: Vec<Rule>,
+}
+
+impl WeightMap {
+    pub fn new(costs: &[EditCosts]) -> Self {
+        let mut weights = WeightMap::default();
+        for costs in costs {
+            weights.add(costs);
+        }
+        weights
+    }
+
+    /// Adds the costs of a map. The lowest cost of an edit is kept.
+    pub fn add(&mut self, costs: &EditCosts) {
+        let penalty = costs.penalty.unwrap_or(0);
+        for group in costs.map.split('|') {
+            let items = parse_group(group);
+            for (i, a) in items.iter().enumerate() {
+                if let Some(cost) = costs.ins_del {
+                    match a.as_slice() {
+                        [c] => set_min(&mut self.ins_del, *c, cost + penalty),
+                        _ => {
+                            self.add_rule(a, &[], cost + penalty);
+                            self.add_rule(&[], a, cost + penalty);
+                        }
+                    }
+                }
+
+                for b in &items[i + 1..] {
+                    if let Some(cost) = costs.replace {
+                        match (a.as_slice(), b.as_slice()) {
+                            ([x], [y]) => {
+                                set_min(&mut self.replace, (*x, *y), cost + penalty);
+                                set_min(&mut self.replace, (*y, *x), cost + penalty);
+                            }
+                            _ => {
+                                self.add_rule(a, b, cost + penalty);
+                                self.add_rule(b, a, cost + penalty);
+                            }
+                        }
+                    }
+                    if let (Some(cost), [x], [y]) = (costs.swap, a.as_slice(), b.as_slice()) {
+                        set_min(&mut self.swap, (*x, *y), cost + penalty);
+                        set_min(&mut self.swap, (*y, *x), cost + penalty);
+                    }
+                }
+            }
+        }
+    }
+
+    fn add_rule(&mut self, from: &[char], to: &[char], cost: u32) {
+        if from == to {
+            return;
+        }
+        match self.rules.iter_mut().find(|r| r.from == from && r.to == to) {
+            Some(rule) => rule.cost = rule.cost.min(cost),
+            None => self.rules.push(Rule {
+                from: from.to_vec(),
+                to: to.to_vec(),
+                cost,
+            }),
+        }
+    }
+
+    pub(crate) fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// The cost of replacing `a` with `b`.
+    pub fn replace_cost(&self, a: char, b: char) -> u32 {
+        if a == b {
+            return 0;
+        }
+
+        let default = if a.to_lowercase().eq(b.to_lowercase()) {
+            CASE_COST
+        } else if base_letter(a) == base_letter(b) {
+            ACCENT_COST
+        } else if is_keyboard_neighbour(a, b) {
+            KEYBOARD_COST
+        } else {
+            BASE_COST
+        };
+        self.replace
+            .get(&(a, b))
+            .map_or(default, |&c| c.min(default))
+    }
+
+    /// The cost of inserting or deleting `c`.
+    pub fn ins_del_cost(&self, c: char) -> u32 {
+        match self.ins_del.get(&c) {
+            Some(&cost) => cost,
+            None if is_combining_mark(c) => ACCENT_COST,
+            None => BASE_COST,
+        }
+    }
+
+    /// The cost of inserting or deleting `c` next to another `c`.
+    pub fn double_letter_cost(&self, c: char) -> u32 {
+        self.ins_del_cost(c).min(DOUBLE_LETTER_COST)
+    }
+
+    /// The cost of swapping `a` and `b`, i.e. `ab` to `ba`.
+    pub fn swap_cost(&self, a: char, b: char) -> u32 {
+        self.swap.get(&(a, b)).copied().unwrap_or(SWAP_COST)
+    }
+}
+
+fn set_min<K: std::hash::Hash + Eq>(map: &mut HashMap<K, u32>, key: K, cost: u32) {
+    let entry = map.entry(key).or_insert(cost);
+    *entry = (*entry).min(cost);
+}
+
+/// Splits a group of a map into its characters and `(sequences)`.
+fn parse_group(group: &str) -> Vec<Vec<char>> {
+    let mut items = vec![];
+    let mut chars = group.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '(' => items.push(chars.by_ref().take_while(|&c| c != ')').collect()),
+            _ => items.push(vec![c]),
+        }
+    }
+    items
+}
+
+/// The lower case letter without accents, i.e. `e` for `É`.
+fn base_letter(c: char) -> Option<char> {
+    c.to_lowercase().nfd().find(|&c| !is_combining_mark(c))
+}
+
+fn is_keyboard_neighbour(a: char, b: char) -> bool {
+    let position = |c: char| {
+        let c = c.to_ascii_lowercase();
+        KEYBOARD_ROWS
+            .iter()
+            .enumerate()
+            .find_map(|(row, keys)| keys.find(c).map(|col| (row as isize, col as isize)))
+    };
+    let (Some((row_a, col_a)), Some((row_b, col_b))) = (position(a), position(b)) else {
+        return false;
+    };
+
+    // Each row is shifted a little to the right of the row above it.
+    match row_b - row_a {
+        0 => (col_a - col_b).abs() == 1,
+        1 => col_b == col_a || col_b == col_a - 1,
+        -1 => col_b == col_a || col_b == col_a + 1,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_default_costs() {
+        let weights = WeightMap::default();
+        let cases = [
+            ('a', 'a', 0),
+            ('p', 'P', CASE_COST),
+            ('e', 'é', ACCENT_COST),
+            ('E', 'é', ACCENT_COST),
+            ('g', 'h', KEYBOARD_COST),
+            ('s', 'w', KEYBOARD_COST),
+            ('s', 'z', KEYBOARD_COST),
+            ('s', 'c', BASE_COST),
+            ('a', 'z', KEYBOARD_COST),
+            ('a', 'p', BASE_COST),
+            ('1', '2', BASE_COST),
+        ];
+
+        for (a, b, expected) in cases {
+            assert_eq!(expected, weights.replace_cost(a, b), "{a} {b}");
+            assert_eq!(expected, weights.replace_cost(b, a), "{b} {a}");
+        }
+        assert_eq!(BASE_COST, weights.ins_del_cost('a'));
+        assert_eq!(ACCENT_COST, weights.ins_del_cost('\u{301}'));
+        assert_eq!(DOUBLE_LETTER_COST, weights.double_letter_cost('l'));
+        assert_eq!(SWAP_COST, weights.swap_cost('a', 'b'));
+    }
+
+    #[test]
+    fn test_edit_costs() {
+        let weights = WeightMap::new(&[
+            EditCosts {
+                map: "aeiou".into(),
+                replace: Some(50),
+                swap: Some(40),
+                ins_del: Some(30),
+                ..Default::default()
+            },
+            EditCosts {
+                map: "(ph)f|h".into(),
+                replace: Some(30),
+                ins_del: Some(20),
+                penalty: Some(5),
+                ..Default::default()
+            },
+            EditCosts {
+                map: "ae".into(),
+                replace: Some(200),
+                ..Default::default()
+            },
+        ]);
+
+        assert_eq!(50, weights.replace_cost('a', 'e'));
+        assert_eq!(50, weights.replace_cost('u', 'o'));
+        assert_eq!(KEYBOARD_COST, weights.replace_cost('a', 's'));
+        assert_eq!(40, weights.swap_cost('e', 'i'));
+        assert_eq!(30, weights.double_letter_cost('e'));
+        assert_eq!(25, weights.ins_del_cost('h'));
+        assert_eq!(25, weights.ins_del_cost('f'));
+        assert_eq!(
+            vec![
+                Rule {
+                    from: vec!['p', 'h'],
+                    to: vec![],
+                    cost: 25
+                },
+                Rule {
+                    from: vec![],
+                    to: vec!['p', 'h'],
+                    cost: 25
+                },
+                Rule {
+                    from: vec!['p', 'h'],
+                    to: vec!['f'],
+                    cost: 35
+                },
+                Rule {
+                    from: vec!['f'],
+                    to: vec!['p', 'h'],
+                    cost: 35
+                },
+            ],
+            weights.rules()
+        );
+    }
+}
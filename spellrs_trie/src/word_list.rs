@@ -0,0 +1,117 @@
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// Marks a forbidden word, i.e. `!alot`.
+pub const FORBID_PREFIX: char = '!';
+/// Marks a case-insensitive form of a word, i.e. `~paris` for `Paris`.
+pub const CASE_INSENSITIVE_PREFIX: char = '~';
+/// Marks a part of a compound word. `un+` starts a compound and `+do` ends one.
+pub const COMPOUND_FIX: char = '+';
+/// Marks a word that can be used alone or as a part of a compound word, i.e. `*work*`.
+pub const OPTIONAL_COMPOUND_FIX: char = '*';
+/// Starts a comment.
+const COMMENT: char = '#';
+
+/// The form of a word used to find it regardless of case and accents, i.e. `cafe` for `Café`.
+///
+/// cspell stores this form with a [`CASE_INSENSITIVE_PREFIX`] when it differs from the word.
+pub fn case_insensitive_form(word: &str) -> String {
+    word.to_lowercase()
+        .nfd()
+        .filter(|&c| !is_combining_mark(c))
+        .nfc()
+        .collect()
+}
+
+/// Parses a cspell word list, one word per line, into the words to store in a [`crate::Trie`].
+///
+/// The markers are kept the way cspell stores them in a trie: `!word` for forbidden words,
+/// `~word` for case-insensitive forms and `+` for compound parts. `*` markers are expanded into
+/// the word with and without `+`. Words with upper case letters or accents also get a `~` form
+/// from [`case_insensitive_form`]. `#` starts a comment.
+pub fn parse_word_list(src: &str) -> Vec<String> {
+    let mut words = vec![];
+    for line in src.lines() {
+        let line = match line.find(COMMENT) {
+            Some(i) => &line[..i],
+            None => line,
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        for word in expand_optional_compounds(line) {
+            let is_plain = !word.starts_with([FORBID_PREFIX, CASE_INSENSITIVE_PREFIX]);
+            let insensitive = case_insensitive_form(&word);
+            if is_plain && insensitive != word {
+                words.push(format!("{CASE_INSENSITIVE_PREFIX}{insensitive}"));
+            }
+            words.push(word);
+        }
+    }
+
+    words
+}
+
+/// Expands `*word*` into `word`, `+word`, `word+` and `+word+`.
+fn expand_optional_compounds(line: &str) -> Vec<String> {
+    let (prefix, line) = match line.strip_prefix([FORBID_PREFIX, CASE_INSENSITIVE_PREFIX]) {
+        Some(rest) => (&line[..1], rest),
+        None => ("", line),
+    };
+    let (starts, line) = match line.strip_prefix(OPTIONAL_COMPOUND_FIX) {
+        Some(rest) => (vec!["", "+"], rest),
+        None => (vec![""], line),
+    };
+    let (ends, word) = match line.strip_suffix(OPTIONAL_COMPOUND_FIX) {
+        Some(rest) => (vec!["", "+"], rest),
+        None => (vec![""], line),
+    };
+
+    let mut words = vec![];
+    for start in &starts {
+        for end in &ends {
+            words.push(format!("{prefix}{start}{word}{end}"));
+        }
+    }
+    words
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_word_list() {
+        let cases = [
+            ("hello\nworld\n", vec!["hello", "world"]),
+            ("# comment\n  hello  # trailing\n\n", vec!["hello"]),
+            ("Paris", vec!["~paris", "Paris"]),
+            ("Café", vec!["~cafe", "Café"]),
+            ("!alot\n~iphone", vec!["!alot", "~iphone"]),
+            ("un+\n+do\n+Able+", vec!["un+", "+do", "~+able+", "+Able+"]),
+            ("*work*", vec!["work", "work+", "+work", "+work+"]),
+            ("*pre\nfix*", vec!["pre", "+pre", "fix", "fix+"]),
+            ("!*bad", vec!["!bad", "!+bad"]),
+        ];
+
+        for (src, expected) in cases {
+            assert_eq!(expected, parse_word_list(src), "{src}");
+        }
+    }
+
+    #[test]
+    fn test_case_insensitive_form() {
+        let cases = [
+            ("hello", "hello"),
+            ("Paris", "paris"),
+            ("café", "cafe"),
+            ("Ångström", "angstrom"),
+            ("naïve", "naive"),
+        ];
+
+        for (word, expected) in cases {
+            assert_eq!(expected, case_insensitive_form(word), "{word}");
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{cli::CacheStrategy, config::Settings, lint::Issue};
+
+/// Results cached by other versions of spellrs are dropped.
+const CACHE_VERSION: &str = concat!("spellrs-", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    version: String,
+    /// The results by absolute path.
+    files: BTreeMap<String, CacheEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CacheEntry {
+    /// The size and modification time, or the hash of the content, of the file.
+    file_key: String,
+    /// See [`config_key`].
+    config_key: String,
+    issues: Vec<Issue>,
+}
+
+/// The results of the files checked by earlier runs, i.e. `.cspellcache`.
+///
+/// A result is only used when the file and its settings have not changed since it was cached.
+/// A missing or unreadable cache file is treated as empty.
+#[derive(Debug)]
+pub struct ResultCache {
+    path: PathBuf,
+    strategy: CacheStrategy,
+    cache: CacheFile,
+}
+
+impl ResultCache {
+    pub fn load(path: &Path, strategy: CacheStrategy) -> Self {
+        let cache = fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<CacheFile>(&json).ok())
+            .filter(|cache| cache.version == CACHE_VERSION)
+            .unwrap_or_else(|| CacheFile {
+                version: CACHE_VERSION.to_string(),
+                files: BTreeMap::new(),
+            });
+
+        Self {
+            path: path.to_path_buf(),
+            strategy,
+            cache,
+        }
+    }
+
+    /// Identifies the current version of a file with the cache strategy.
+    pub fn file_key(&self, path: &Path) -> io::Result<String> {
+        match self.strategy {
+            CacheStrategy::Metadata => Ok(metadata_key(path)?),
+            CacheStrategy::Content => Ok(hex(&Sha256::digest(fs::read(path)?))),
+        }
+    }
+
+    /// Returns the cached issues of a file, unless the file or its settings changed.
+    pub fn get(&self, path: &Path, file_key: &str, config_key: &str) -> Option<&[Issue]> {
+        self.cache
+            .files
+            .get(path.to_string_lossy().as_ref())
+            .filter(|entry| entry.file_key == file_key && entry.config_key == config_key)
+            .map(|entry| entry.issues.as_slice())
+    }
+
+    pub fn set(&mut self, path: &Path, file_key: String, config_key: String, issues: Vec<Issue>) {
+        let entry = CacheEntry {
+            file_key,
+            config_key,
+            issues,
+        };
+        self.cache
+            .files
+            .insert(path.to_string_lossy().into_owned(), entry);
+    }
+
+    /// Writes the cache file, dropping the results of the files that no longer exist.
+    pub fn save(&mut self) -> io::Result<()> {
+        self.cache.files.retain(|path, _| Path::new(path).exists());
+        fs::write(&self.path, serde_json::to_string(&self.cache)?)
+    }
+}
+
+/// The size and modification time of a file.
+fn metadata_key(path: &Path) -> io::Result<String> {
+    let metadata = fs::metadata(path)?;
+    let modified = metadata
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    Ok(format!("{}-{}", metadata.len(), modified.as_nanos()))
+}
+
+/// Identifies the effective settings of a file and the versions of its dictionaries, including
+/// the `extra` dictionaries from the command line. `suggest` is whether the issues include
+/// suggestions.
+///
+/// The key is a SHA-256 hash of the settings as JSON, so it is the same in every run and
+/// build. Dictionary files are identified by their size and modification time, so that a
+/// dictionary that changed invalidates the results checked against it. Hunspell `.dic` files
+/// include their `.aff` file.
+pub fn config_key(settings: &Settings, extra: &[PathBuf], suggest: bool) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(serde_json::to_vec(settings).unwrap_or_default());
+    hasher.update([suggest as u8]);
+
+    let dictionaries = settings
+        .enabled_dictionaries()
+        .into_iter()
+        .filter_map(|name| settings.dictionary_definition(name)?.path.as_ref())
+        .map(PathBuf::from)
+        .chain(extra.iter().cloned());
+    for path in dictionaries {
+        let aff = path
+            .extension()
+            .is_some_and(|ext| ext == "dic")
+            .then(|| path.with_extension("aff"));
+        for path in [Some(path), aff].into_iter().flatten() {
+            let metadata = metadata_key(&path).unwrap_or_default();
+            hasher.update(format!("\0{}\0{metadata}", path.display()));
+        }
+    }
+
+    hex(&hasher.finalize())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::lint::IssueKind;
+
+    fn issue(word: &str) -> Issue {
+        Issue {
+            word: word.to_string(),
+            offset: 0,
+            line: 1,
+            col: 1,
+            kind: IssueKind::UnknownWord,
+            suggestions: vec![],
+            fix: None,
+        }
+    }
+
+    #[test]
+    fn test_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_path = dir.path().join(".cspellcache");
+        let file = dir.path().join("a.txt");
+        let deleted = dir.path().join("b.txt");
+        fs::write(&file, "helo").unwrap();
+        fs::write(&deleted, "wrold").unwrap();
+
+        for strategy in [CacheStrategy::Metadata, CacheStrategy::Content] {
+            let mut cache = ResultCache::load(&cache_path, strategy);
+            let key = cache.file_key(&file).unwrap();
+            assert_eq!(None, cache.get(&file, &key, "config"));
+            cache.set(&file, key.clone(), "config".into(), vec![issue("helo")]);
+            let deleted_key = cache.file_key(&deleted).unwrap();
+            cache.set(&deleted, deleted_key, "config".into(), vec![issue("wrold")]);
+            fs::remove_file(&deleted).unwrap();
+            cache.save().unwrap();
+
+            let cache = ResultCache::load(&cache_path, strategy);
+            assert_eq!(Some(&[issue("helo")][..]), cache.get(&file, &key, "config"));
+            assert_eq!(None, cache.get(&file, &key, "other config"));
+            assert_eq!(1, cache.cache.files.len());
+            fs::write(&deleted, "wrold").unwrap();
+        }
+
+        // The content strategy only sees a change of content.
+        let cache = ResultCache::load(&cache_path, CacheStrategy::Content);
+        let key = cache.file_key(&file).unwrap();
+        fs::write(&file, "help").unwrap();
+        assert_ne!(key, cache.file_key(&file).unwrap());
+        fs::write(&file, "helo").unwrap();
+        assert_eq!(key, cache.file_key(&file).unwrap());
+
+        fs::write(&cache_path, "{ not json").unwrap();
+        let cache = ResultCache::load(&cache_path, CacheStrategy::Content);
+        assert!(cache.cache.files.is_empty());
+    }
+
+    #[test]
+    fn test_config_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let dic = dir.path().join("words.txt");
+        fs::write(&dic, "hello").unwrap();
+        let settings: Settings = serde_json::from_value(serde_json::json!({
+            "dictionaries": ["words"],
+            "dictionaryDefinitions": [{ "name": "words", "path": dic }],
+        }))
+        .unwrap();
+
+        let key = config_key(&settings, &[], false);
+        assert_eq!(key, config_key(&settings, &[], false));
+
+        let with_words = Settings {
+            words: vec!["spellrs".into()],
+            ..settings.clone()
+        };
+        assert_ne!(key, config_key(&with_words, &[], false));
+        assert_ne!(
+            key,
+            config_key(&settings, std::slice::from_ref(&dic), false)
+        );
+        assert_ne!(key, config_key(&settings, &[], true));
+
+        fs::write(&dic, "hello\nworld").unwrap();
+        assert_ne!(key, config_key(&settings, &[], false));
+
+        // A Hunspell dictionary changes with its `.aff` file.
+        let dic = dir.path().join("en.dic");
+        let aff = dir.path().join("en.aff");
+        fs::write(&dic, "1\nhello").unwrap();
+        fs::write(&aff, "").unwrap();
+        let key = config_key(&settings, std::slice::from_ref(&dic), false);
+        fs::write(&aff, "TRY abc").unwrap();
+        assert_ne!(key, config_key(&settings, &[dic], false));
+    }
+}
@@ -0,0 +1,173 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+#[derive(Debug, Parser)]
+#[command(name = "spellrs", about = "A spell checker for code", version)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Check the spelling of the files matching the globs.
+    Lint(LintArgs),
+    /// Suggest corrections for misspelled words.
+    Suggest(SuggestArgs),
+    /// Run a language server on stdin and stdout, for spelling diagnostics in editors.
+    Lsp(LspArgs),
+    /// Show which dictionaries have or forbid a word, and the settings that enabled them.
+    Trace(TraceArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct LintArgs {
+    /// Configuration file to use instead of searching for one.
+    #[arg(short, long, value_name = "cspell.json")]
+    pub config: Option<PathBuf>,
+
+    /// Dictionary to check against, either a Hunspell `.dic` file with the `.aff` file next to
+    /// it or a cspell `.trie` or `.trie.gz` file.
+    #[arg(short, long = "dictionary", value_name = "file")]
+    pub dictionaries: Vec<PathBuf>,
+
+    /// Include files and directories starting with `.`
+    #[arg(long)]
+    pub dot: bool,
+
+    /// Only check the files changed since this git commit, branch or tag, i.e. `origin/main`.
+    /// Changes count from where the current branch forked from it, and include uncommitted
+    /// changes and untracked files.
+    #[arg(long, value_name = "ref")]
+    pub changed_since: Option<String>,
+
+    /// Only report the issues on the lines added or changed since `--changed-since`.
+    #[arg(long, requires = "changed_since")]
+    pub changed_lines: bool,
+
+    /// Replace the misspelled words that have a single confident suggestion, or a replacement
+    /// in `flagWords` like `teh->the`. The case of the word is kept, so `TEH` becomes `THE`.
+    #[arg(long)]
+    pub fix: bool,
+
+    /// Ask whether to replace, ignore or add each misspelled word to the project words.
+    #[arg(short, long)]
+    pub interactive: bool,
+
+    /// Print the fixes as a unified diff instead of writing the files. The issues are reported
+    /// on stderr.
+    #[arg(long)]
+    pub diff: bool,
+
+    /// How to report the issues.
+    #[arg(long, value_enum, default_value_t = Reporter::Default)]
+    pub reporter: Reporter,
+
+    /// Do not print the summary.
+    #[arg(long)]
+    pub no_summary: bool,
+
+    /// Keep the results of each file and only check the files that changed since the last run.
+    #[arg(long)]
+    pub cache: bool,
+
+    /// Where to keep the results.
+    #[arg(long, value_name = "file", default_value = ".cspellcache")]
+    pub cache_location: PathBuf,
+
+    /// How to find the files that changed.
+    #[arg(long, value_enum, default_value_t = CacheStrategy::Metadata)]
+    pub cache_strategy: CacheStrategy,
+
+    /// Files or glob patterns to check, i.e. `"src/**/*.rs"`. Defaults to `files` from the
+    /// configuration. `stdin://path` checks stdin with the file type and configuration of
+    /// `path`, plain `stdin` checks it as plain text.
+    #[arg(value_name = "globs")]
+    pub globs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Reporter {
+    /// `file:line:col - message` lines.
+    Default,
+    /// The issues and their suggestions as a JSON object.
+    Json,
+    /// SARIF 2.1.0, for uploading to code scanning.
+    Sarif,
+    /// JUnit XML, with a test case per file.
+    Junit,
+    /// `::warning` workflow commands, shown as annotations by GitHub Actions.
+    Github,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CacheStrategy {
+    /// Compare the size and modification time of the files. Fast, but touching a file makes
+    /// it look changed.
+    Metadata,
+    /// Compare the content of the files.
+    Content,
+}
+
+#[derive(Debug, Args)]
+pub struct SuggestArgs {
+    /// Configuration file to use instead of searching for one.
+    #[arg(short, long, value_name = "cspell.json")]
+    pub config: Option<PathBuf>,
+
+    /// Dictionary to suggest words from, in addition to the ones from the configuration.
+    #[arg(short, long = "dictionary", value_name = "file")]
+    pub dictionaries: Vec<PathBuf>,
+
+    /// Use the settings for this file, i.e. its `languageSettings` and `overrides`.
+    #[arg(long, value_name = "file")]
+    pub file: Option<PathBuf>,
+
+    /// Number of suggestions per word. Defaults to `numSuggestions` or 10.
+    #[arg(short, long, value_name = "count")]
+    pub num_suggestions: Option<usize>,
+
+    /// Maximum number of edits of a suggestion. Defaults to `suggestionNumChanges` or 3.
+    #[arg(long, value_name = "count")]
+    pub num_changes: Option<usize>,
+
+    /// Time limit per word in milliseconds. Defaults to `suggestionsTimeout` or 500.
+    #[arg(long, value_name = "ms")]
+    pub timeout: Option<u64>,
+
+    /// Show the cost of each suggestion.
+    #[arg(short, long)]
+    pub verbose: bool,
+
+    /// Words to suggest corrections for.
+    #[arg(value_name = "words", required = true)]
+    pub words: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct TraceArgs {
+    /// Configuration file to use instead of searching for one.
+    #[arg(short, long, value_name = "cspell.json")]
+    pub config: Option<PathBuf>,
+
+    /// Dictionary to check against, in addition to the ones from the configuration.
+    #[arg(short, long = "dictionary", value_name = "file")]
+    pub dictionaries: Vec<PathBuf>,
+
+    /// Use the settings for this file, i.e. its `languageSettings` and `overrides`.
+    #[arg(long, value_name = "file")]
+    pub file: Option<PathBuf>,
+
+    /// Words to trace.
+    #[arg(value_name = "words", required = true)]
+    pub words: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct LspArgs {
+    /// Communicate over stdin and stdout. This is the only transport, the flag is accepted
+    /// because clients pass it.
+    #[arg(long)]
+    pub stdio: bool,
+}
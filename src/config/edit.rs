@@ -0,0 +1,256 @@
+use std::{fs, path::Path};
+
+use super::jsonc::strip_jsonc;
+
+/// Adds a word to the `words` of a configuration file. A missing file is created as a
+/// `cspell.json` with just the word.
+///
+/// JSON and YAML files are edited in place, so their comments and formatting are kept. The
+/// `cspell` section of a `package.json` is not supported.
+pub fn add_word(path: &Path, word: &str) -> Result<(), String> {
+    let error = |e: &dyn std::fmt::Display| format!("{}: {e}", path.display());
+    if !path.exists() {
+        let config = serde_json::json!({ "version": "0.2", "words": [word] });
+        let json = serde_json::to_string_pretty(&config).map_err(|e| error(&e))?;
+        return fs::write(path, json + "\n").map_err(|e| error(&e));
+    }
+
+    let src = fs::read_to_string(path).map_err(|e| error(&e))?;
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let edited = match path.extension().and_then(|ext| ext.to_str()) {
+        _ if name == "package.json" => Err("words can't be added to package.json".to_string()),
+        Some("json" | "jsonc") => add_word_to_json(&src, word),
+        Some("yaml" | "yml") => add_word_to_yaml(&src, word),
+        _ => Err("unsupported configuration file".to_string()),
+    };
+    fs::write(path, edited.map_err(|e| error(&e))?).map_err(|e| error(&e))
+}
+
+/// Appends a word to the top level `words` array, adding the array if there is none.
+fn add_word_to_json(src: &str, word: &str) -> Result<String, String> {
+    let body = src.trim_start_matches('\u{feff}');
+    let bom = &src[..src.len() - body.len()];
+    // Comments are blanked out, so offsets in `json` are the same as in `body`.
+    let json = strip_jsonc(body);
+    let bytes = json.as_bytes();
+    let quoted = serde_json::to_string(word).map_err(|e| e.to_string())?;
+
+    let start = skip_whitespace(bytes, 0);
+    if bytes.get(start) != Some(&b'{') {
+        return Err("the configuration is not a JSON object".to_string());
+    }
+
+    let (insert_at, insert) = match find_words_array(bytes, start) {
+        Some((open, close)) => {
+            let last = (open + 1..close)
+                .rev()
+                .find(|&i| !bytes[i].is_ascii_whitespace());
+            match last {
+                None => (open + 1, quoted),
+                Some(last) if json[open..close].contains('\n') => {
+                    let indent = line_indent(body, last);
+                    (last + 1, format!(",\n{indent}{quoted}"))
+                }
+                Some(last) => (last + 1, format!(", {quoted}")),
+            }
+        }
+        None => {
+            let next = skip_whitespace(bytes, start + 1);
+            let entry = format!("\"words\": [{quoted}]");
+            match (bytes.get(next), json[start..next].contains('\n')) {
+                (Some(b'}'), _) => (start + 1, entry),
+                (_, true) => (start + 1, format!("\n{}{entry},", line_indent(body, next))),
+                (_, false) => (start + 1, format!("{entry}, ")),
+            }
+        }
+    };
+
+    Ok(format!(
+        "{bom}{}{insert}{}",
+        &body[..insert_at],
+        &body[insert_at..]
+    ))
+}
+
+/// Finds the brackets of the `words` array of the object starting at `start`.
+fn find_words_array(bytes: &[u8], start: usize) -> Option<(usize, usize)> {
+    let mut depth = 0;
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                let end = string_end(bytes, i);
+                let colon = skip_whitespace(bytes, end);
+                if depth == 1 && &bytes[i..end] == b"\"words\"" && bytes.get(colon) == Some(&b':') {
+                    let open = skip_whitespace(bytes, colon + 1);
+                    return match bytes.get(open) {
+                        Some(b'[') => Some((open, matching_bracket(bytes, open)?)),
+                        _ => None,
+                    };
+                }
+                i = end;
+                continue;
+            }
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => depth -= 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// The index of the bracket closing the one at `open`.
+fn matching_bracket(bytes: &[u8], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = open;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                i = string_end(bytes, i);
+                continue;
+            }
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// The index after the string starting at `start`.
+fn string_end(bytes: &[u8], start: usize) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() && bytes[i] != b'"' {
+        i += match bytes[i] {
+            b'\\' => 2,
+            _ => 1,
+        };
+    }
+    (i + 1).min(bytes.len())
+}
+
+fn skip_whitespace(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+        i += 1;
+    }
+    i
+}
+
+/// The leading whitespace of the line containing `offset`.
+fn line_indent(text: &str, offset: usize) -> &str {
+    let line = &text[text[..offset].rfind('\n').map_or(0, |i| i + 1)..];
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// Appends a word to the top level `words` list, adding the list if there is none.
+fn add_word_to_yaml(src: &str, word: &str) -> Result<String, String> {
+    let mut lines: Vec<&str> = src.lines().collect();
+    let item;
+    match lines.iter().position(|line| line.starts_with("words:")) {
+        Some(i) if lines[i].trim_end() != "words:" => {
+            return Err("`words` is not a list of `- word` lines".to_string());
+        }
+        Some(i) => {
+            let items = lines[i + 1..]
+                .iter()
+                .take_while(|line| line.trim_start().starts_with("- "))
+                .count();
+            let indent = match items {
+                0 => "  ",
+                _ => line_indent(lines[i + 1], 0),
+            };
+            item = format!("{indent}- {word}");
+            lines.insert(i + 1 + items, &item);
+        }
+        None => {
+            item = format!("words:\n  - {word}");
+            lines.push(&item);
+        }
+    }
+    Ok(lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_add_word_to_json() {
+        let cases = [
+            ("{}", "{\"words\": [\"new\"]}"),
+            (
+                "{\"words\": []}",
+                "{\"words\": [\"new\"]}",
+            ),
+            (
+                "{\"words\": [\"old\"], \"flagWords\": [\"words\"]}",
+                "{\"words\": [\"old\", \"new\"], \"flagWords\": [\"words\"]}",
+            ),
+            (
+                "{\n  // \"words\": [],\n  \"words\": [\n    \"old\", // comment\n  ],\n}",
+                "{\n  // \"words\": [],\n  \"words\": [\n    \"old\",\n    \"new\", // comment\n  ],\n}",
+            ),
+            (
+                "{\n  \"version\": \"0.2\"\n}",
+                "{\n  \"words\": [\"new\"],\n  \"version\": \"0.2\"\n}",
+            ),
+            (
+                "{\"overrides\": [{\"words\": [\"old\"]}]}",
+                "{\"words\": [\"new\"], \"overrides\": [{\"words\": [\"old\"]}]}",
+            ),
+            ("\u{feff}{}", "\u{feff}{\"words\": [\"new\"]}"),
+        ];
+
+        for (src, expected) in cases {
+            assert_eq!(
+                Ok(expected.to_string()),
+                add_word_to_json(src, "new"),
+                "{src}"
+            );
+        }
+        assert!(add_word_to_json("[]", "new").is_err());
+    }
+
+    #[test]
+    fn test_add_word_to_yaml() {
+        let cases = [
+            ("version: '0.2'\n", "version: '0.2'\nwords:\n  - new\n"),
+            ("words:\n", "words:\n  - new\n"),
+            (
+                "words:\n    - old\nignorePaths: []\n",
+                "words:\n    - old\n    - new\nignorePaths: []\n",
+            ),
+        ];
+
+        for (src, expected) in cases {
+            assert_eq!(
+                Ok(expected.to_string()),
+                add_word_to_yaml(src, "new"),
+                "{src}"
+            );
+        }
+        assert!(add_word_to_yaml("words: [old]\n", "new").is_err());
+    }
+
+    #[test]
+    fn test_add_word() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cspell.json");
+        add_word(&path, "first").unwrap();
+        add_word(&path, "second").unwrap();
+        let config: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(serde_json::json!(["first", "second"]), config["words"]);
+
+        let package = dir.path().join("package.json");
+        fs::write(&package, "{}").unwrap();
+        assert!(add_word(&package, "word").is_err());
+    }
+}
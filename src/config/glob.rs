@@ -0,0 +1,75 @@
+use std::path::{Path, PathBuf};
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+use super::GlobDef;
+
+/// Matches file paths against cspell style globs.
+///
+/// Like `.gitignore`, a glob without a `/` matches a file or directory name at any depth, i.e.
+/// `target` or `*.log`. Other globs are relative to their root. Matching a directory matches
+/// everything inside of it.
+#[derive(Debug, Default)]
+pub struct GlobMatcher {
+    globs: Vec<(PathBuf, GlobSet)>,
+}
+
+impl GlobMatcher {
+    pub fn new<'a>(defs: impl IntoIterator<Item = &'a GlobDef>) -> Result<Self, globset::Error> {
+        let mut globs = vec![];
+        for def in defs {
+            let glob = def.glob.trim_end_matches('/');
+            let glob = match glob.strip_prefix('/') {
+                Some(glob) => glob.to_string(),
+                None if !glob.contains('/') => format!("**/{glob}"),
+                None => glob.strip_prefix("./").unwrap_or(glob).to_string(),
+            };
+
+            let mut set = GlobSetBuilder::new();
+            for pattern in [glob.clone(), format!("{glob}/**")] {
+                set.add(GlobBuilder::new(&pattern).literal_separator(true).build()?);
+            }
+            globs.push((def.root.clone(), set.build()?));
+        }
+
+        Ok(Self { globs })
+    }
+
+    /// Checks if an absolute path matches any of the globs.
+    pub fn is_match(&self, path: &Path) -> bool {
+        self.globs.iter().any(|(root, set)| {
+            path.strip_prefix(root)
+                .is_ok_and(|relative| set.is_match(relative))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_match() {
+        let glob = |glob: &str| GlobDef {
+            glob: glob.to_string(),
+            root: "/project".into(),
+        };
+        let cases = [
+            ("target", "/project/target/debug/main.rs", true),
+            ("target", "/project/sub/target/x.rs", true),
+            ("target", "/project/targets/x.rs", false),
+            ("*.log", "/project/a/b/c.log", true),
+            ("/src", "/project/src/main.rs", true),
+            ("/src", "/project/a/src/main.rs", false),
+            ("src/*.rs", "/project/src/main.rs", true),
+            ("src/*.rs", "/project/src/bin/main.rs", false),
+            ("./docs/", "/project/docs/readme.md", true),
+            ("*.log", "/elsewhere/c.log", false),
+        ];
+
+        for (def, path, expected) in cases {
+            let matcher = GlobMatcher::new([&glob(def)]).unwrap();
+            assert_eq!(expected, matcher.is_match(Path::new(path)), "{def} {path}");
+        }
+    }
+}
@@ -0,0 +1,88 @@
+/// Converts JSON with comments (JSONC) into plain JSON.
+///
+/// `//` and `/* */` comments are replaced with spaces and trailing commas are removed, so byte
+/// offsets and line numbers in error messages still match the original text.
+pub fn strip_jsonc(src: &str) -> String {
+    let src = src.trim_start_matches('\u{feff}');
+    let mut out: Vec<u8> = Vec::with_capacity(src.len());
+    let bytes = src.as_bytes();
+    // The index in `out` of a comma that is trailing if the next token closes the object or array.
+    let mut last_comma: Option<usize> = None;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match (bytes[i], bytes.get(i + 1)) {
+            (b'"', _) => {
+                let start = i;
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += match bytes[i] {
+                        b'\\' => 2,
+                        _ => 1,
+                    };
+                }
+                i = (i + 1).min(bytes.len());
+                out.extend_from_slice(&bytes[start..i]);
+                last_comma = None;
+                continue;
+            }
+            (b'/', Some(b'/')) => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    out.push(b' ');
+                    i += 1;
+                }
+                continue;
+            }
+            (b'/', Some(b'*')) => {
+                let end = src[i + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |j| i + 2 + j + 2);
+                out.extend(bytes[i..end].iter().map(|&b| match b {
+                    b'\n' | b'\r' => b,
+                    _ => b' ',
+                }));
+                i = end;
+                continue;
+            }
+            (b',', _) => last_comma = Some(out.len()),
+            (b'}' | b']', _) => {
+                if let Some(comma) = last_comma.take() {
+                    out[comma] = b' ';
+                }
+            }
+            (b, _) if b.is_ascii_whitespace() => {}
+            _ => last_comma = None,
+        }
+
+        out.push(bytes[i]);
+        i += 1;
+    }
+
+    // Only ASCII bytes are replaced, so the result is still valid UTF-8.
+    String::from_utf8(out).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_strip_jsonc() {
+        let cases = [
+            (r#"{"a": 1}"#, r#"{"a": 1}"#),
+            ("{\"a\": 1 // one\n}", "{\"a\": 1       \n}"),
+            (r#"{"a": /* one */ 1}"#, r#"{"a":           1}"#),
+            ("{\"a\": [1, 2,],\n}", "{\"a\": [1, 2 ] \n}"),
+            (
+                r#"{"url": "http://x.com/*", "b": "\"//\""}"#,
+                r#"{"url": "http://x.com/*", "b": "\"//\""}"#,
+            ),
+            ("{\"a\": \"é\", /* é */}", "{\"a\": \"é\"          }"),
+        ];
+
+        for (src, expected) in cases {
+            assert_eq!(expected, strip_jsonc(src), "{src}");
+            assert!(serde_json::from_str::<serde_json::Value>(&strip_jsonc(src)).is_ok());
+        }
+    }
+}
@@ -0,0 +1,428 @@
+use std::{
+    collections::HashMap,
+    fmt, fs, mem,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use serde::Deserialize;
+use spellrs_js::NodePath;
+use spellrs_url::{file_url_to_path, FileUrlBuilder, StUrl};
+use url::Url;
+
+use super::{jsonc::strip_jsonc, Settings};
+
+/// The configuration file names, in the order cspell searches for them.
+///
+/// `package.json` only counts when it has a `cspell` section.
+pub const CONFIG_FILE_NAMES: [&str; 30] = [
+    "package.json",
+    ".cspell.json",
+    "cspell.json",
+    ".cSpell.json",
+    "cSpell.json",
+    ".cspell.jsonc",
+    "cspell.jsonc",
+    ".cspell.yaml",
+    "cspell.yaml",
+    ".cspell.yml",
+    "cspell.yml",
+    ".cspell.config.json",
+    "cspell.config.json",
+    ".cspell.config.jsonc",
+    "cspell.config.jsonc",
+    ".cspell.config.yaml",
+    "cspell.config.yaml",
+    ".cspell.config.yml",
+    "cspell.config.yml",
+    ".config/.cspell.json",
+    ".config/cspell.json",
+    ".config/.cSpell.json",
+    ".config/cSpell.json",
+    ".config/.cspell.jsonc",
+    ".config/cspell.jsonc",
+    ".config/cspell.yaml",
+    ".config/cspell.yml",
+    ".vscode/.cspell.json",
+    ".vscode/cSpell.json",
+    ".vscode/cspell.json",
+];
+
+/// A configuration file that could not be read or imported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+/// Finds and loads cspell configuration files.
+///
+/// Like cspell, problems with a configuration file don't stop spell checking. The file is
+/// treated as empty and the error is kept for [`ConfigLoader::take_errors`].
+#[derive(Debug, Default)]
+pub struct ConfigLoader {
+    node_path: NodePath,
+    urls: FileUrlBuilder,
+    /// Loaded files, with their imports merged in.
+    configs: HashMap<PathBuf, Arc<Settings>>,
+    /// The nearest configuration file of each searched directory.
+    searched: HashMap<PathBuf, Option<PathBuf>>,
+    errors: Vec<ConfigError>,
+}
+
+impl ConfigLoader {
+    /// Finds the nearest configuration file, searching `dir` and then its parents.
+    pub fn search(&mut self, dir: &Path) -> Option<PathBuf> {
+        let mut current = self.node_path.resolve(&[&dir.to_string_lossy()]);
+        let mut visited = vec![];
+
+        let found = loop {
+            if let Some(found) = self.searched.get(Path::new(&current)) {
+                break found.clone();
+            }
+            visited.push(PathBuf::from(&current));
+
+            let config = CONFIG_FILE_NAMES
+                .iter()
+                .map(|name| Path::new(&current).join(name))
+                .find(|path| is_config_file(path));
+            if config.is_some() {
+                break config;
+            }
+
+            let parent = self.node_path.parse(&current).dir;
+            if parent == current {
+                break None;
+            }
+            current = parent;
+        };
+
+        for dir in visited {
+            self.searched.insert(dir, found.clone());
+        }
+        found
+    }
+
+    /// Loads a configuration file. Imported files are merged in before the file's own settings.
+    pub fn load(&mut self, path: &Path) -> Arc<Settings> {
+        let path = PathBuf::from(self.node_path.resolve(&[&path.to_string_lossy()]));
+        if let Some(settings) = self.configs.get(&path) {
+            return settings.clone();
+        }
+
+        let settings = Arc::new(merge_sources(&self.sources(&path)));
+        self.configs.insert(path, settings.clone());
+        settings
+    }
+
+    /// The files a configuration file is merged from, imports first and the file last, each
+    /// with its own settings. Files that fail to load are left out.
+    pub fn sources(&mut self, path: &Path) -> Vec<(PathBuf, Settings)> {
+        let path = PathBuf::from(self.node_path.resolve(&[&path.to_string_lossy()]));
+        let mut sources = vec![];
+        self.load_with_imports(&path, &mut vec![], &mut sources);
+        sources
+    }
+
+    /// Returns the errors found since the last call.
+    pub fn take_errors(&mut self) -> Vec<ConfigError> {
+        mem::take(&mut self.errors)
+    }
+
+    fn error(&mut self, path: &Path, message: impl Into<String>) {
+        self.errors.push(ConfigError {
+            path: path.to_path_buf(),
+            message: message.into(),
+        });
+    }
+
+    fn load_with_imports(
+        &mut self,
+        path: &Path,
+        stack: &mut Vec<PathBuf>,
+        sources: &mut Vec<(PathBuf, Settings)>,
+    ) {
+        if stack.iter().any(|p| p == path) {
+            self.error(path, "circular import");
+            return;
+        }
+
+        let mut settings = match read_settings(path) {
+            Ok(settings) => settings,
+            Err(message) => {
+                self.error(path, message);
+                return;
+            }
+        };
+        let dir = path.parent().unwrap_or(Path::new("/"));
+        settings.set_root(dir);
+
+        let url = self
+            .urls
+            .to_file_url(&StUrl::String(path.to_string_lossy().into_owned()), None);
+
+        stack.push(path.to_path_buf());
+        for import in mem::take(&mut settings.import) {
+            match self.resolve_import(&import, &url, dir) {
+                Some(import_path) => self.load_with_imports(&import_path, stack, sources),
+                None => self.error(path, format!("failed to resolve import `{import}`")),
+            }
+        }
+        stack.pop();
+
+        sources.push((path.to_path_buf(), settings));
+    }
+
+    /// Resolves an import relative to the URL of the importing file. Imports that are not
+    /// relative are also looked for in `node_modules`, i.e. `@cspell/dict-de-de/cspell-ext.json`.
+    fn resolve_import(&self, import: &str, config_url: &Url, dir: &Path) -> Option<PathBuf> {
+        let url = self
+            .urls
+            .to_file_url(&StUrl::Str(import), Some(&StUrl::Url(config_url.clone())));
+        if let Ok(path) = file_url_to_path(url.as_str()) {
+            if path.is_file() {
+                return Some(path);
+            }
+        }
+
+        if import.starts_with('.') || self.node_path.is_absolute(import) {
+            return None;
+        }
+
+        dir.ancestors()
+            .map(|dir| dir.join("node_modules").join(import))
+            .find_map(|path| match path.is_dir() {
+                true => package_main(&path),
+                false => path.is_file().then_some(path),
+            })
+    }
+}
+
+fn is_config_file(path: &Path) -> bool {
+    match path.file_name().is_some_and(|name| name == "package.json") {
+        true => package_json(path).is_some_and(|value| value.get("cspell").is_some()),
+        false => path.is_file(),
+    }
+}
+
+fn package_json(path: &Path) -> Option<serde_json::Value> {
+    let src = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&src).ok()
+}
+
+/// The configuration file of a package, given by `main` in its `package.json`.
+fn package_main(dir: &Path) -> Option<PathBuf> {
+    let main = package_json(&dir.join("package.json"))
+        .and_then(|value| value.get("main")?.as_str().map(String::from))
+        .unwrap_or_else(|| "cspell-ext.json".to_string());
+    let path = dir.join(main);
+    path.is_file().then_some(path)
+}
+
+/// Reads a configuration file based on its extension. JSON files may contain comments.
+pub fn read_settings(path: &Path) -> Result<Settings, String> {
+    let src = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let is_yaml = path
+        .extension()
+        .is_some_and(|ext| ext == "yaml" || ext == "yml");
+
+    if path.file_name().is_some_and(|name| name == "package.json") {
+        let value: serde_json::Value = serde_json::from_str(&src).map_err(|e| e.to_string())?;
+        return match value.get("cspell") {
+            Some(cspell) => Settings::deserialize(cspell).map_err(|e| e.to_string()),
+            None => Ok(Settings::default()),
+        };
+    }
+
+    match is_yaml {
+        true if src.trim().is_empty() => Ok(Settings::default()),
+        true => serde_yaml::from_str(&src).map_err(|e| e.to_string()),
+        false => serde_json::from_str(&strip_jsonc(&src)).map_err(|e| e.to_string()),
+    }
+}
+
+/// Merges the settings of [`ConfigLoader::sources`] into the settings of the configuration,
+/// like [`ConfigLoader::load`].
+pub fn merge_sources(sources: &[(PathBuf, Settings)]) -> Settings {
+    sources
+        .iter()
+        .fold(Settings::default(), |merged, (_, settings)| {
+            merged.merge(settings)
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn write(root: &Path, files: &[(&str, &str)]) {
+        for (name, src) in files {
+            let path = root.join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, src).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_read_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            &[
+                ("cspell.json", r#"{ "words": ["json"] }"#),
+                (
+                    "cspell.jsonc",
+                    "{\n  // comment\n  \"words\": [\"jsonc\",],\n}",
+                ),
+                ("cspell.yaml", "words:\n  - yaml\nflagWords: [teh]\n"),
+                ("empty.yml", ""),
+                (
+                    "package.json",
+                    r#"{ "name": "x", "cspell": { "words": ["pkg"] } }"#,
+                ),
+                ("bad.json", r#"{ "words": "#),
+            ],
+        );
+
+        let cases = [
+            ("cspell.json", vec!["json"]),
+            ("cspell.jsonc", vec!["jsonc"]),
+            ("cspell.yaml", vec!["yaml"]),
+            ("empty.yml", vec![]),
+            ("package.json", vec!["pkg"]),
+        ];
+
+        for (name, expected) in cases {
+            let settings = read_settings(&root.join(name)).unwrap();
+            assert_eq!(expected, settings.words, "{name}");
+        }
+        assert!(read_settings(&root.join("bad.json")).is_err());
+        assert!(read_settings(&root.join("missing.json")).is_err());
+    }
+
+    #[test]
+    fn test_search() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            &[
+                ("cspell.json", "{}"),
+                ("a/b/c/file.txt", ""),
+                ("a/.vscode/cspell.json", "{}"),
+                ("pkg/package.json", r#"{ "name": "no-config" }"#),
+                ("pkg2/package.json", r#"{ "cspell": {} }"#),
+                ("pkg2/cspell.json", "{}"),
+            ],
+        );
+
+        let mut loader = ConfigLoader::default();
+        let cases = [
+            ("", "cspell.json"),
+            ("a/b/c", "a/.vscode/cspell.json"),
+            ("a/b", "a/.vscode/cspell.json"),
+            ("pkg", "cspell.json"),
+            ("pkg2", "pkg2/package.json"),
+        ];
+
+        for (dir, expected) in cases {
+            let found = loader.search(&root.join(dir));
+            assert_eq!(Some(root.join(expected)), found, "{dir}");
+        }
+    }
+
+    #[test]
+    fn test_load_imports() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            &[
+                (
+                    "cspell.json",
+                    r#"{ "import": ["./config/base.yaml", "@org/words"], "words": ["main"], "minWordLength": 5 }"#,
+                ),
+                (
+                    "config/base.yaml",
+                    "import: ../shared/common.json\nwords: [base]\nminWordLength: 3\nlanguage: fr\n",
+                ),
+                (
+                    "shared/common.json",
+                    r#"{ "words": ["common"], "language": "en", "dictionaryDefinitions": [{ "name": "custom", "path": "./custom.dic" }] }"#,
+                ),
+                ("node_modules/@org/words/package.json", r#"{ "main": "ext.json" }"#),
+                ("node_modules/@org/words/ext.json", r#"{ "words": ["package"] }"#),
+            ],
+        );
+
+        let mut loader = ConfigLoader::default();
+        let settings = loader.load(&root.join("cspell.json"));
+        assert_eq!(Vec::<ConfigError>::new(), loader.take_errors());
+        assert_eq!(vec!["common", "base", "package", "main"], settings.words);
+        assert_eq!(Some(5), settings.min_word_length);
+        assert_eq!(Some("fr".to_string()), settings.language);
+
+        let sources = loader.sources(&root.join("cspell.json"));
+        assert_eq!(*settings, merge_sources(&sources));
+        let sources: Vec<PathBuf> = sources.into_iter().map(|(path, _)| path).collect();
+        let expected = [
+            "shared/common.json",
+            "config/base.yaml",
+            "node_modules/@org/words/ext.json",
+            "cspell.json",
+        ]
+        .map(|path| root.join(path));
+        assert_eq!(expected.to_vec(), sources);
+
+        let custom = settings.dictionary_definition("custom").unwrap();
+        let expected = root.join("shared").join("custom.dic");
+        assert_eq!(
+            Some(expected.to_string_lossy().as_ref()),
+            custom.path.as_deref()
+        );
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            &[
+                (
+                    "a.json",
+                    r#"{ "import": ["./b.json", "./missing.json"], "words": ["a"] }"#,
+                ),
+                ("b.json", r#"{ "import": "./a.json", "words": ["b"] }"#),
+                ("bad.json", "{"),
+            ],
+        );
+
+        let mut loader = ConfigLoader::default();
+        let settings = loader.load(&root.join("a.json"));
+        assert_eq!(vec!["b", "a"], settings.words);
+
+        let errors: Vec<String> = loader
+            .take_errors()
+            .iter()
+            .map(|e| e.message.clone())
+            .collect();
+        assert_eq!(
+            vec![
+                "circular import",
+                "failed to resolve import `./missing.json`"
+            ],
+            errors
+        );
+
+        assert_eq!(Settings::default(), *loader.load(&root.join("bad.json")));
+        assert_eq!(1, loader.take_errors().len());
+    }
+}
@@ -0,0 +1,10 @@
+mod edit;
+mod glob;
+mod jsonc;
+mod loader;
+mod settings;
+
+pub use edit::add_word;
+pub use glob::GlobMatcher;
+pub use loader::{merge_sources, ConfigLoader, CONFIG_FILE_NAMES};
+pub use settings::{DictionaryDefinition, GlobDef, LanguageSetting, PatternDefinition, Settings};
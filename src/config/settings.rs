@@ -0,0 +1,637 @@
+use std::{
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{Deserialize, Deserializer, Serialize};
+use spellrs_filetype::FileTypeId;
+use spellrs_trie::{EditCosts, SuggestOptions};
+
+use super::GlobMatcher;
+
+/// The settings of a cspell configuration file, i.e. `cspell.json`.
+///
+/// Only the settings understood by spellrs are read, everything else is ignored. See the
+/// [cspell docs](https://cspell.org/configuration/) for the meaning of each setting.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    /// Other configuration files to merge in, relative to this one.
+    #[serde(deserialize_with = "one_or_many")]
+    pub import: Vec<String>,
+    /// `false` turns off spell checking.
+    pub enabled: Option<bool>,
+    /// The locales to check against, i.e. `en,fr`.
+    pub language: Option<String>,
+    /// Words to be considered correct.
+    pub words: Vec<String>,
+    /// Same as `words`.
+    pub user_words: Vec<String>,
+    /// Words to be ignored. Unlike `words`, these are never suggested.
+    pub ignore_words: Vec<String>,
+    /// Words that are always reported, even if a dictionary has them.
+    pub flag_words: Vec<String>,
+    /// Preferred suggestions for words, i.e. `colour->color` or `teh: the, then`.
+    pub suggest_words: Vec<String>,
+    /// The names of the dictionaries to use. A leading `!` removes a dictionary.
+    pub dictionaries: Vec<String>,
+    pub dictionary_definitions: Vec<DictionaryDefinition>,
+    /// Globs of the files to check when none are given on the command line.
+    pub files: Vec<GlobDef>,
+    /// Globs of the files to skip.
+    pub ignore_paths: Vec<GlobDef>,
+    /// Regular expressions or pattern names of the text to skip, i.e. `Urls` or
+    /// `/0x[0-9a-f]+/gi`.
+    pub ignore_reg_exp_list: Vec<String>,
+    /// Regular expressions or pattern names of the text to check. Everything else is skipped.
+    pub include_reg_exp_list: Vec<String>,
+    /// Named regular expressions for `ignoreRegExpList` and `includeRegExpList`.
+    pub patterns: Vec<PatternDefinition>,
+    /// Words shorter than this are not checked.
+    pub min_word_length: Option<usize>,
+    /// `true` checks the case and accents of words, so `Iphone` does not match `iPhone`.
+    /// Defaults to `false`, where `paris` matches `Paris` and `cafe` matches `café`.
+    pub case_sensitive: Option<bool>,
+    /// The maximum number of suggestions for a word.
+    pub num_suggestions: Option<usize>,
+    /// The maximum number of edits to get from a word to a suggestion.
+    pub suggestion_num_changes: Option<usize>,
+    /// The time limit for finding suggestions for a word, in milliseconds.
+    pub suggestions_timeout: Option<u64>,
+    /// Settings for the files matching a glob.
+    pub overrides: Vec<OverrideSettings>,
+    /// Forces the file type, i.e. `markdown`, instead of detecting it from the file name.
+    pub language_id: Option<String>,
+    /// Settings for the files of a file type.
+    pub language_settings: Vec<LanguageSetting>,
+}
+
+/// Where to find a custom dictionary.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DictionaryDefinition {
+    pub name: String,
+    /// Relative to the configuration file that defines it. Made absolute when loaded.
+    pub path: Option<String>,
+    pub description: Option<String>,
+    /// Overrides `caseSensitive` of the settings for this dictionary.
+    pub case_sensitive: Option<bool>,
+    pub dictionary_information: Option<DictionaryInformation>,
+}
+
+/// Information about the language of a dictionary, used to rank suggestions.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DictionaryInformation {
+    pub suggestion_edit_costs: Vec<SuggestionEditCost>,
+}
+
+/// The costs of the edits between groups of characters, i.e. making vowels cheaper to replace.
+///
+/// `map` has groups of characters separated by `|`, with sequences in parentheses, i.e.
+/// `aeiou|(ph)f`. Costs are relative to 100, the cost of replacing any character.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SuggestionEditCost {
+    pub map: String,
+    pub replace: Option<u32>,
+    pub ins_del: Option<u32>,
+    pub swap: Option<u32>,
+    pub penalty: Option<u32>,
+    pub description: Option<String>,
+}
+
+impl From<&SuggestionEditCost> for EditCosts {
+    fn from(cost: &SuggestionEditCost) -> Self {
+        EditCosts {
+            map: cost.map.clone(),
+            replace: cost.replace,
+            ins_del: cost.ins_del,
+            swap: cost.swap,
+            penalty: cost.penalty,
+        }
+    }
+}
+
+/// A named regular expression, or a list of them, i.e. `{ "name": "Todo", "pattern": "TODO.*" }`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PatternDefinition {
+    pub name: String,
+    #[serde(deserialize_with = "one_or_many")]
+    pub pattern: Vec<String>,
+    pub description: Option<String>,
+}
+
+/// A glob and the directory it is relative to.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "String")]
+pub struct GlobDef {
+    pub glob: String,
+    /// The directory of the configuration file that defines the glob. Set when loaded.
+    pub root: PathBuf,
+}
+
+impl From<String> for GlobDef {
+    fn from(glob: String) -> Self {
+        Self {
+            glob,
+            root: PathBuf::new(),
+        }
+    }
+}
+
+/// Settings applied to the files matching `filename`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OverrideSettings {
+    #[serde(deserialize_with = "one_or_many")]
+    pub filename: Vec<GlobDef>,
+    #[serde(flatten)]
+    pub settings: Settings,
+}
+
+/// Settings applied to the files of the file types in `language_id`, i.e. `typescript`.
+///
+/// Both `language_id` and `locale` can hold several comma separated values. An empty list or
+/// `*` matches everything and a leading `!` excludes a value.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LanguageSetting {
+    #[serde(deserialize_with = "one_or_many")]
+    pub language_id: Vec<String>,
+    #[serde(alias = "local", deserialize_with = "one_or_many")]
+    pub locale: Vec<String>,
+    #[serde(flatten)]
+    pub settings: Settings,
+}
+
+impl LanguageSetting {
+    /// Whether the block applies to a file of the given file types, with the `languageId` and
+    /// `language` of the settings of the file. See [`Settings::for_language`].
+    pub fn applies_to(&self, settings: &Settings, file_types: &[FileTypeId]) -> bool {
+        let file_types: Vec<&str> = match &settings.language_id {
+            Some(id) => split_list([id.as_str()]),
+            None => file_types.iter().map(String::as_str).collect(),
+        };
+        let language = settings.language.as_deref().unwrap_or("en");
+        let locales: Vec<String> = split_list([language])
+            .into_iter()
+            .map(normalize_locale)
+            .collect();
+        self.matches(&file_types, &locales)
+    }
+
+    fn matches(&self, file_types: &[&str], locales: &[String]) -> bool {
+        let language_ids = split_list(self.language_id.iter().map(String::as_str));
+        let block_locales: Vec<String> = split_list(self.locale.iter().map(String::as_str))
+            .into_iter()
+            .map(normalize_locale)
+            .collect();
+
+        matches_any(&language_ids, |id| file_types.contains(&id))
+            && matches_any(&block_locales, |locale| {
+                locales.iter().any(|l| is_same_locale(l, locale))
+            })
+    }
+}
+
+/// Splits comma separated values, i.e. `["typescript,javascript"]`.
+fn split_list<'a>(values: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    values
+        .into_iter()
+        .flat_map(|v| v.split(','))
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+/// Checks a list of values that may contain `*` and `!value` exclusions.
+fn matches_any(values: &[impl AsRef<str>], is_match: impl Fn(&str) -> bool) -> bool {
+    let (excluded, included): (Vec<&str>, Vec<&str>) = values
+        .iter()
+        .map(AsRef::as_ref)
+        .partition(|v| v.starts_with('!'));
+
+    let included = included.is_empty() || included.iter().any(|v| *v == "*" || is_match(v));
+    included && !excluded.iter().any(|v| is_match(&v[1..]))
+}
+
+/// Normalizes a locale so that `en_US` and `en-us` are the same.
+fn normalize_locale(locale: &str) -> String {
+    locale.trim().replace('_', "-").to_lowercase()
+}
+
+/// `en` matches any English locale, while `en-us` and `en-gb` only match themselves.
+fn is_same_locale(a: &str, b: &str) -> bool {
+    let language = |l: &str| l.split('-').next().unwrap_or_default().to_string();
+    a == b || (!a.contains('-') || !b.contains('-')) && language(a) == language(b)
+}
+
+fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany<T> {
+        One(T),
+        Many(Vec<T>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
+
+impl Settings {
+    /// Merges `other` on top of `self` the same way cspell does. Lists are combined, keeping
+    /// the first of any duplicates. Single values are replaced when they are set in `other`.
+    pub fn merge(&self, other: &Settings) -> Settings {
+        Settings {
+            import: vec![],
+            enabled: other.enabled.or(self.enabled),
+            language: other.language.clone().or_else(|| self.language.clone()),
+            words: merge_unique(&self.words, &other.words),
+            user_words: merge_unique(&self.user_words, &other.user_words),
+            ignore_words: merge_unique(&self.ignore_words, &other.ignore_words),
+            flag_words: merge_unique(&self.flag_words, &other.flag_words),
+            suggest_words: merge_unique(&self.suggest_words, &other.suggest_words),
+            dictionaries: merge_unique(&self.dictionaries, &other.dictionaries),
+            dictionary_definitions: merge_unique(
+                &self.dictionary_definitions,
+                &other.dictionary_definitions,
+            ),
+            files: merge_unique(&self.files, &other.files),
+            ignore_paths: merge_unique(&self.ignore_paths, &other.ignore_paths),
+            ignore_reg_exp_list: merge_unique(
+                &self.ignore_reg_exp_list,
+                &other.ignore_reg_exp_list,
+            ),
+            include_reg_exp_list: merge_unique(
+                &self.include_reg_exp_list,
+                &other.include_reg_exp_list,
+            ),
+            patterns: merge_unique(&self.patterns, &other.patterns),
+            min_word_length: other.min_word_length.or(self.min_word_length),
+            case_sensitive: other.case_sensitive.or(self.case_sensitive),
+            num_suggestions: other.num_suggestions.or(self.num_suggestions),
+            suggestion_num_changes: other.suggestion_num_changes.or(self.suggestion_num_changes),
+            suggestions_timeout: other.suggestions_timeout.or(self.suggestions_timeout),
+            overrides: merge_unique(&self.overrides, &other.overrides),
+            language_id: other
+                .language_id
+                .clone()
+                .or_else(|| self.language_id.clone()),
+            language_settings: merge_unique(&self.language_settings, &other.language_settings),
+        }
+    }
+
+    /// Sets the root of the globs and makes the dictionary paths absolute.
+    pub(crate) fn set_root(&mut self, root: &Path) {
+        let globs = self.files.iter_mut().chain(&mut self.ignore_paths);
+        for glob in globs.filter(|g| g.root.as_os_str().is_empty()) {
+            glob.root = root.to_path_buf();
+        }
+
+        for def in &mut self.dictionary_definitions {
+            if let Some(path) = &def.path {
+                let path: PathBuf = root
+                    .join(path)
+                    .components()
+                    .filter(|c| c != &Component::CurDir)
+                    .collect();
+                def.path = Some(path.to_string_lossy().into_owned());
+            }
+        }
+
+        for o in &mut self.overrides {
+            for glob in o
+                .filename
+                .iter_mut()
+                .filter(|g| g.root.as_os_str().is_empty())
+            {
+                glob.root = root.to_path_buf();
+            }
+            o.settings.set_root(root);
+        }
+
+        for language in &mut self.language_settings {
+            language.settings.set_root(root);
+        }
+    }
+
+    /// The settings for a file, with the matching `overrides` merged in order.
+    pub fn for_file(&self, path: &Path) -> Settings {
+        self.overrides
+            .iter()
+            .filter(|o| GlobMatcher::new(&o.filename).is_ok_and(|m| m.is_match(path)))
+            .fold(self.clone(), |settings, o| settings.merge(&o.settings))
+    }
+
+    /// The settings for a file of the given file types, with every matching `languageSettings`
+    /// block merged in order. `languageId` replaces the file types and blocks with a `locale`
+    /// only apply when it matches `language`, which defaults to `en`.
+    pub fn for_language(&self, file_types: &[FileTypeId]) -> Settings {
+        self.language_settings
+            .iter()
+            .filter(|l| l.applies_to(self, file_types))
+            .fold(self.clone(), |settings, l| settings.merge(&l.settings))
+    }
+
+    /// The names of the enabled dictionaries, after applying `!name` removals in order.
+    /// `!!name` adds a removed dictionary back.
+    pub fn enabled_dictionaries(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = vec![];
+        for name in &self.dictionaries {
+            match (name.strip_prefix("!!"), name.strip_prefix('!')) {
+                (Some(name), _) => names.push(name),
+                (None, Some(name)) => names.retain(|n| *n != name),
+                (None, None) => names.push(name),
+            }
+        }
+
+        let mut unique = vec![];
+        for name in names {
+            if !unique.contains(&name) {
+                unique.push(name);
+            }
+        }
+        unique
+    }
+
+    /// The preferred suggestions from `suggestWords` and the replacements from `flagWords`, by
+    /// word. Later entries replace earlier ones, and `suggestWords` replace `flagWords`.
+    ///
+    /// An entry is either `word: suggestion` or `word->suggestion`, with several suggestions
+    /// separated by commas.
+    pub fn suggest_word_map(&self) -> HashMap<String, Vec<String>> {
+        self.flag_words
+            .iter()
+            .chain(&self.suggest_words)
+            .filter_map(|entry| parse_suggest_word(entry))
+            .collect()
+    }
+
+    /// The forbidden words of `flagWords`, without their replacements, i.e. `teh` for
+    /// `teh->the`.
+    pub fn forbidden_words(&self) -> Vec<String> {
+        self.flag_words
+            .iter()
+            .map(|entry| match parse_suggest_word(entry) {
+                Some((word, _)) => word,
+                None => entry.trim().to_string(),
+            })
+            .collect()
+    }
+
+    /// The suggestion options from `numSuggestions`, `suggestionNumChanges` and
+    /// `suggestionsTimeout`, with the defaults for the missing ones.
+    pub fn suggest_options(&self) -> SuggestOptions {
+        let defaults = SuggestOptions::default();
+        SuggestOptions {
+            num_suggestions: self.num_suggestions.unwrap_or(defaults.num_suggestions),
+            num_changes: self.suggestion_num_changes.unwrap_or(defaults.num_changes),
+            timeout: self
+                .suggestions_timeout
+                .map_or(defaults.timeout, Duration::from_millis),
+        }
+    }
+
+    /// Finds a dictionary definition by name. Later definitions replace earlier ones.
+    pub fn dictionary_definition(&self, name: &str) -> Option<&DictionaryDefinition> {
+        self.dictionary_definitions
+            .iter()
+            .rev()
+            .find(|def| def.name == name)
+    }
+}
+
+/// Splits `word: first, second` or `word->first, second` into the word and its suggestions.
+fn parse_suggest_word(entry: &str) -> Option<(String, Vec<String>)> {
+    let (word, suggestions) = entry.split_once("->").or_else(|| entry.split_once(':'))?;
+    let word = word.trim();
+    let suggestions: Vec<String> = suggestions
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect();
+
+    match word.is_empty() || suggestions.is_empty() {
+        true => None,
+        false => Some((word.to_string(), suggestions)),
+    }
+}
+
+fn merge_unique<T: Clone + PartialEq>(left: &[T], right: &[T]) -> Vec<T> {
+    let mut merged = left.to_vec();
+    for item in right {
+        if !merged.contains(item) {
+            merged.push(item.clone());
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_deserialize() {
+        let settings: Settings = serde_json::from_str(
+            r#"{
+                "version": "0.2",
+                "import": "./base.json",
+                "words": ["spellrs"],
+                "flagWords": ["teh"],
+                "minWordLength": 3,
+                "ignorePaths": ["target"],
+                "overrides": [{ "filename": "*.md", "words": ["markdown"], "enabled": false }]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(vec!["./base.json"], settings.import);
+        assert_eq!(vec!["spellrs"], settings.words);
+        assert_eq!(vec!["teh"], settings.flag_words);
+        assert_eq!(Some(3), settings.min_word_length);
+        assert_eq!("target", settings.ignore_paths[0].glob);
+        assert_eq!("*.md", settings.overrides[0].filename[0].glob);
+        assert_eq!(vec!["markdown"], settings.overrides[0].settings.words);
+        assert_eq!(Some(false), settings.overrides[0].settings.enabled);
+    }
+
+    #[test]
+    fn test_merge() {
+        let left = Settings {
+            enabled: Some(false),
+            language: Some("en".into()),
+            words: vec!["one".into(), "two".into()],
+            min_word_length: Some(3),
+            ..Default::default()
+        };
+        let right = Settings {
+            language: Some("fr".into()),
+            words: vec!["two".into(), "three".into()],
+            ..Default::default()
+        };
+
+        let merged = left.merge(&right);
+        assert_eq!(Some(false), merged.enabled);
+        assert_eq!(Some("fr".into()), merged.language);
+        assert_eq!(vec!["one", "two", "three"], merged.words);
+        assert_eq!(Some(3), merged.min_word_length);
+    }
+
+    #[test]
+    fn test_for_file() {
+        let mut settings: Settings = serde_json::from_str(
+            r#"{
+                "words": ["base"],
+                "overrides": [
+                    { "filename": "*.md", "words": ["markdown"] },
+                    { "filename": ["docs/**", "*.txt"], "words": ["docs"], "enabled": false }
+                ]
+            }"#,
+        )
+        .unwrap();
+        settings.set_root(Path::new("/project"));
+
+        let cases = [
+            ("/project/src/main.rs", vec!["base"], None),
+            ("/project/README.md", vec!["base", "markdown"], None),
+            (
+                "/project/docs/a.md",
+                vec!["base", "markdown", "docs"],
+                Some(false),
+            ),
+            ("/project/notes.txt", vec!["base", "docs"], Some(false)),
+        ];
+
+        for (path, words, enabled) in cases {
+            let file_settings = settings.for_file(Path::new(path));
+            assert_eq!(words, file_settings.words, "{path}");
+            assert_eq!(enabled, file_settings.enabled, "{path}");
+        }
+    }
+
+    #[test]
+    fn test_for_language() {
+        let settings: Settings = serde_json::from_str(
+            r#"{
+                "words": ["base"],
+                "languageSettings": [
+                    { "languageId": "typescript,javascript", "words": ["script"] },
+                    { "languageId": "*", "words": ["any"] },
+                    { "languageId": ["*", "!markdown"], "words": ["code"] },
+                    { "languageId": "markdown", "locale": "fr", "words": ["french"] },
+                    { "languageId": "markdown", "local": "en_US", "words": ["american"] },
+                    { "languageId": "rust", "dictionaries": ["rust"], "enabled": false, "caseSensitive": true }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let cases = [
+            (
+                None,
+                None,
+                "typescript",
+                vec!["base", "script", "any", "code"],
+            ),
+            (None, None, "markdown", vec!["base", "any", "american"]),
+            (Some("fr"), None, "markdown", vec!["base", "any", "french"]),
+            (Some("en-GB"), None, "markdown", vec!["base", "any"]),
+            (
+                Some("en,fr"),
+                None,
+                "markdown",
+                vec!["base", "any", "french", "american"],
+            ),
+            (
+                None,
+                Some("javascript"),
+                "markdown",
+                vec!["base", "script", "any", "code"],
+            ),
+            (None, None, "rust", vec!["base", "any", "code"]),
+        ];
+
+        for (language, language_id, file_type, expected) in cases {
+            let settings = Settings {
+                language: language.map(String::from),
+                language_id: language_id.map(String::from),
+                ..settings.clone()
+            };
+            let file_settings = settings.for_language(&[file_type.to_string()]);
+            assert_eq!(expected, file_settings.words, "{file_type} {language:?}");
+        }
+
+        let rust = settings.for_language(&["rust".to_string()]);
+        assert_eq!(vec!["rust"], rust.enabled_dictionaries());
+        assert_eq!(Some(false), rust.enabled);
+        assert_eq!(Some(true), rust.case_sensitive);
+    }
+
+    #[test]
+    fn test_suggest_word_map() {
+        let settings = Settings {
+            suggest_words: [
+                "colour->color",
+                "teh: the, then",
+                " alot : a lot ",
+                "invalid",
+                "empty:",
+                "colour: colours",
+            ]
+            .iter()
+            .map(|w| w.to_string())
+            .collect(),
+            ..Default::default()
+        };
+
+        let map = settings.suggest_word_map();
+        assert_eq!(3, map.len());
+        assert_eq!(vec!["colours"], map["colour"]);
+        assert_eq!(vec!["the", "then"], map["teh"]);
+        assert_eq!(vec!["a lot"], map["alot"]);
+
+        let settings = Settings {
+            flag_words: vec!["teh->the".into(), "alot".into(), "recieve: receive".into()],
+            suggest_words: vec!["teh: the, then".into()],
+            ..Default::default()
+        };
+        assert_eq!(vec!["teh", "alot", "recieve"], settings.forbidden_words());
+        let map = settings.suggest_word_map();
+        assert_eq!(2, map.len());
+        assert_eq!(vec!["the", "then"], map["teh"]);
+        assert_eq!(vec!["receive"], map["recieve"]);
+    }
+
+    #[test]
+    fn test_enabled_dictionaries() {
+        let cases = [
+            (vec!["a", "b", "a"], vec!["a", "b"]),
+            (vec!["a", "b", "!a"], vec!["b"]),
+            (vec!["a", "!a", "!!a"], vec!["a"]),
+            (vec!["!a", "a"], vec!["a"]),
+        ];
+
+        for (dictionaries, expected) in cases {
+            let settings = Settings {
+                dictionaries: dictionaries.iter().map(|d| d.to_string()).collect(),
+                ..Default::default()
+            };
+            assert_eq!(
+                expected,
+                settings.enabled_dictionaries(),
+                "{dictionaries:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,821 @@
+use std::{
+    collections::{HashMap, HashSet},
+    mem,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock},
+    time::Instant,
+};
+
+use spellrs_hunspell::{case_variants, Case, HunspellChecker, HunspellDictionary, Lookup};
+use spellrs_trie::{
+    case_insensitive_form, edit_distance, EditCosts, SuggestOptions, Suggestion, Trie, WeightMap,
+    CASE_INSENSITIVE_PREFIX, FORBID_PREFIX,
+};
+
+use crate::config::{DictionaryDefinition, Settings};
+
+/// A source of correctly spelled words.
+///
+/// Dictionaries are shared between the threads checking files, so they must be `Send + Sync`.
+pub trait SpellingDictionary: Send + Sync {
+    /// Checks if the word is in the dictionary.
+    ///
+    /// Capitalized and upper case words also match the lower case word, so `Hello` and `HELLO`
+    /// match `hello`. With `ignore_case`, case and accents are ignored entirely, so `paris`
+    /// matches `Paris` and `cafe` matches `café`. Without it, `Iphone` does not match `iPhone`.
+    fn has(&self, word: &str, ignore_case: bool) -> bool;
+
+    /// Checks if the word is explicitly marked as a misspelling.
+    fn is_forbidden(&self, word: &str) -> bool;
+
+    /// Checks if the word is forbidden or in the dictionary at once, for dictionaries where a
+    /// single lookup answers both.
+    fn find(&self, word: &str, ignore_case: bool) -> Found {
+        match self.is_forbidden(word) {
+            true => Found::Forbidden,
+            false if self.has(word, ignore_case) => Found::Word,
+            false => Found::Unknown,
+        }
+    }
+
+    /// Finds the words closest to `word`, cheapest first.
+    fn suggest(&self, word: &str, options: &SuggestOptions) -> Vec<Suggestion>;
+}
+
+/// Whether a dictionary has a word, see [`SpellingDictionary::find`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Found {
+    Word,
+    Forbidden,
+    Unknown,
+}
+
+/// A dictionary backed by Hunspell `.aff` and `.dic` files.
+pub struct HunspellSpellingDictionary {
+    checker: HunspellChecker,
+    weights: WeightMap,
+    /// A copy of the dictionary with the roots and affixes in their [`case_insensitive_form`],
+    /// made on the first lookup that ignores case.
+    insensitive: OnceLock<HunspellChecker>,
+}
+
+impl HunspellSpellingDictionary {
+    pub fn new(dict: HunspellDictionary) -> Self {
+        Self {
+            checker: HunspellChecker::new(dict),
+            weights: WeightMap::default(),
+            insensitive: OnceLock::new(),
+        }
+    }
+
+    pub fn from_dic_file(path: &Path) -> Result<Self, spellrs_hunspell::Error> {
+        Ok(Self::new(HunspellDictionary::from_dic_file(path)?))
+    }
+
+    pub fn with_weights(self, weights: WeightMap) -> Self {
+        Self { weights, ..self }
+    }
+}
+
+impl HunspellSpellingDictionary {
+    /// Finds a word regardless of case and accents. Hunspell itself only knows the case of
+    /// words, so the word is looked up in a copy of the dictionary where the roots and affixes
+    /// are folded the same way. The word forms are never expanded.
+    fn has_ignoring_case(&self, word: &str) -> bool {
+        let insensitive = self.insensitive.get_or_init(|| {
+            HunspellChecker::new(self.checker.dictionary().folded(case_insensitive_form))
+        });
+        insensitive.check(&case_insensitive_form(word))
+    }
+}
+
+impl SpellingDictionary for HunspellSpellingDictionary {
+    fn has(&self, word: &str, ignore_case: bool) -> bool {
+        self.checker.check(word) || (ignore_case && self.has_ignoring_case(word))
+    }
+
+    fn is_forbidden(&self, word: &str) -> bool {
+        self.checker.is_forbidden(word)
+    }
+
+    fn find(&self, word: &str, ignore_case: bool) -> Found {
+        match self.checker.find(word) {
+            Lookup::Valid | Lookup::Compound => Found::Word,
+            Lookup::Forbidden => Found::Forbidden,
+            Lookup::Unknown if ignore_case && self.has_ignoring_case(word) => Found::Word,
+            Lookup::Unknown => Found::Unknown,
+        }
+    }
+
+    /// Ranks the suggestions made with the `.aff` file by their edit distance.
+    fn suggest(&self, word: &str, options: &SuggestOptions) -> Vec<Suggestion> {
+        let mut suggestions: Vec<Suggestion> = self
+            .checker
+            .suggest_until(word, Instant::now() + options.timeout)
+            .into_iter()
+            .map(|s| Suggestion {
+                cost: edit_distance(word, &s, &self.weights),
+                word: s,
+            })
+            .filter(|s| s.cost <= options.max_cost())
+            .collect();
+        suggestions.sort();
+        suggestions.truncate(options.num_suggestions);
+        suggestions
+    }
+}
+
+/// A dictionary backed by a cspell `.trie` file or a cspell word list, i.e. `words.txt`.
+///
+/// cspell stores forbidden words with a `!` prefix, case-insensitive forms with a `~` prefix,
+/// i.e. `~paris` for `Paris`, and the parts of compound words with `+`.
+pub struct TrieSpellingDictionary {
+    trie: Trie,
+    weights: WeightMap,
+}
+
+impl TrieSpellingDictionary {
+    /// Loads a `.trie` or `.trie.gz` file, or any other file as a word list.
+    pub fn from_file(path: &Path) -> Result<Self, spellrs_trie::Error> {
+        let name = path.to_string_lossy();
+        let trie = match name.ends_with(".trie") || name.ends_with(".trie.gz") {
+            true => Trie::from_file(path)?,
+            false => Trie::from_word_list_file(path)?,
+        };
+        Ok(Self {
+            trie,
+            weights: WeightMap::default(),
+        })
+    }
+
+    pub fn with_weights(self, weights: WeightMap) -> Self {
+        Self { weights, ..self }
+    }
+}
+
+impl SpellingDictionary for TrieSpellingDictionary {
+    fn has(&self, word: &str, ignore_case: bool) -> bool {
+        case_variants(word)
+            .iter()
+            .any(|form| self.trie.has_compound(form))
+            || (ignore_case
+                && (self.trie.has_compound(&word.to_lowercase())
+                    || self.trie.has_compound(&format!(
+                        "{CASE_INSENSITIVE_PREFIX}{}",
+                        case_insensitive_form(word)
+                    ))))
+    }
+
+    fn is_forbidden(&self, word: &str) -> bool {
+        self.trie.has(&format!("{FORBID_PREFIX}{word}"))
+            || self
+                .trie
+                .has(&format!("{FORBID_PREFIX}{}", word.to_lowercase()))
+    }
+
+    fn suggest(&self, word: &str, options: &SuggestOptions) -> Vec<Suggestion> {
+        self.trie.suggest(word, options, &self.weights)
+    }
+}
+
+/// A dictionary made from lists of words, i.e. `words` and `flagWords` in the configuration.
+///
+/// Forbidden words are found in any case, so `alot` forbids `Alot`.
+#[derive(Debug, Default)]
+pub struct WordListDictionary {
+    words: HashSet<String>,
+    /// The [`case_insensitive_form`] of the words.
+    insensitive: HashSet<String>,
+    forbidden: HashSet<String>,
+    no_suggest: bool,
+}
+
+impl WordListDictionary {
+    pub fn new<'a>(
+        words: impl IntoIterator<Item = &'a String>,
+        forbidden: impl IntoIterator<Item = &'a String>,
+    ) -> Self {
+        let words: HashSet<String> = words.into_iter().cloned().collect();
+        Self {
+            insensitive: words.iter().map(|w| case_insensitive_form(w)).collect(),
+            words,
+            forbidden: forbidden.into_iter().cloned().collect(),
+            no_suggest: false,
+        }
+    }
+
+    /// Keeps the words from being suggested, as for `ignoreWords`.
+    pub fn no_suggest(mut self) -> Self {
+        self.no_suggest = true;
+        self
+    }
+}
+
+impl SpellingDictionary for WordListDictionary {
+    fn has(&self, word: &str, ignore_case: bool) -> bool {
+        case_variants(word)
+            .iter()
+            .any(|form| self.words.contains(form))
+            || (ignore_case && self.insensitive.contains(&case_insensitive_form(word)))
+    }
+
+    fn is_forbidden(&self, word: &str) -> bool {
+        self.forbidden.contains(word) || self.forbidden.contains(&word.to_lowercase())
+    }
+
+    fn suggest(&self, word: &str, options: &SuggestOptions) -> Vec<Suggestion> {
+        if self.no_suggest {
+            return vec![];
+        }
+
+        let weights = WeightMap::default();
+        let mut suggestions: Vec<Suggestion> = self
+            .words
+            .iter()
+            .map(|w| Suggestion {
+                cost: edit_distance(word, w, &weights),
+                word: w.clone(),
+            })
+            .filter(|s| s.cost <= options.max_cost())
+            .collect();
+        suggestions.sort();
+        suggestions.truncate(options.num_suggestions);
+        suggestions
+    }
+}
+
+/// Checks words against several dictionaries at once.
+///
+/// A word is found if any dictionary has it, unless a dictionary forbids it.
+#[derive(Default)]
+pub struct DictionaryCollection {
+    /// The dictionaries and their `caseSensitive` setting, if it differs from the collection's.
+    dictionaries: Vec<(Arc<dyn SpellingDictionary>, Option<bool>)>,
+    /// Preferred suggestions from `suggestWords`.
+    suggest_words: HashMap<String, Vec<String>>,
+}
+
+impl DictionaryCollection {
+    pub fn push(&mut self, dictionary: Arc<dyn SpellingDictionary>) {
+        self.dictionaries.push((dictionary, None));
+    }
+
+    /// Adds a dictionary that is always searched with or without case, whatever the
+    /// `ignore_case` of a search.
+    pub fn push_case_sensitive(
+        &mut self,
+        dictionary: Arc<dyn SpellingDictionary>,
+        case_sensitive: bool,
+    ) {
+        self.dictionaries.push((dictionary, Some(case_sensitive)));
+    }
+}
+
+impl SpellingDictionary for DictionaryCollection {
+    fn has(&self, word: &str, ignore_case: bool) -> bool {
+        self.find(word, ignore_case) == Found::Word
+    }
+
+    fn is_forbidden(&self, word: &str) -> bool {
+        self.dictionaries.iter().any(|(d, _)| d.is_forbidden(word))
+    }
+
+    /// A word is forbidden by any dictionary that forbids it, even if others have it.
+    fn find(&self, word: &str, ignore_case: bool) -> Found {
+        let mut found = Found::Unknown;
+        for (dictionary, case_sensitive) in &self.dictionaries {
+            let ignore_case = case_sensitive.map_or(ignore_case, |sensitive| !sensitive);
+            match dictionary.find(word, ignore_case) {
+                Found::Forbidden => return Found::Forbidden,
+                Found::Word => found = Found::Word,
+                Found::Unknown => {}
+            }
+        }
+        found
+    }
+
+    /// Combines the suggestions of all the dictionaries, with the `suggestWords` first.
+    ///
+    /// Capitalized and upper case words are looked up in lower case and the suggestions are
+    /// changed back to the case of the word, so `Teh` suggests `The`.
+    fn suggest(&self, word: &str, options: &SuggestOptions) -> Vec<Suggestion> {
+        let deadline = Instant::now() + options.timeout;
+        let case = Case::of(word);
+        let lookup = match case {
+            Case::Title | Case::Upper => word.to_lowercase(),
+            Case::Lower | Case::Mixed => word.to_string(),
+        };
+
+        let preferred = self
+            .suggest_words
+            .get(word)
+            .or_else(|| self.suggest_words.get(&lookup));
+        let mut suggestions: Vec<Suggestion> = preferred
+            .into_iter()
+            .flatten()
+            .map(|s| Suggestion {
+                cost: 0,
+                word: s.clone(),
+            })
+            .collect();
+
+        for (dictionary, _) in &self.dictionaries {
+            let options = SuggestOptions {
+                timeout: deadline.saturating_duration_since(Instant::now()),
+                ..options.clone()
+            };
+            let mut found = dictionary.suggest(&lookup, &options);
+            found.sort();
+            suggestions.extend(found);
+        }
+        // Sorting is stable, so preferred suggestions stay ahead of the others that cost 0.
+        suggestions.sort_by_key(|s| s.cost);
+
+        let mut seen = HashSet::new();
+        suggestions.retain_mut(|s| {
+            s.word = case.apply(&s.word);
+            s.word != word && !self.is_forbidden(&s.word) && seen.insert(s.word.clone())
+        });
+        suggestions.truncate(options.num_suggestions);
+        suggestions
+    }
+}
+
+/// The weights from the `suggestionEditCosts` of a dictionary definition.
+fn weights(def: &DictionaryDefinition) -> WeightMap {
+    let costs: Vec<EditCosts> = def
+        .dictionary_information
+        .iter()
+        .flat_map(|info| &info.suggestion_edit_costs)
+        .map(EditCosts::from)
+        .collect();
+    WeightMap::new(&costs)
+}
+
+/// A dictionary loaded once by [`DictionaryLoader`], `None` if it failed to load.
+type LoadedDictionary = Arc<OnceLock<Option<Arc<dyn SpellingDictionary>>>>;
+
+/// Loads dictionary files, keeping them so that each file is only loaded once.
+///
+/// The loader can be shared between threads. A file is loaded by the first thread that needs it,
+/// other threads only wait for the files they need themselves.
+#[derive(Default)]
+pub struct DictionaryLoader {
+    /// The dictionaries by path and `dictionaryInformation` as JSON, `None` if they failed to
+    /// load.
+    loaded: Mutex<HashMap<(PathBuf, String), LoadedDictionary>>,
+    /// Enabled dictionaries without a definition, reported once each.
+    unknown: Mutex<HashSet<String>>,
+    errors: Mutex<Vec<String>>,
+}
+
+impl DictionaryLoader {
+    /// Loads a dictionary file: a Hunspell `.dic` file, a cspell `.trie` file or a word list.
+    pub fn load(&self, path: &Path) -> Result<Arc<dyn SpellingDictionary>, String> {
+        self.load_with_weights(path, WeightMap::default())
+    }
+
+    /// Loads a dictionary that ranks its suggestions with `weights`.
+    fn load_with_weights(
+        &self,
+        path: &Path,
+        weights: WeightMap,
+    ) -> Result<Arc<dyn SpellingDictionary>, String> {
+        let is_dic = path.extension().is_some_and(|ext| ext == "dic");
+        let dictionary: Arc<dyn SpellingDictionary> = match is_dic {
+            true => Arc::new(
+                HunspellSpellingDictionary::from_dic_file(path)
+                    .map_err(|e| e.to_string())?
+                    .with_weights(weights),
+            ),
+            false => Arc::new(
+                TrieSpellingDictionary::from_file(path)
+                    .map_err(|e| e.to_string())?
+                    .with_weights(weights),
+            ),
+        };
+
+        Ok(dictionary)
+    }
+
+    /// Builds the dictionaries for a file from its settings.
+    ///
+    /// `extra` dictionaries, i.e. from the command line, are always included. Enabled
+    /// dictionaries without a definition and errors loading the dictionaries are kept for
+    /// [`DictionaryLoader::take_errors`].
+    pub fn for_settings(
+        &self,
+        settings: &Settings,
+        extra: &[Arc<dyn SpellingDictionary>],
+    ) -> DictionaryCollection {
+        let mut collection = DictionaryCollection {
+            suggest_words: settings.suggest_word_map(),
+            ..Default::default()
+        };
+        for dictionary in extra {
+            collection.push(dictionary.clone());
+        }
+
+        for name in settings.enabled_dictionaries() {
+            let Some(def) = settings.dictionary_definition(name) else {
+                if self.unknown.lock().unwrap().insert(name.to_string()) {
+                    let error = format!("unknown dictionary \"{name}\"");
+                    self.errors.lock().unwrap().push(error);
+                }
+                continue;
+            };
+            if let Some(dictionary) = self.load_definition(def) {
+                match def.case_sensitive {
+                    Some(case_sensitive) => {
+                        collection.push_case_sensitive(dictionary, case_sensitive)
+                    }
+                    None => collection.push(dictionary),
+                }
+            }
+        }
+
+        let words = settings.words.iter().chain(&settings.user_words);
+        collection.push(Arc::new(WordListDictionary::new(
+            words,
+            &settings.forbidden_words(),
+        )));
+        collection.push(Arc::new(
+            WordListDictionary::new(&settings.ignore_words, []).no_suggest(),
+        ));
+
+        collection
+    }
+
+    /// Loads the dictionary of a definition, or `None` if it has no path or fails to load.
+    pub fn load_definition(
+        &self,
+        def: &DictionaryDefinition,
+    ) -> Option<Arc<dyn SpellingDictionary>> {
+        let path = PathBuf::from(def.path.as_ref()?);
+        let information = serde_json::to_string(&def.dictionary_information).unwrap_or_default();
+        let slot = self
+            .loaded
+            .lock()
+            .unwrap()
+            .entry((path.clone(), information))
+            .or_default()
+            .clone();
+
+        slot.get_or_init(|| {
+            let dictionary = self.load_with_weights(&path, weights(def));
+            if let Err(e) = &dictionary {
+                self.errors.lock().unwrap().push(e.clone());
+            }
+            dictionary.ok()
+        })
+        .clone()
+    }
+
+    /// Returns the errors found since the last call.
+    pub fn take_errors(&mut self) -> Vec<String> {
+        mem::take(self.errors.get_mut().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, time::Duration};
+
+    use spellrs_hunspell::parse_dic;
+    use spellrs_trie::parse_word_list;
+
+    use super::*;
+
+    fn word_list(words: &[&str], forbidden: &[&str]) -> Arc<dyn SpellingDictionary> {
+        let words: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+        let forbidden: Vec<String> = forbidden.iter().map(|w| w.to_string()).collect();
+        Arc::new(WordListDictionary::new(&words, &forbidden))
+    }
+
+    #[test]
+    fn test_collection() {
+        let mut dicts = DictionaryCollection::default();
+        dicts.push(word_list(&["hello", "world", "alot"], &[]));
+        dicts.push(word_list(&["rust"], &["alot"]));
+
+        let cases = [
+            ("hello", Found::Word),
+            ("Hello", Found::Word),
+            ("HELLO", Found::Word),
+            ("rust", Found::Word),
+            ("alot", Found::Forbidden),
+            ("Alot", Found::Forbidden),
+            ("unknown", Found::Unknown),
+        ];
+
+        for (word, expected) in cases {
+            assert_eq!(expected, dicts.find(word, true), "{word}");
+            assert_eq!(expected == Found::Word, dicts.has(word, true), "{word}");
+        }
+    }
+
+    #[test]
+    fn test_trie_dictionary() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("words.trie.gz");
+        Trie::from_words(["hello", "Paris", "~paris", "!helo"])
+            .write_file(&path, &Default::default())
+            .unwrap();
+
+        let loader = DictionaryLoader::default();
+        let dictionary = loader.load(&path).unwrap();
+        let cases = [
+            ("hello", true, false),
+            ("Hello", true, false),
+            ("Paris", true, false),
+            ("paris", true, false),
+            ("PARIS", true, false),
+            ("helo", false, true),
+            ("world", false, false),
+        ];
+
+        for (word, has, forbidden) in cases {
+            assert_eq!(has, dictionary.has(word, true), "{word}");
+            assert_eq!(forbidden, dictionary.is_forbidden(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn test_word_list_dictionary() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("project-words.txt");
+        fs::write(
+            &path,
+            "# Project words\nspellrs\nParis\n~iphone\n!alot\nun+\n+do\n*work*\n",
+        )
+        .unwrap();
+
+        let loader = DictionaryLoader::default();
+        let dictionary = loader.load(&path).unwrap();
+        let cases = [
+            ("spellrs", true, false),
+            ("Spellrs", true, false),
+            ("paris", true, false),
+            ("iPhone", true, false),
+            ("alot", false, true),
+            ("Alot", false, true),
+            ("undo", true, false),
+            ("un", false, false),
+            ("unwork", true, false),
+            ("workdo", true, false),
+            ("work", true, false),
+            ("comment", false, false),
+        ];
+
+        for (word, has, forbidden) in cases {
+            assert_eq!(has, dictionary.has(word, true), "{word}");
+            assert_eq!(forbidden, dictionary.is_forbidden(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn test_for_settings() {
+        let dic = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("spellrs_hunspell")
+            .join("testdata")
+            .join("en_sample.dic");
+        let settings: Settings = serde_json::from_value(serde_json::json!({
+            "words": ["spellrs"],
+            "ignoreWords": ["todo"],
+            "flagWords": ["hello"],
+            "dictionaries": ["en", "missing", "unknown-format", "unused", "!unused", "en_us"],
+            "dictionaryDefinitions": [
+                { "name": "en", "path": dic },
+                { "name": "missing", "path": "/missing.dic" },
+                { "name": "unknown-format", "path": "/words.txt" },
+                { "name": "unused", "path": "/unused.dic" },
+            ],
+        }))
+        .unwrap();
+
+        let mut loader = DictionaryLoader::default();
+        let dicts = loader.for_settings(&settings, &[word_list(&["extra"], &[])]);
+        let cases = [
+            ("spellrs", true),
+            ("todo", true),
+            ("boxes", true),
+            ("extra", true),
+            ("hello", false),
+            ("alot", false),
+        ];
+
+        for (word, expected) in cases {
+            assert_eq!(expected, dicts.has(word, true), "{word}");
+        }
+        let errors = loader.take_errors();
+        assert_eq!(3, errors.len());
+        assert_eq!("unknown dictionary \"en_us\"", errors[2]);
+
+        loader.for_settings(&settings, &[]);
+        assert!(loader.take_errors().is_empty());
+    }
+
+    #[test]
+    fn test_load_definition() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("words.txt");
+        fs::write(&path, "phone\nbone\n").unwrap();
+        let definitions: Vec<DictionaryDefinition> = serde_json::from_value(serde_json::json!([
+            { "name": "plain", "path": path },
+            { "name": "same-path", "path": path },
+            {
+                "name": "cheap-ph",
+                "path": path,
+                "dictionaryInformation": {
+                    "suggestionEditCosts": [{ "map": "(ph)f", "replace": 20 }],
+                },
+            },
+        ]))
+        .unwrap();
+
+        let loader = DictionaryLoader::default();
+        let dictionaries: Vec<_> = definitions
+            .iter()
+            .map(|def| loader.load_definition(def).unwrap())
+            .collect();
+        assert!(Arc::ptr_eq(&dictionaries[0], &dictionaries[1]));
+
+        // Each `suggestionEditCosts` ranks the suggestions of the same file with its own weights.
+        let options = SuggestOptions {
+            num_suggestions: 1,
+            ..Default::default()
+        };
+        let cases = [(0, "bone"), (2, "phone")];
+        for (i, expected) in cases {
+            let suggestions = dictionaries[i].suggest("fone", &options);
+            assert_eq!(expected, suggestions[0].word, "{}", definitions[i].name);
+        }
+    }
+
+    #[test]
+    fn test_suggest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("words.txt");
+        fs::write(&path, "phone\nbone\nthe\nthen\nhello\nParis\nalot\n").unwrap();
+        let settings: Settings = serde_json::from_value(serde_json::json!({
+            "words": ["colour"],
+            "ignoreWords": ["thee"],
+            "flagWords": ["alot"],
+            "suggestWords": ["colour->color", "teh: tech"],
+            "dictionaries": ["words"],
+            "dictionaryDefinitions": [{
+                "name": "words",
+                "path": path,
+                "dictionaryInformation": {
+                    "suggestionEditCosts": [{ "map": "(ph)f", "replace": 20 }],
+                },
+            }],
+        }))
+        .unwrap();
+
+        let loader = DictionaryLoader::default();
+        let dicts = loader.for_settings(&settings, &[]);
+        let options = SuggestOptions {
+            num_suggestions: 3,
+            num_changes: 2,
+            ..Default::default()
+        };
+        let cases = [
+            ("teh", vec!["tech", "the", "then"]),
+            ("Teh", vec!["Tech", "The", "Then"]),
+            ("TEH", vec!["TECH", "THE", "THEN"]),
+            ("fone", vec!["phone", "bone"]),
+            ("colour", vec!["color"]),
+            ("paris", vec!["Paris"]),
+            ("alott", vec![]),
+        ];
+
+        for (word, expected) in cases {
+            let suggestions: Vec<String> = dicts
+                .suggest(word, &options)
+                .into_iter()
+                .map(|s| s.word)
+                .collect();
+            assert_eq!(expected, suggestions, "{word}");
+        }
+    }
+
+    #[test]
+    fn test_hunspell_suggest_options() {
+        let aff = "".parse().unwrap();
+        let dic = parse_dic("2\nhello\nhelp", &aff).unwrap();
+        let hunspell = HunspellSpellingDictionary::new(HunspellDictionary::new(aff, dic));
+        let suggest = |num_changes, timeout| -> Vec<String> {
+            let options = SuggestOptions {
+                num_changes,
+                timeout,
+                ..Default::default()
+            };
+            hunspell
+                .suggest("helllo", &options)
+                .into_iter()
+                .map(|s| s.word)
+                .collect()
+        };
+
+        assert_eq!(vec!["hello"], suggest(1, Duration::from_secs(60)));
+        assert!(suggest(0, Duration::from_secs(60)).is_empty());
+        assert!(suggest(1, Duration::ZERO).is_empty());
+    }
+
+    #[test]
+    fn test_case_sensitive() {
+        let words = ["iPhone", "Paris", "café", "hello"];
+        let trie = Trie::from_words(parse_word_list(&words.join("\n")));
+        let trie = TrieSpellingDictionary {
+            trie,
+            weights: WeightMap::default(),
+        };
+        let list = word_list(&words, &[]);
+        let cases = [
+            ("iPhone", true, true),
+            ("IPHONE", false, true),
+            ("Iphone", false, true),
+            ("iphone", false, true),
+            ("Paris", true, true),
+            ("PARIS", true, true),
+            ("paris", false, true),
+            ("café", true, true),
+            ("Café", true, true),
+            ("cafe", false, true),
+            ("hello", true, true),
+            ("Hello", true, true),
+            ("HELLO", true, true),
+            ("hELLO", false, true),
+        ];
+
+        let aff = "FORBIDDENWORD *\n".parse().unwrap();
+        let dic = parse_dic(&format!("{}\n{}", words.len(), words.join("\n")), &aff).unwrap();
+        let hunspell = HunspellSpellingDictionary::new(HunspellDictionary::new(aff, dic));
+
+        for (word, strict, ignore_case) in cases {
+            assert_eq!(strict, hunspell.has(word, false), "hunspell {word}");
+            assert_eq!(ignore_case, hunspell.has(word, true), "hunspell {word}");
+            assert_eq!(strict, trie.has(word, false), "trie {word}");
+            assert_eq!(ignore_case, trie.has(word, true), "trie {word}");
+            assert_eq!(strict, list.has(word, false), "list {word}");
+            assert_eq!(ignore_case, list.has(word, true), "list {word}");
+        }
+
+        let mut dicts = DictionaryCollection::default();
+        dicts.push_case_sensitive(word_list(&["Paris"], &[]), true);
+        dicts.push_case_sensitive(word_list(&["Berlin"], &[]), false);
+        dicts.push(word_list(&["Rome"], &[]));
+        let cases = [
+            ("paris", false, false),
+            ("berlin", true, true),
+            ("rome", false, true),
+        ];
+
+        for (word, strict, ignore_case) in cases {
+            assert_eq!(strict, dicts.has(word, false), "{word}");
+            assert_eq!(ignore_case, dicts.has(word, true), "{word}");
+        }
+    }
+
+    #[test]
+    fn test_ignore_case_without_expansion() {
+        // 100 roots with 300 prefixes and 300 suffixes each make 9 million word forms.
+        let letters = |n: usize| {
+            format!(
+                "{}{}",
+                (b'a' + (n / 26) as u8) as char,
+                (b'a' + (n % 26) as u8) as char
+            )
+        };
+        let mut aff = String::from("FLAG long\nPFX Pa Y 300\n");
+        for i in 0..300 {
+            aff.push_str(&format!("PFX Pa 0 {}É .\n", letters(i).to_uppercase()));
+        }
+        aff.push_str("SFX Sa Y 300\n");
+        for i in 0..300 {
+            aff.push_str(&format!("SFX Sa 0 ç{} .\n", letters(i)));
+        }
+        let aff = aff.parse().unwrap();
+        let roots: Vec<String> = (0..100)
+            .map(|i| format!("Wörd{}/PaSa", letters(i)))
+            .collect();
+        let dic = parse_dic(&format!("{}\n{}", roots.len(), roots.join("\n")), &aff).unwrap();
+        let hunspell = HunspellSpellingDictionary::new(HunspellDictionary::new(aff, dic));
+
+        let cases = [
+            ("ABÉWördcxçkl", true, true),
+            ("abewordcxckl", false, true),
+            ("ABEWORDCXCKL", false, true),
+            ("abewordcxcklx", false, false),
+        ];
+        for (word, strict, ignore_case) in cases {
+            assert_eq!(strict, hunspell.has(word, false), "{word}");
+            assert_eq!(ignore_case, hunspell.has(word, true), "{word}");
+        }
+
+        let insensitive = hunspell.insensitive.get().unwrap().dictionary();
+        assert_eq!(100, insensitive.dic.entries.len());
+        assert_eq!("wordbx", insensitive.dic.entries[49].word);
+    }
+}
@@ -0,0 +1,403 @@
+use std::{collections::HashSet, ops::Range};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use spellrs_trie::{edit_distance, WeightMap, BASE_COST};
+
+use crate::{config::Settings, patterns::parse_reg_exp, text::TextOffset};
+
+/// `cspell:name value`, also written `spell-checker: name`, `spellchecker::name` and so on. The
+/// value runs to the end of the line.
+static DIRECTIVE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\bc?spell(?:-?checker)?::?[ \t]*([\w-]*)([^\r\n]*)").unwrap());
+
+/// The directive names offered when a name is misspelled.
+const DIRECTIVE_NAMES: [&str; 15] = [
+    "enable",
+    "disable",
+    "disable-line",
+    "disable-next-line",
+    "words",
+    "ignore",
+    "flagWords",
+    "ignoreRegExp",
+    "includeRegExp",
+    "locale",
+    "dictionaries",
+    "enableCaseSensitive",
+    "disableCaseSensitive",
+    "enableCompoundWords",
+    "disableCompoundWords",
+];
+
+/// A `cspell:` comment in a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Directive<'a> {
+    pub name: &'a str,
+    pub value: &'a str,
+    /// Byte offset of the name.
+    pub name_offset: usize,
+    /// Byte offset of the value.
+    pub value_offset: usize,
+    /// The whole directive, from `cspell` to the end of the line.
+    pub range: Range<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DirectiveKind {
+    Enable,
+    Disable,
+    DisableLine,
+    DisableNext,
+    Words,
+    Ignore,
+    FlagWords,
+    IgnoreRegExp,
+    IncludeRegExp,
+    Locale,
+    Dictionaries,
+    CaseSensitive(bool),
+    /// Compound words are always allowed, so these have no effect.
+    CompoundWords,
+}
+
+impl DirectiveKind {
+    /// Names are not case sensitive and most have several spellings, like cspell.
+    fn from_name(name: &str) -> Option<Self> {
+        use DirectiveKind::*;
+
+        let kind = match name.to_ascii_lowercase().as_str() {
+            "enable" => Enable,
+            "disable" => Disable,
+            "disable-line" => DisableLine,
+            "disable-next" | "disable-next-line" => DisableNext,
+            "word" | "words" => Words,
+            "ignore" | "ignoreword" | "ignorewords" | "ignore-word" | "ignore-words" => Ignore,
+            "flag" | "flagword" | "flagwords" | "flag-word" | "flag-words" | "forbid"
+            | "forbidword" | "forbid-word" => FlagWords,
+            "ignoreregexp" => IgnoreRegExp,
+            "includeregexp" => IncludeRegExp,
+            "local" | "locale" | "language" => Locale,
+            "dictionary" | "dictionaries" => Dictionaries,
+            "enablecasesensitive" => CaseSensitive(true),
+            "disablecasesensitive" => CaseSensitive(false),
+            "enablecompoundwords"
+            | "enableallowcompoundwords"
+            | "disablecompoundwords"
+            | "disableallowcompoundwords" => CompoundWords,
+            _ => return None,
+        };
+        Some(kind)
+    }
+}
+
+/// A directive that is misspelled, is missing its value or has no effect.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectiveIssue {
+    /// The text the issue is about, i.e. the name of the directive or an unused word.
+    pub text: String,
+    /// Byte offset of the text.
+    pub offset: usize,
+    /// Describes the issue, including the text, i.e. `Unused word (foo)`.
+    pub message: String,
+}
+
+/// Finds the directives in a document.
+pub fn parse_directives(text: &str) -> Vec<Directive<'_>> {
+    DIRECTIVE_REGEX
+        .captures_iter(text)
+        .map(|caps| {
+            let (all, name, value) = (
+                caps.get(0).unwrap(),
+                caps.get(1).unwrap(),
+                caps.get(2).unwrap(),
+            );
+            Directive {
+                name: name.as_str(),
+                value: value.as_str(),
+                name_offset: name.start(),
+                value_offset: value.start(),
+                range: all.range(),
+            }
+        })
+        .collect()
+}
+
+/// The words listed by a directive, separated by spaces or commas. Comment endings like `*/`
+/// are skipped.
+fn directive_words<'a>(directive: &Directive<'a>) -> Vec<(usize, &'a str)> {
+    let value = directive.value;
+    value
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .map(|word| {
+            let offset = word.as_ptr() as usize - value.as_ptr() as usize;
+            (directive.value_offset + offset, word)
+        })
+        .collect()
+}
+
+/// The first part of the value, i.e. the pattern of `cspell:ignoreRegExp /0x\w+/g */`.
+fn first_value<'a>(directive: &Directive<'a>) -> Option<&'a str> {
+    directive.value.split_whitespace().next()
+}
+
+/// The settings from the directives of a document, to merge on top of the settings of the file.
+///
+/// The text turned off with `cspell:disable` and the like is skipped by the predefined
+/// `SpellCheckerDisable` pattern instead.
+pub fn directive_settings(directives: &[Directive]) -> Settings {
+    let mut settings = Settings::default();
+    for directive in directives {
+        let words = || {
+            directive_words(directive)
+                .into_iter()
+                .map(|(_, w)| w.to_string())
+        };
+        match DirectiveKind::from_name(directive.name) {
+            Some(DirectiveKind::Words) => settings.words.extend(words()),
+            Some(DirectiveKind::Ignore) => settings.ignore_words.extend(words()),
+            Some(DirectiveKind::FlagWords) => settings.flag_words.extend(words()),
+            Some(DirectiveKind::Dictionaries) => settings.dictionaries.extend(words()),
+            Some(DirectiveKind::IgnoreRegExp) => settings
+                .ignore_reg_exp_list
+                .extend(first_value(directive).map(String::from)),
+            Some(DirectiveKind::IncludeRegExp) => settings
+                .include_reg_exp_list
+                .extend(first_value(directive).map(String::from)),
+            Some(DirectiveKind::Locale) => {
+                settings.language = first_value(directive).map(String::from)
+            }
+            Some(DirectiveKind::CaseSensitive(case_sensitive)) => {
+                settings.case_sensitive = Some(case_sensitive)
+            }
+            _ => {}
+        }
+    }
+    settings
+}
+
+/// Finds the directives that are misspelled, are missing their value or have no effect.
+///
+/// `words` are the words of the document outside of the directives. A word of `cspell:words`
+/// or `cspell:ignore` that is not one of them is unused.
+pub fn validate_directives(directives: &[Directive], words: &[TextOffset]) -> Vec<DirectiveIssue> {
+    let mut issues = vec![];
+    let mut issue = |text: &str, offset: usize, message: String| {
+        issues.push(DirectiveIssue {
+            text: text.to_string(),
+            offset,
+            message,
+        })
+    };
+
+    // The lower case words of the document, made when a directive lists words.
+    let mut lower_words: Option<HashSet<String>> = None;
+    let mut disabled = false;
+    for directive in directives {
+        let name = directive.name;
+        let Some(kind) = DirectiveKind::from_name(name) else {
+            let message = match closest_name(name) {
+                Some(closest) => format!("Unknown directive ({name}), did you mean `{closest}`?"),
+                None => format!("Unknown directive ({name})"),
+            };
+            issue(name, directive.name_offset, message);
+            continue;
+        };
+
+        match kind {
+            DirectiveKind::Enable | DirectiveKind::Disable => {
+                let disable = kind == DirectiveKind::Disable;
+                if disabled == disable {
+                    let state = match disable {
+                        true => "already disabled",
+                        false => "not disabled",
+                    };
+                    issue(
+                        name,
+                        directive.name_offset,
+                        format!("Unused directive ({name}), spell checking is {state}"),
+                    );
+                }
+                disabled = disable;
+            }
+            DirectiveKind::Words | DirectiveKind::Ignore | DirectiveKind::FlagWords => {
+                let listed = directive_words(directive);
+                if listed.is_empty() {
+                    issue(
+                        name,
+                        directive.name_offset,
+                        format!("Missing words ({name})"),
+                    );
+                }
+                if kind == DirectiveKind::FlagWords {
+                    continue;
+                }
+                let lower_words = lower_words
+                    .get_or_insert_with(|| words.iter().map(|w| w.text.to_lowercase()).collect());
+                for (offset, word) in listed {
+                    if !lower_words.contains(&word.to_lowercase()) {
+                        issue(word, offset, format!("Unused word ({word})"));
+                    }
+                }
+            }
+            DirectiveKind::IgnoreRegExp | DirectiveKind::IncludeRegExp => {
+                match first_value(directive).map(parse_reg_exp) {
+                    None => issue(
+                        name,
+                        directive.name_offset,
+                        format!("Missing pattern ({name})"),
+                    ),
+                    Some(Err(e)) => issue(
+                        name,
+                        directive.name_offset,
+                        format!("Invalid directive ({name}), {e}"),
+                    ),
+                    Some(Ok(_)) => {}
+                }
+            }
+            DirectiveKind::Locale | DirectiveKind::Dictionaries => {
+                if first_value(directive).is_none() {
+                    issue(
+                        name,
+                        directive.name_offset,
+                        format!("Missing value ({name})"),
+                    );
+                }
+            }
+            DirectiveKind::DisableLine
+            | DirectiveKind::DisableNext
+            | DirectiveKind::CaseSensitive(_)
+            | DirectiveKind::CompoundWords => {}
+        }
+    }
+
+    issues
+}
+
+/// The directive name closest to a misspelled one, if any is close enough.
+fn closest_name(name: &str) -> Option<&'static str> {
+    let weights = WeightMap::default();
+    let name = name.to_lowercase();
+    DIRECTIVE_NAMES
+        .into_iter()
+        .map(|known| (edit_distance(&name, &known.to_lowercase(), &weights), known))
+        .filter(|(cost, _)| *cost <= 2 * BASE_COST)
+        .min()
+        .map(|(_, known)| known)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::text::extract_words;
+
+    #[test]
+    fn test_parse_directives() {
+        let text = "// cspell:ignore foo, bar\n/* spell-checker: words baz */\n# cSpell::disable\n";
+        let found: Vec<(&str, &str)> = parse_directives(text)
+            .iter()
+            .map(|d| (d.name, d.value))
+            .collect();
+        assert_eq!(
+            vec![
+                ("ignore", " foo, bar"),
+                ("words", " baz */"),
+                ("disable", ""),
+            ],
+            found
+        );
+
+        let directive = &parse_directives(text)[1];
+        assert_eq!("words", &text[directive.name_offset..][..5]);
+        assert_eq!(
+            "spell-checker: words baz */",
+            &text[directive.range.clone()]
+        );
+    }
+
+    #[test]
+    fn test_directive_settings() {
+        let text = "cspell:words alpha beta\n\
+            /* cspell:ignore-words gamma */\n\
+            cspell:flagWords delta\n\
+            cspell:ignoreRegExp /0x\\w+/g\n\
+            cspell:locale fr\n\
+            cspell:dictionaries rust, cpp\n\
+            cspell:enableCaseSensitive\n\
+            cspell:disable";
+        let settings = directive_settings(&parse_directives(text));
+        assert_eq!(vec!["alpha", "beta"], settings.words);
+        assert_eq!(vec!["gamma"], settings.ignore_words);
+        assert_eq!(vec!["delta"], settings.flag_words);
+        assert_eq!(vec!["/0x\\w+/g"], settings.ignore_reg_exp_list);
+        assert_eq!(Some("fr".to_string()), settings.language);
+        assert_eq!(vec!["rust", "cpp"], settings.dictionaries);
+        assert_eq!(Some(true), settings.case_sensitive);
+    }
+
+    #[test]
+    fn test_validate_directives() {
+        let cases = [
+            (
+                "cspell:wrods foo\nfoo",
+                vec![("wrods", "Unknown directive (wrods), did you mean `words`?")],
+            ),
+            (
+                "cspell:xyzzyplugh",
+                vec![("xyzzyplugh", "Unknown directive (xyzzyplugh)")],
+            ),
+            (
+                "cspell:ignore foo bar\nfoo",
+                vec![("bar", "Unused word (bar)")],
+            ),
+            ("cspell:ignore Foo\nfoo", vec![]),
+            ("cspell:words */", vec![("words", "Missing words (words)")]),
+            ("cspell:flagWords alot", vec![]),
+            (
+                "cspell:enable\ncspell:disable\ncspell:disable",
+                vec![
+                    (
+                        "enable",
+                        "Unused directive (enable), spell checking is not disabled",
+                    ),
+                    (
+                        "disable",
+                        "Unused directive (disable), spell checking is already disabled",
+                    ),
+                ],
+            ),
+            ("cspell:disable\ncspell:enable", vec![]),
+            (
+                "cspell:ignoreRegExp",
+                vec![("ignoreRegExp", "Missing pattern (ignoreRegExp)")],
+            ),
+            ("cspell:locale", vec![("locale", "Missing value (locale)")]),
+            (
+                "cspell:disable-next-line\ncspell:enableCaseSensitive",
+                vec![],
+            ),
+        ];
+
+        for (text, expected) in cases {
+            let directives = parse_directives(text);
+            let words: Vec<TextOffset> = extract_words(text)
+                .filter(|w| !directives.iter().any(|d| d.range.contains(&w.offset)))
+                .collect();
+            let issues = validate_directives(&directives, &words);
+            let found: Vec<(&str, &str)> = issues
+                .iter()
+                .map(|i| (&text[i.offset..][..i.text.len()], i.message.as_str()))
+                .collect();
+            assert_eq!(expected, found, "{text}");
+        }
+
+        let directives = parse_directives("cspell:ignoreRegExp /(x/");
+        let issues = validate_directives(&directives, &[]);
+        let e = parse_reg_exp("/(x/").unwrap_err();
+        assert_eq!(
+            format!("Invalid directive (ignoreRegExp), {e}"),
+            issues[0].message
+        );
+    }
+}
@@ -0,0 +1,432 @@
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, BufRead, Write},
+    mem,
+    path::{Path, PathBuf},
+};
+
+use spellrs_trie::{Suggestion, BASE_COST};
+
+use crate::{
+    config::add_word,
+    lint::{Issue, IssueKind},
+    reporter::FileReport,
+};
+
+/// Lines of context around the changes of a diff.
+const DIFF_CONTEXT: usize = 3;
+
+/// How to fix the issues, from `--fix` and `--diff`. `--interactive` passes a [`Prompt`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FixOptions {
+    /// Apply the [`Issue::fix`] of the issues.
+    pub fix: bool,
+    /// Print the changes instead of writing the files.
+    pub diff: bool,
+}
+
+/// The replacement to apply without asking, if any.
+///
+/// That is the only replacement from `flagWords` or `suggestWords`, which are the suggestions
+/// that cost nothing, or else the only suggestion that is cheaper than a plain edit, i.e. one
+/// with swapped or doubled letters, or a change of accents or case. The suggestions are
+/// expected to have the case of the word already, so `TEH` becomes `THE`.
+pub fn confident_fix(suggestions: &[Suggestion]) -> Option<String> {
+    let preferred: Vec<&Suggestion> = suggestions.iter().filter(|s| s.cost == 0).collect();
+    let confident: Vec<&Suggestion> = match preferred.is_empty() {
+        true => suggestions.iter().filter(|s| s.cost < BASE_COST).collect(),
+        false => preferred,
+    };
+    match confident.as_slice() {
+        [suggestion] => Some(suggestion.word.clone()),
+        _ => None,
+    }
+}
+
+/// What to do with an issue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Answer {
+    Replace(String),
+    /// Leave the issue as it is.
+    Keep,
+    /// The word was added to the project words, so the issue is gone.
+    Added,
+}
+
+/// Asks what to do with each issue in `--interactive` mode.
+pub struct Prompt<R, W> {
+    input: R,
+    output: W,
+    /// The configuration the project words are added to.
+    words_config: PathBuf,
+    /// The words added to the project words, which are no longer issues.
+    added: HashSet<String>,
+    /// Set when the user quits, the issues that are left are kept.
+    quit: bool,
+}
+
+impl<R: BufRead, W: Write> Prompt<R, W> {
+    pub fn new(input: R, output: W, words_config: PathBuf) -> Self {
+        Self {
+            input,
+            output,
+            words_config,
+            added: HashSet::new(),
+            quit: false,
+        }
+    }
+
+    /// Shows an issue with its line and suggestions, and reads what to do with it.
+    fn ask(&mut self, path: &Path, issue: &Issue, line: &str, col: usize) -> io::Result<Answer> {
+        if self.added.contains(&issue.word) {
+            return Ok(Answer::Added);
+        }
+        if self.quit {
+            return Ok(Answer::Keep);
+        }
+
+        let out = &mut self.output;
+        writeln!(
+            out,
+            "{}:{}:{} - {}",
+            path.display(),
+            issue.line,
+            issue.col,
+            issue.message()
+        )?;
+        // Tabs are kept so that the marker lines up with the word.
+        let indent: String = line[..col]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        writeln!(out, "    {line}")?;
+        writeln!(
+            out,
+            "    {indent}{}",
+            "^".repeat(issue.word.chars().count())
+        )?;
+        let numbered: Vec<String> = issue
+            .suggestions
+            .iter()
+            .enumerate()
+            .map(|(i, s)| format!("{}) {s}", i + 1))
+            .collect();
+        if !numbered.is_empty() {
+            writeln!(out, "    {}", numbered.join("  "))?;
+        }
+
+        loop {
+            let choices = match issue.suggestions.len() {
+                0 => String::new(),
+                1 => "1, ".to_string(),
+                n => format!("1-{n}, "),
+            };
+            let answer = self.read(&format!(
+                "Replace with [{choices}e]dit, [i]gnore, [a]dd to words or [q]uit: "
+            ))?;
+            let Some(answer) = answer else {
+                self.quit = true;
+                return Ok(Answer::Keep);
+            };
+
+            match answer.as_str() {
+                "" | "i" => return Ok(Answer::Keep),
+                "q" => {
+                    self.quit = true;
+                    return Ok(Answer::Keep);
+                }
+                "e" => match self.read("Replace with: ")? {
+                    Some(word) if !word.is_empty() => return Ok(Answer::Replace(word)),
+                    _ => continue,
+                },
+                "a" => match add_word(&self.words_config, &issue.word) {
+                    Ok(()) => {
+                        self.added.insert(issue.word.clone());
+                        return Ok(Answer::Added);
+                    }
+                    Err(e) => writeln!(self.output, "spellrs: Configuration Error: {e}")?,
+                },
+                number => {
+                    let chosen = number
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|n| issue.suggestions.get(n.wrapping_sub(1)));
+                    if let Some(word) = chosen {
+                        return Ok(Answer::Replace(word.clone()));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Prints a prompt and reads a line, or `None` at the end of the input.
+    fn read(&mut self, prompt: &str) -> io::Result<Option<String>> {
+        write!(self.output, "{prompt}")?;
+        self.output.flush()?;
+        let mut answer = String::new();
+        match self.input.read_line(&mut answer)? {
+            0 => Ok(None),
+            _ => Ok(Some(answer.trim().to_string())),
+        }
+    }
+}
+
+/// Fixes the issues of a file, then writes the file or, with `diff`, prints the changes to
+/// `out`. The issues that are fixed are removed from the report.
+///
+/// Files that are not UTF-8, or that changed since they were checked, are left as they are.
+pub fn fix_file<R: BufRead, W: Write>(
+    report: &mut FileReport,
+    absolute: &Path,
+    options: FixOptions,
+    mut prompt: Option<&mut Prompt<R, W>>,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    if report.issues.is_empty() {
+        return Ok(());
+    }
+    let Ok(text) = String::from_utf8(fs::read(absolute)?) else {
+        return Ok(());
+    };
+
+    let mut edits = vec![];
+    for issue in mem::take(&mut report.issues) {
+        let end = issue.offset + issue.word.len();
+        if issue.kind != IssueKind::UnknownWord
+            || text.get(issue.offset..end) != Some(issue.word.as_str())
+        {
+            report.issues.push(issue);
+            continue;
+        }
+
+        let answer = match (&issue.fix, prompt.as_mut()) {
+            (Some(fix), _) if options.fix => Answer::Replace(fix.clone()),
+            (_, Some(prompt)) => {
+                let start = text[..issue.offset].rfind('\n').map_or(0, |i| i + 1);
+                let line = text[start..].lines().next().unwrap_or_default();
+                prompt.ask(&report.path, &issue, line, issue.offset - start)?
+            }
+            _ => Answer::Keep,
+        };
+        match answer {
+            Answer::Replace(replacement) => edits.push((issue.offset..end, replacement)),
+            Answer::Keep => report.issues.push(issue),
+            Answer::Added => {}
+        }
+    }
+    if edits.is_empty() {
+        return Ok(());
+    }
+
+    let mut fixed = text.clone();
+    for (range, replacement) in edits.into_iter().rev() {
+        fixed.replace_range(range, &replacement);
+    }
+    match options.diff {
+        true => write!(out, "{}", unified_diff(&report.path, &text, &fixed)),
+        false => fs::write(absolute, fixed),
+    }
+}
+
+/// A unified diff of the changes to a file. The lines of the texts are expected to differ
+/// only in their content, as when words are replaced.
+pub fn unified_diff(path: &Path, old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+    let changed: Vec<usize> = (0..old_lines.len())
+        .filter(|&i| old_lines[i] != new_lines[i])
+        .collect();
+
+    // Changes that are close together share a hunk.
+    let mut hunks: Vec<(usize, usize)> = vec![];
+    for &i in &changed {
+        let start = i.saturating_sub(DIFF_CONTEXT);
+        let end = (i + DIFF_CONTEXT + 1).min(old_lines.len());
+        match hunks.last_mut() {
+            Some(hunk) if start <= hunk.1 => hunk.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut diff = format!("--- a/{path}\n+++ b/{path}\n");
+    let mut line = |prefix: char, text: &str| {
+        diff.push(prefix);
+        diff.push_str(text);
+        if !text.ends_with('\n') {
+            diff.push_str("\n\\ No newline at end of file\n");
+        }
+    };
+    for (start, end) in hunks {
+        let count = end - start;
+        line(
+            '@',
+            &format!("@ -{},{count} +{},{count} @@\n", start + 1, start + 1),
+        );
+        let mut i = start;
+        while i < end {
+            // Consecutive changed lines are removed, then added, together.
+            let run = (i..end).take_while(|j| changed.contains(j)).count();
+            match run {
+                0 => line(' ', old_lines[i]),
+                _ => {
+                    old_lines[i..i + run]
+                        .iter()
+                        .for_each(|text| line('-', text));
+                    new_lines[i..i + run]
+                        .iter()
+                        .for_each(|text| line('+', text));
+                }
+            }
+            i += run.max(1);
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod test {
+    use url::Url;
+
+    use super::*;
+
+    fn suggestion(word: &str, cost: u32) -> Suggestion {
+        Suggestion {
+            word: word.to_string(),
+            cost,
+        }
+    }
+
+    #[test]
+    fn test_confident_fix() {
+        let cases = [
+            (vec![], None),
+            (
+                vec![suggestion("the", 75), suggestion("tea", 100)],
+                Some("the"),
+            ),
+            (vec![suggestion("the", 75), suggestion("ten", 90)], None),
+            (vec![suggestion("tea", 100)], None),
+            // Replacements from `flagWords` win over the dictionaries.
+            (
+                vec![suggestion("THE", 0), suggestion("TEN", 90)],
+                Some("THE"),
+            ),
+            (vec![suggestion("the", 0), suggestion("then", 0)], None),
+        ];
+
+        for (suggestions, expected) in cases {
+            let expected = expected.map(String::from);
+            assert_eq!(expected, confident_fix(&suggestions), "{suggestions:?}");
+        }
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let old = "1\n2\n3 teh\n4\n5\n6\n7\n8\n9\n10\n11 teh\n12 teh";
+        let new = "1\n2\n3 the\n4\n5\n6\n7\n8\n9\n10\n11 the\n12 the";
+        let expected = "\
+--- a/src/a b.txt
++++ b/src/a b.txt
+@@ -1,6 +1,6 @@
+ 1
+ 2
+-3 teh
++3 the
+ 4
+ 5
+ 6
+@@ -8,5 +8,5 @@
+ 8
+ 9
+ 10
+-11 teh
+-12 teh
+\\ No newline at end of file
++11 the
++12 the
+\\ No newline at end of file
+";
+        assert_eq!(expected, unified_diff(Path::new("src/a b.txt"), old, new));
+    }
+
+    fn issue(word: &str, offset: usize, suggestions: &[&str], fix: Option<&str>) -> Issue {
+        Issue {
+            word: word.to_string(),
+            offset,
+            line: 1,
+            col: offset + 1,
+            kind: IssueKind::UnknownWord,
+            suggestions: suggestions.iter().map(|s| s.to_string()).collect(),
+            fix: fix.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_fix_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        let text = "TEH helo wrold helo zzyzx";
+        let issues = vec![
+            issue("TEH", 0, &["THE"], Some("THE")),
+            issue("helo", 4, &["hello", "help"], None),
+            issue("wrold", 9, &["world"], None),
+            issue("helo", 15, &["hello", "help"], None),
+            issue("zzyzx", 20, &[], None),
+        ];
+        let report = |issues: &[Issue]| FileReport {
+            path: "a.txt".into(),
+            url: Url::from_file_path(&path).unwrap(),
+            issues: issues.to_vec(),
+        };
+
+        // Only the confident fixes, as a diff.
+        fs::write(&path, text).unwrap();
+        let mut fixed = report(&issues);
+        let mut diff = vec![];
+        let options = FixOptions {
+            fix: true,
+            diff: true,
+        };
+        fix_file::<&[u8], Vec<u8>>(&mut fixed, &path, options, None, &mut diff).unwrap();
+        assert_eq!(
+            "--- a/a.txt\n+++ b/a.txt\n@@ -1,1 +1,1 @@\n\
+             -TEH helo wrold helo zzyzx\n\\ No newline at end of file\n\
+             +THE helo wrold helo zzyzx\n\\ No newline at end of file\n",
+            String::from_utf8(diff).unwrap()
+        );
+        assert_eq!(&issues[1..], fixed.issues);
+        assert_eq!(text, fs::read_to_string(&path).unwrap());
+
+        // Picks a suggestion after an invalid answer, adds a word to the project words, which
+        // also takes care of its later issue, edits a word and quits.
+        let text = "teh helo wrold helo zzyzx";
+        fs::write(&path, text).unwrap();
+        let issues = vec![
+            issue("teh", 0, &["the", "ten"], None),
+            issue("helo", 4, &["hello", "help"], None),
+            issue("wrold", 9, &["world"], None),
+            issue("helo", 15, &["hello", "help"], None),
+            issue("zzyzx", 20, &[], None),
+        ];
+        let config = dir.path().join("cspell.json");
+        let input = "x\n1\na\ne\nworld\nq\n".as_bytes();
+        let mut prompt = Prompt::new(input, vec![], config.clone());
+        let mut fixed = report(&issues);
+        let options = FixOptions::default();
+        fix_file(&mut fixed, &path, options, Some(&mut prompt), &mut vec![]).unwrap();
+
+        assert_eq!(
+            "the helo world helo zzyzx",
+            fs::read_to_string(&path).unwrap()
+        );
+        assert_eq!(&issues[4..], fixed.issues);
+        assert!(fs::read_to_string(&config).unwrap().contains("\"helo\""));
+        let output = String::from_utf8(prompt.output).unwrap();
+        assert!(output.starts_with(
+            "a.txt:1:1 - Unknown word (teh)\n    \
+             teh helo wrold helo zzyzx\n    ^^^\n    1) the  2) ten\n"
+        ));
+    }
+}
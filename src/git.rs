@@ -0,0 +1,271 @@
+use std::{
+    collections::HashMap,
+    ops::Range,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// The files changed in a git repository since a commit, and their changed lines.
+///
+/// Changes are counted from where `HEAD` forked from the commit, so changes made on i.e.
+/// `origin/main` since then don't count. Uncommitted changes and untracked files do.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ChangedFiles {
+    /// The added or changed lines, 1 based, of the files relative to the directory. `None` for
+    /// untracked files, which are new as a whole.
+    files: HashMap<PathBuf, Option<Vec<Range<usize>>>>,
+}
+
+impl ChangedFiles {
+    /// Finds the changes since `since`, a commit, branch or tag, in the repository of `dir`.
+    /// Only the files inside `dir` are included.
+    pub fn since(dir: &Path, since: &str) -> Result<Self, String> {
+        let merge_base = git(dir, &["merge-base", since, "HEAD"])?;
+        let diff = git(
+            dir,
+            &[
+                "-c",
+                "core.quotePath=false",
+                "diff",
+                "--no-color",
+                "--no-ext-diff",
+                "--relative",
+                "--unified=0",
+                // Overrides `diff.noprefix` and `diff.mnemonicPrefix`, `parse_diff` expects `b/`.
+                "--src-prefix=a/",
+                "--dst-prefix=b/",
+                merge_base.trim(),
+            ],
+        )?;
+        let mut changes = parse_diff(&diff);
+
+        let untracked = git(dir, &["ls-files", "-z", "--others", "--exclude-standard"])?;
+        for path in untracked.split('\0').filter(|path| !path.is_empty()) {
+            changes.files.insert(PathBuf::from(path), None);
+        }
+        Ok(changes)
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.files.contains_key(path)
+    }
+
+    /// Whether the 1 based line of a file was added or changed.
+    pub fn is_changed_line(&self, path: &Path, line: usize) -> bool {
+        match self.files.get(path) {
+            Some(Some(lines)) => lines.iter().any(|lines| lines.contains(&line)),
+            Some(None) => true,
+            None => false,
+        }
+    }
+}
+
+/// Runs git in `dir` and returns its output.
+fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("failed to run git: {e}"))?;
+    match output.status.success() {
+        true => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+        false => Err(format!(
+            "git: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+    }
+}
+
+/// Reads the added lines of each file from a `git diff --unified=0`.
+fn parse_diff(diff: &str) -> ChangedFiles {
+    let mut changes = ChangedFiles::default();
+    let mut lines: Option<&mut Vec<Range<usize>>> = None;
+    for line in diff.lines() {
+        if let Some(path) = line.strip_prefix("+++ ") {
+            // Git ends unquoted paths with spaces with a tab.
+            let path = path.strip_suffix('\t').unwrap_or(path);
+            // Deleted files are compared with `/dev/null`.
+            lines = match unquote(path).strip_prefix("b/") {
+                Some(path) => changes
+                    .files
+                    .entry(PathBuf::from(path))
+                    .or_insert_with(|| Some(vec![]))
+                    .as_mut(),
+                None => None,
+            };
+        } else if let Some(hunk) = line.strip_prefix("@@ ") {
+            let added = hunk.split(' ').find_map(|range| range.strip_prefix('+'));
+            if let (Some(lines), Some(added)) = (lines.as_mut(), added) {
+                let (start, count) = added.split_once(',').unwrap_or((added, "1"));
+                let start: usize = start.parse().unwrap_or(0);
+                let count: usize = count.parse().unwrap_or(0);
+                if count > 0 {
+                    lines.push(start..start + count);
+                }
+            }
+        }
+    }
+    changes
+}
+
+/// Unquotes a path that git quoted because of special characters, i.e. `"b/a\"b"`.
+fn unquote(path: &str) -> String {
+    let Some(quoted) = path.strip_prefix('"').and_then(|p| p.strip_suffix('"')) else {
+        return path.to_string();
+    };
+
+    let mut bytes = vec![];
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+            continue;
+        }
+        match chars.next() {
+            Some('n') => bytes.push(b'\n'),
+            Some('t') => bytes.push(b'\t'),
+            Some(d @ '0'..='7') => {
+                // Octal escapes are the bytes of UTF-8 characters.
+                let octal: String = [d].into_iter().chain(chars.by_ref().take(2)).collect();
+                bytes.push(u8::from_str_radix(&octal, 8).unwrap_or(b'?'));
+            }
+            Some(c) => bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+            None => {}
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod test {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_diff() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -3 +3 @@ fn main() {
+-helo
++hello
+@@ -10,0 +11,2 @@
++wrold
++world
+@@ -20,2 +21,0 @@
+-gone
+-gone
+diff --git a/old.txt b/old.txt
+deleted file mode 100644
+--- a/old.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-old
+diff --git \"a/a \\\"b\\\".txt\" \"b/a \\\"b\\\".txt\"
+--- \"a/a \\\"b\\\".txt\"
++++ \"b/a \\\"b\\\".txt\"
+@@ -0,0 +1 @@
++new
+diff --git a/my file.txt b/my file.txt
+--- a/my file.txt	
++++ b/my file.txt	
+@@ -1 +1,2 @@
++spaced
+";
+        let changes = parse_diff(diff);
+        let expected = ChangedFiles {
+            files: HashMap::from([
+                (PathBuf::from("src/lib.rs"), Some(vec![3..4, 11..13])),
+                (PathBuf::from("a \"b\".txt"), Some(vec![1..2])),
+                (PathBuf::from("my file.txt"), Some(vec![1..3])),
+            ]),
+        };
+        assert_eq!(expected, changes);
+    }
+
+    #[test]
+    fn test_unquote() {
+        let cases = [
+            ("b/plain.txt", "b/plain.txt"),
+            ("\"b/tab\\there\"", "b/tab\there"),
+            ("\"b/caf\\303\\251.txt\"", "b/café.txt"),
+            ("\"b/back\\\\slash\"", "b/back\\slash"),
+        ];
+
+        for (path, expected) in cases {
+            assert_eq!(expected, unquote(path), "{path}");
+        }
+    }
+
+    /// Runs git in a fixture repository, with an identity for commits.
+    fn run(dir: &Path, args: &[&str]) {
+        let identity = [
+            "-c",
+            "user.name=spellrs",
+            "-c",
+            "user.email=spellrs@example.com",
+        ];
+        let args: Vec<&str> = identity.iter().chain(args).copied().collect();
+        git(dir, &args).unwrap();
+    }
+
+    #[test]
+    fn test_changed_since() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        run(root, &["init", "--quiet", "--initial-branch=main"]);
+        fs::create_dir(root.join("docs")).unwrap();
+        fs::write(root.join("a.txt"), "one\ntwo\nthree\n").unwrap();
+        fs::write(root.join("docs/b.txt"), "one\n").unwrap();
+        fs::write(root.join("c.txt"), "one\n").unwrap();
+        fs::write(root.join("my file.txt"), "one\n").unwrap();
+        run(root, &["add", "."]);
+        run(root, &["commit", "--quiet", "-m", "first"]);
+
+        // Changes on main after the branch forked don't count.
+        run(root, &["checkout", "--quiet", "-b", "feature"]);
+        run(root, &["checkout", "--quiet", "main"]);
+        fs::write(root.join("c.txt"), "one\ntwo\n").unwrap();
+        run(root, &["commit", "--quiet", "-am", "main"]);
+        run(root, &["checkout", "--quiet", "feature"]);
+
+        // A commit, an uncommitted change and an untracked file.
+        fs::write(root.join("a.txt"), "one\n2\nthree\nfour\n").unwrap();
+        fs::write(root.join("my file.txt"), "one\ntwo\n").unwrap();
+        run(root, &["commit", "--quiet", "-am", "feature"]);
+        fs::write(root.join("docs/b.txt"), "zero\none\n").unwrap();
+        fs::write(root.join("docs/new.txt"), "new\n").unwrap();
+
+        // The diff prefixes don't depend on the user's configuration.
+        run(root, &["config", "diff.noprefix", "true"]);
+
+        let changes = ChangedFiles::since(root, "main").unwrap();
+        let expected = ChangedFiles {
+            files: HashMap::from([
+                (PathBuf::from("a.txt"), Some(vec![2..3, 4..5])),
+                (PathBuf::from("my file.txt"), Some(vec![2..3])),
+                (PathBuf::from("docs/b.txt"), Some(vec![1..2])),
+                (PathBuf::from("docs/new.txt"), None),
+            ]),
+        };
+        assert_eq!(expected, changes);
+        assert!(changes.is_changed_line(Path::new("docs/new.txt"), 10));
+        assert!(!changes.is_changed_line(Path::new("a.txt"), 3));
+
+        // Paths are relative to the directory, which limits the files.
+        let changes = ChangedFiles::since(&root.join("docs"), "main").unwrap();
+        let expected = ChangedFiles {
+            files: HashMap::from([
+                (PathBuf::from("b.txt"), Some(vec![1..2])),
+                (PathBuf::from("new.txt"), None),
+            ]),
+        };
+        assert_eq!(expected, changes);
+
+        assert!(ChangedFiles::since(root, "no-such-branch").is_err());
+    }
+}
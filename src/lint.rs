@@ -0,0 +1,853 @@
+use std::{
+    collections::HashMap,
+    env,
+    error::Error,
+    fs,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::{Arc, Mutex},
+};
+
+use globset::{GlobBuilder, GlobSetBuilder};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use spellrs_filetype::{find_matching_file_types, is_binary_file, is_generated_file};
+use spellrs_url::{has_protocol, FileUrlBuilder, StUrl};
+use walkdir::{DirEntry, WalkDir};
+
+use crate::{
+    cache::{config_key, ResultCache},
+    cli::{LintArgs, Reporter},
+    config::{ConfigLoader, GlobMatcher, Settings},
+    dictionary::{DictionaryCollection, DictionaryLoader, SpellingDictionary},
+    directives::{directive_settings, parse_directives, validate_directives, Directive},
+    fix::{confident_fix, fix_file, FixOptions, Prompt},
+    git::ChangedFiles,
+    patterns::{merge_ranges, overlaps, PatternLoader, TextPatterns},
+    reporter::{write_report, FileReport},
+    text::{extract_words, LineIndex, TextOffset},
+};
+
+/// Words shorter than this are not checked. This is the cspell default.
+pub const MIN_WORD_LENGTH: usize = 4;
+
+/// Directories that are never searched for files.
+const IGNORED_DIRS: [&str; 1] = ["node_modules"];
+
+/// A misspelled word or a problem with a directive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Issue {
+    /// The misspelled word, or the text of the directive the issue is about.
+    pub word: String,
+    /// Byte offset of the word in the file.
+    pub offset: usize,
+    pub line: usize,
+    pub col: usize,
+    pub kind: IssueKind,
+    /// Corrections of an unknown word, when a reporter shows them or the issues are fixed.
+    #[serde(default)]
+    pub suggestions: Vec<String>,
+    /// The correction `--fix` applies, see [`confident_fix`].
+    #[serde(default)]
+    pub fix: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IssueKind {
+    UnknownWord,
+    /// A `cspell:` directive that is misspelled, is missing its value or has no effect, and
+    /// the message describing it.
+    Directive(String),
+}
+
+impl Issue {
+    /// Describes the issue, i.e. `Unknown word (teh)`.
+    pub fn message(&self) -> String {
+        match &self.kind {
+            IssueKind::UnknownWord => format!("Unknown word ({})", self.word),
+            IssueKind::Directive(message) => message.clone(),
+        }
+    }
+}
+
+/// How to check the text of a file.
+#[derive(Debug, Clone)]
+pub struct CheckOptions {
+    /// Words shorter than this are not checked.
+    pub min_word_length: usize,
+    /// The opposite of `caseSensitive`.
+    pub ignore_case: bool,
+    /// The text to skip, from `ignoreRegExpList` and `includeRegExpList`.
+    pub patterns: TextPatterns,
+}
+
+impl CheckOptions {
+    /// The options from the settings of a file, with the patterns loaded for them.
+    pub fn new(settings: &Settings, patterns: TextPatterns) -> Self {
+        Self {
+            min_word_length: settings.min_word_length.unwrap_or(MIN_WORD_LENGTH),
+            ignore_case: !settings.case_sensitive.unwrap_or(false),
+            patterns,
+        }
+    }
+}
+
+impl Default for CheckOptions {
+    fn default() -> Self {
+        Self {
+            min_word_length: MIN_WORD_LENGTH,
+            ignore_case: true,
+            patterns: TextPatterns::default(),
+        }
+    }
+}
+
+/// Runs `spellrs lint`. Exits with a failure when any issues are found.
+pub fn run(args: &LintArgs) -> Result<ExitCode, Box<dyn Error>> {
+    if args.diff && !args.fix && !args.interactive {
+        return Err("--diff requires --fix or --interactive".into());
+    }
+    let cwd = env::current_dir()?;
+    let mut configs = ConfigLoader::default();
+    let root_config = match &args.config {
+        Some(path) => Some(path.clone()),
+        None => configs.search(&cwd),
+    };
+    let root_settings = match &root_config {
+        Some(path) => configs.load(path),
+        None => Arc::new(Settings::default()),
+    };
+
+    let (stdin_paths, file_globs): (Vec<_>, Vec<_>) = args
+        .globs
+        .iter()
+        .partition(|glob| stdin_path(glob).is_some());
+    let stdin = match stdin_paths.as_slice() {
+        [] => None,
+        [glob] => Some((stdin_path(glob).unwrap_or_default(), read_stdin()?)),
+        _ => return Err("stdin can only be checked once".into()),
+    };
+    if stdin.is_some() && args.interactive {
+        return Err("--interactive can't be used with stdin".into());
+    }
+
+    let globs: Vec<String> = match args.globs.is_empty() {
+        true => root_settings
+            .files
+            .iter()
+            .map(|def| match def.root.strip_prefix(&cwd) {
+                Ok(dir) if !dir.as_os_str().is_empty() => {
+                    format!("{}/{}", dir.display(), def.glob)
+                }
+                _ => def.glob.clone(),
+            })
+            .collect(),
+        false => file_globs.into_iter().cloned().collect(),
+    };
+    if globs.is_empty() && stdin.is_none() {
+        return Err("no files to check, pass a glob or set `files` in the configuration".into());
+    }
+
+    let dictionaries = DictionaryLoader::default();
+    let mut extra = vec![];
+    for path in &args.dictionaries {
+        extra.push(dictionaries.load(path)?);
+    }
+
+    let changes = match &args.changed_since {
+        Some(since) => Some(ChangedFiles::since(&cwd, since)?),
+        None => None,
+    };
+
+    let ignore = GlobMatcher::new(&root_settings.ignore_paths)?;
+    let mut files = match globs.is_empty() {
+        true => vec![],
+        false => find_files(&cwd, &globs, args.dot, &ignore)?,
+    };
+    if let Some(changes) = &changes {
+        files.retain(|path| changes.contains(path));
+    }
+    let mut cache = match args.cache {
+        true => Some(ResultCache::load(
+            &cwd.join(&args.cache_location),
+            args.cache_strategy,
+        )),
+        false => None,
+    };
+
+    // Settings are resolved up front, the files are then checked in parallel.
+    let mut jobs = vec![];
+    // The `ignorePaths` of each configuration, compiled once.
+    let mut ignores: HashMap<String, GlobMatcher> = HashMap::new();
+    let files = files.into_iter().map(|path| (path, None));
+    for (path, text) in files.chain(stdin.map(|(path, text)| (path, Some(text)))) {
+        let absolute = cwd.join(&path);
+        let config = match &root_config {
+            Some(config) if args.config.is_some() => Some(config.clone()),
+            _ => absolute.parent().and_then(|dir| configs.search(dir)),
+        };
+        let settings = match config {
+            Some(config) => configs.load(&config).for_file(&absolute),
+            None => Settings::default(),
+        };
+        let settings = settings.for_language(&find_matching_file_types(&path.to_string_lossy()));
+
+        let key = serde_json::to_string(&settings.ignore_paths)?;
+        if !ignores.contains_key(&key) {
+            ignores.insert(key.clone(), GlobMatcher::new(&settings.ignore_paths)?);
+        }
+        let ignored = ignores[&key].is_match(&absolute);
+        if settings.enabled == Some(false) || ignored {
+            continue;
+        }
+        jobs.push(FileJob {
+            path,
+            absolute,
+            settings,
+            text,
+        });
+    }
+
+    let checker = Checker {
+        dictionaries,
+        collections: Mutex::new(HashMap::new()),
+        patterns: Mutex::new(PatternLoader::default()),
+        extra,
+        extra_paths: &args.dictionaries,
+        cache: cache.as_ref(),
+        suggest: args.reporter != Reporter::Default || args.fix || args.interactive,
+    };
+    let results: Vec<io::Result<FileResult>> =
+        jobs.par_iter().map(|job| checker.check(job)).collect();
+    let Checker {
+        mut dictionaries,
+        patterns,
+        ..
+    } = checker;
+
+    let urls = FileUrlBuilder::default();
+    let mut reports = vec![];
+    // Stdin can't be fixed, only the files.
+    let mut fixable = vec![];
+    let mut read_errors = 0;
+    for (job, result) in jobs.into_iter().zip(results) {
+        // A file that can't be read is reported, the other files are still checked.
+        let FileResult {
+            mut issues,
+            cache_keys,
+        } = match result {
+            Ok(result) => result,
+            Err(e) => {
+                eprintln!("spellrs: Unable to read file {}: {e}", job.path.display());
+                read_errors += 1;
+                continue;
+            }
+        };
+        if let (Some(cache), Some((file_key, config_key))) = (&mut cache, cache_keys) {
+            cache.set(&job.absolute, file_key, config_key, issues.clone());
+        }
+        if let Some(changes) = changes.as_ref().filter(|_| args.changed_lines) {
+            // Stdin is not part of the diff, so all of its lines count.
+            if job.text.is_none() {
+                issues.retain(|issue| changes.is_changed_line(&job.path, issue.line));
+            }
+        }
+        fixable.push(job.text.is_none());
+        reports.push(FileReport {
+            url: urls.path_to_file_url(&job.absolute.to_string_lossy(), None),
+            path: job.path,
+            issues,
+        });
+    }
+
+    let mut out = io::stdout().lock();
+    if args.fix || args.interactive {
+        let options = FixOptions {
+            fix: args.fix,
+            diff: args.diff,
+        };
+        let words_config = root_config
+            .clone()
+            .unwrap_or_else(|| cwd.join("cspell.json"));
+        let mut prompt = args
+            .interactive
+            .then(|| Prompt::new(io::stdin().lock(), io::stderr(), words_config));
+        for (report, _) in reports.iter_mut().zip(&fixable).filter(|(_, &f)| f) {
+            let absolute = cwd.join(&report.path);
+            fix_file(report, &absolute, options, prompt.as_mut(), &mut out)?;
+        }
+        out.flush()?;
+    }
+
+    let root = urls.to_fil_dir_url(&cwd.to_string_lossy().as_ref().into(), None);
+    match args.diff {
+        // Keep stdout a diff that can be applied.
+        true => write_report(&mut io::stderr().lock(), args.reporter, &root, &reports)?,
+        false => write_report(&mut out, args.reporter, &root, &reports)?,
+    }
+    out.flush()?;
+
+    if let Some(cache) = &mut cache {
+        if let Err(e) = cache.save() {
+            eprintln!("spellrs: Cache Error: {e}");
+        }
+    }
+    for error in configs.take_errors() {
+        eprintln!("spellrs: Configuration Error: {error}");
+    }
+    for error in patterns.into_inner().unwrap().take_errors() {
+        eprintln!("spellrs: Configuration Error: {error}");
+    }
+    for error in dictionaries.take_errors() {
+        eprintln!("spellrs: Dictionary Error: {error}");
+    }
+
+    let files_checked = reports.len();
+    let issue_count: usize = reports.iter().map(|r| r.issues.len()).sum();
+    let files_with_issues = reports.iter().filter(|r| !r.issues.is_empty()).count();
+    if !args.no_summary {
+        eprintln!(
+            "Files checked: {files_checked}, Issues found: {issue_count} in {files_with_issues} files."
+        );
+    }
+
+    match issue_count + read_errors {
+        0 => Ok(ExitCode::SUCCESS),
+        _ => Ok(ExitCode::FAILURE),
+    }
+}
+
+/// A file to check, with its settings before the directives in the file.
+struct FileJob {
+    /// Relative to the current directory.
+    path: PathBuf,
+    absolute: PathBuf,
+    settings: Settings,
+    /// The text to check instead of the content of the file, i.e. from stdin.
+    text: Option<String>,
+}
+
+struct FileResult {
+    issues: Vec<Issue>,
+    /// The keys to cache the issues under, if they were not already cached.
+    cache_keys: Option<(String, String)>,
+}
+
+/// Checks files on several threads. Dictionaries and patterns are loaded once and shared.
+struct Checker<'a> {
+    dictionaries: DictionaryLoader,
+    /// The dictionaries built for the settings of the files, by the settings as JSON.
+    collections: Mutex<HashMap<String, Arc<DictionaryCollection>>>,
+    patterns: Mutex<PatternLoader>,
+    /// Dictionaries from the command line.
+    extra: Vec<Arc<dyn SpellingDictionary>>,
+    extra_paths: &'a [PathBuf],
+    cache: Option<&'a ResultCache>,
+    /// Whether to suggest corrections of the unknown words, and find their fixes.
+    suggest: bool,
+}
+
+impl Checker<'_> {
+    fn check(&self, job: &FileJob) -> io::Result<FileResult> {
+        let text = match &job.text {
+            Some(text) => text.clone(),
+            None => read_text(&job.absolute)?,
+        };
+        let directives = parse_directives(&text);
+        let settings = job.settings.merge(&directive_settings(&directives));
+
+        // The key includes the settings of the directives, i.e. a dictionary that only the
+        // file enables.
+        let cache_keys = match self.cache.filter(|_| job.text.is_none()) {
+            Some(cache) => Some((
+                cache.file_key(&job.absolute)?,
+                config_key(&settings, self.extra_paths, self.suggest),
+            )),
+            None => None,
+        };
+        let cached =
+            self.cache
+                .zip(cache_keys.as_ref())
+                .and_then(|(cache, (file_key, config_key))| {
+                    cache.get(&job.absolute, file_key, config_key)
+                });
+        if let Some(issues) = cached {
+            return Ok(FileResult {
+                issues: issues.to_vec(),
+                cache_keys: None,
+            });
+        }
+
+        let dictionary = self.dictionaries_for(&settings);
+        let patterns = self.patterns.lock().unwrap().for_settings(&settings);
+        let options = CheckOptions::new(&settings, patterns);
+
+        let mut issues = check_text(&text, &directives, dictionary.as_ref(), &options);
+        if self.suggest {
+            let suggest_options = settings.suggest_options();
+            for issue in &mut issues {
+                if issue.kind == IssueKind::UnknownWord {
+                    let suggestions = dictionary.suggest(&issue.word, &suggest_options);
+                    issue.fix = confident_fix(&suggestions);
+                    issue.suggestions = suggestions.into_iter().map(|s| s.word).collect();
+                }
+            }
+        }
+
+        Ok(FileResult { issues, cache_keys })
+    }
+
+    /// The dictionaries of the settings, built once for all the files that share them.
+    fn dictionaries_for(&self, settings: &Settings) -> Arc<DictionaryCollection> {
+        let key = serde_json::to_string(settings).unwrap_or_default();
+        if let Some(dictionary) = self.collections.lock().unwrap().get(&key) {
+            return dictionary.clone();
+        }
+
+        // Built without the lock, other files don't wait for the words of this one.
+        let dictionary = Arc::new(self.dictionaries.for_settings(settings, &self.extra));
+        self.collections
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_insert(dictionary)
+            .clone()
+    }
+}
+
+/// The path to check stdin as for `stdin`, `stdin:path` and `stdin://path` globs. The path
+/// picks the file type and the configuration. It is `stdin` when there is none.
+fn stdin_path(glob: &str) -> Option<PathBuf> {
+    if glob != "stdin" && !has_protocol(&StUrl::Str(glob), "stdin") {
+        return None;
+    }
+    let path = glob["stdin".len()..].strip_prefix(':').unwrap_or_default();
+    let path = path.strip_prefix("//").unwrap_or(path);
+    match path.is_empty() {
+        true => Some(PathBuf::from("stdin")),
+        false => Some(PathBuf::from(path)),
+    }
+}
+
+/// Finds the files to check. Paths are relative to `root`, sorted and without duplicates.
+///
+/// Each glob is either a file, a directory (all of its files), or a pattern like `src/**/*.rs`.
+/// Binary and generated files are skipped, as are the paths matching `ignore`. So are hidden
+/// files, unless `dot` is set or they are named explicitly.
+pub fn find_files(
+    root: &Path,
+    globs: &[String],
+    dot: bool,
+    ignore: &GlobMatcher,
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut files = vec![];
+    let mut patterns = GlobSetBuilder::new();
+    let mut has_patterns = false;
+
+    for glob in globs {
+        let glob = glob.strip_prefix("./").unwrap_or(glob);
+        let path = root.join(glob);
+        if path.is_file() {
+            files.push(PathBuf::from(glob));
+            continue;
+        }
+
+        let pattern = match path.is_dir() {
+            true => format!("{}/**", glob.trim_end_matches('/')),
+            false => glob.to_string(),
+        };
+        patterns.add(GlobBuilder::new(&pattern).literal_separator(true).build()?);
+        has_patterns = true;
+    }
+
+    if has_patterns {
+        let patterns = patterns.build()?;
+        let walker = WalkDir::new(root)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|entry| {
+                entry.depth() == 0 || !(is_ignored(entry, dot) || ignore.is_match(entry.path()))
+            });
+
+        for entry in walker {
+            let entry = entry?;
+            let path = entry.path().strip_prefix(root)?;
+            if entry.file_type().is_file() && patterns.is_match(path) {
+                files.push(path.to_path_buf());
+            }
+        }
+    }
+
+    files.sort();
+    files.dedup();
+    files.retain(|path| {
+        let name = path.to_string_lossy();
+        !is_binary_file(&name) && !is_generated_file(&name)
+    });
+
+    Ok(files)
+}
+
+fn is_ignored(entry: &DirEntry, dot: bool) -> bool {
+    let name = entry.file_name().to_string_lossy();
+    (!dot && name.starts_with('.'))
+        || (entry.file_type().is_dir() && IGNORED_DIRS.contains(&name.as_ref()))
+}
+
+fn read_text(path: &Path) -> io::Result<String> {
+    let bytes = fs::read(path)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Reads all of stdin, like [`read_text`].
+fn read_stdin() -> io::Result<String> {
+    let mut bytes = vec![];
+    io::stdin().read_to_end(&mut bytes)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Finds the words in `text` that are not in the dictionary, and the problems with its
+/// directives. Words that are too short, that overlap the text excluded by the patterns or that
+/// are part of a directive are not checked.
+///
+/// `directives` are the [`parse_directives`] of the text. The dictionary is expected to have
+/// their words, see [`directive_settings`].
+pub fn check_text(
+    text: &str,
+    directives: &[Directive],
+    dictionary: &impl SpellingDictionary,
+    options: &CheckOptions,
+) -> Vec<Issue> {
+    let index = LineIndex::new(text);
+    let mut excluded = options.patterns.excluded_ranges(text);
+    excluded.extend(directives.iter().map(|d| d.range.clone()));
+    let excluded = merge_ranges(excluded);
+
+    let words: Vec<TextOffset> = extract_words(text)
+        .filter(|word| !overlaps(&excluded, &(word.offset..word.offset + word.text.len())))
+        .collect();
+    let unknown_words = words
+        .iter()
+        .filter(|word| word.text.chars().count() >= options.min_word_length)
+        .filter(|word| !dictionary.has(word.text, options.ignore_case))
+        .map(|word| (word.text.to_string(), word.offset, IssueKind::UnknownWord));
+    let directive_issues = validate_directives(directives, &words)
+        .into_iter()
+        .map(|issue| {
+            (
+                issue.text,
+                issue.offset,
+                IssueKind::Directive(issue.message),
+            )
+        });
+
+    let mut issues: Vec<Issue> = unknown_words
+        .chain(directive_issues)
+        .map(|(word, offset, kind)| {
+            let position = index.position(offset);
+            Issue {
+                word,
+                offset,
+                line: position.line,
+                col: position.col,
+                kind,
+                suggestions: vec![],
+                fix: None,
+            }
+        })
+        .collect();
+    issues.sort_by_key(|issue| issue.offset);
+    issues
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{cli::CacheStrategy, config::GlobDef, dictionary::HunspellSpellingDictionary};
+
+    fn en_sample() -> HunspellSpellingDictionary {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("spellrs_hunspell")
+            .join("testdata")
+            .join("en_sample.dic");
+        HunspellSpellingDictionary::from_dic_file(&path).unwrap()
+    }
+
+    #[test]
+    fn test_check_text() {
+        let dictionary = en_sample();
+        let text = "Hello boxes\n  tried to wrk, and\nreworked alot of nonsense";
+        let issues: Vec<(&str, usize, usize)> = check_text(
+            text,
+            &parse_directives(text),
+            &dictionary,
+            &CheckOptions::default(),
+        )
+        .iter()
+        .map(|issue| {
+            (
+                &text[issue.offset..][..issue.word.len()],
+                issue.line,
+                issue.col,
+            )
+        })
+        .collect();
+
+        assert_eq!(vec![("alot", 3, 10)], issues);
+
+        let cases = [
+            (MIN_WORD_LENGTH, vec!["Helllo", "world"]),
+            (6, vec!["Helllo"]),
+            (1, vec!["Helllo", "a", "world"]),
+        ];
+
+        for (min_word_length, expected) in cases {
+            let options = CheckOptions {
+                min_word_length,
+                ..Default::default()
+            };
+            let issues = check_text("Helllo a world", &[], &dictionary, &options);
+            let words: Vec<&str> = issues.iter().map(|i| i.word.as_str()).collect();
+            assert_eq!(expected, words, "{min_word_length}");
+        }
+    }
+
+    #[test]
+    fn test_check_text_patterns() {
+        let dictionary = en_sample();
+        let settings: Settings = serde_json::from_value(serde_json::json!({
+            "ignoreRegExpList": ["/wrk\\w*/"],
+        }))
+        .unwrap();
+        let options = CheckOptions {
+            patterns: PatternLoader::default().for_settings(&settings),
+            ..Default::default()
+        };
+        let text = "Hello https://exampel.com/pathh wrkflow boxes\n\
+            sha 9fceb02d0ae598e95dc970b74767f19372d61af8 alot\n\
+            // cspell:disable-next-line\n\
+            nonsenze";
+        let words: Vec<String> = check_text(text, &parse_directives(text), &dictionary, &options)
+            .into_iter()
+            .map(|i| i.word)
+            .collect();
+        assert_eq!(vec!["alot"], words);
+    }
+
+    #[test]
+    fn test_check_text_directives() {
+        let dictionary = en_sample();
+        let text = "// cspell:ignore wrk, unusedword
+            wrk boxes nonsenze
+            /* cspell:disable-next-line */
+            thiss line is skipped
+            // cspell:wrods zzz";
+        let directives = parse_directives(text);
+        let settings = directive_settings(&directives);
+        let loader = DictionaryLoader::default();
+        let dictionary = loader.for_settings(&settings, &[Arc::new(dictionary)]);
+        let options = CheckOptions {
+            patterns: PatternLoader::default().for_settings(&settings),
+            ..Default::default()
+        };
+
+        let issues: Vec<(usize, String)> = check_text(text, &directives, &dictionary, &options)
+            .iter()
+            .map(|issue| (issue.line, issue.message()))
+            .collect();
+        assert_eq!(
+            vec![
+                (1, "Unused word (unusedword)".to_string()),
+                (2, "Unknown word (nonsenze)".to_string()),
+                (
+                    5,
+                    "Unknown directive (wrods), did you mean `words`?".to_string()
+                ),
+            ],
+            issues
+        );
+    }
+
+    #[test]
+    fn test_find_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for file in [
+            "README.md",
+            "src/main.rs",
+            "src/lib/util.rs",
+            "src/image.png",
+            "src/.hidden.rs",
+            ".config/settings.rs",
+            "node_modules/pkg/index.rs",
+            "Cargo.lock",
+        ] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+
+        let cases: [(&[&str], bool, &[&str]); 8] = [
+            (&["**/*.rs"], false, &["src/lib/util.rs", "src/main.rs"]),
+            (&["*.rs"], false, &[]),
+            (&["src/*.rs"], false, &["src/main.rs"]),
+            (
+                &["**/*.rs"],
+                true,
+                &[
+                    ".config/settings.rs",
+                    "src/.hidden.rs",
+                    "src/lib/util.rs",
+                    "src/main.rs",
+                ],
+            ),
+            (
+                &["src", "./README.md"],
+                false,
+                &["README.md", "src/lib/util.rs", "src/main.rs"],
+            ),
+            (
+                &["src/.hidden.rs", "src/main.rs"],
+                false,
+                &["src/.hidden.rs", "src/main.rs"],
+            ),
+            (
+                &["**"],
+                false,
+                &["README.md", "src/lib/util.rs", "src/main.rs"],
+            ),
+            (&["src/lib"], false, &[]),
+        ];
+
+        let ignore = GlobMatcher::new(&[GlobDef {
+            glob: "lib".into(),
+            root: root.to_path_buf(),
+        }])
+        .unwrap();
+        for (globs, dot, expected) in cases {
+            let globs: Vec<String> = globs.iter().map(|g| g.to_string()).collect();
+            let ignore = match globs[0].as_str() {
+                "src/lib" => &ignore,
+                _ => &GlobMatcher::default(),
+            };
+            let files = find_files(root, &globs, dot, ignore).unwrap();
+            let expected: Vec<PathBuf> = expected.iter().map(PathBuf::from).collect();
+            assert_eq!(expected, files, "{globs:?}");
+        }
+    }
+
+    #[test]
+    fn test_stdin_path() {
+        let cases = [
+            ("stdin", Some("stdin")),
+            ("stdin:", Some("stdin")),
+            ("stdin://", Some("stdin")),
+            ("stdin://src/foo.ts", Some("src/foo.ts")),
+            ("stdin:src/foo.ts", Some("src/foo.ts")),
+            ("stdin:///tmp/foo.ts", Some("/tmp/foo.ts")),
+            ("stdin.txt", None),
+            ("src/**/*.ts", None),
+        ];
+
+        for (glob, expected) in cases {
+            assert_eq!(expected.map(PathBuf::from), stdin_path(glob), "{glob}");
+        }
+    }
+
+    #[test]
+    fn test_checker() {
+        let dir = tempfile::tempdir().unwrap();
+        let dic = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("spellrs_hunspell")
+            .join("testdata")
+            .join("en_sample.dic");
+        let dictionaries = DictionaryLoader::default();
+        let extra = vec![dictionaries.load(&dic).unwrap()];
+
+        let words = ["zebra", "alot", "wrold", "qwerty"];
+        let jobs: Vec<FileJob> = (0..64)
+            .map(|i| {
+                let path = PathBuf::from(format!("{i:02}.txt"));
+                let absolute = dir.path().join(&path);
+                fs::write(&absolute, format!("hello {}", words[i % words.len()])).unwrap();
+                FileJob {
+                    path,
+                    absolute,
+                    settings: Settings::default(),
+                    text: None,
+                }
+            })
+            .collect();
+
+        let checker = Checker {
+            dictionaries,
+            collections: Mutex::new(HashMap::new()),
+            patterns: Mutex::new(PatternLoader::default()),
+            extra,
+            extra_paths: &[],
+            cache: None,
+            suggest: false,
+        };
+        let results: Vec<Vec<String>> = jobs
+            .par_iter()
+            .map(|job| checker.check(job).unwrap().issues)
+            .map(|issues| issues.into_iter().map(|issue| issue.word).collect())
+            .collect();
+
+        // The results are in the order of the files, whichever thread checked them.
+        for (i, issues) in results.iter().enumerate() {
+            assert_eq!(vec![words[i % words.len()]], *issues, "{i}");
+        }
+        // The files share their settings, and so their dictionaries.
+        assert_eq!(1, checker.collections.lock().unwrap().len());
+    }
+
+    #[test]
+    fn test_checker_cache_directive_dictionary() {
+        let dir = tempfile::tempdir().unwrap();
+        let words = dir.path().join("words.txt");
+        fs::write(&words, "hello").unwrap();
+        let settings: Settings = serde_json::from_value(serde_json::json!({
+            "dictionaryDefinitions": [{ "name": "words", "path": words }],
+        }))
+        .unwrap();
+        let absolute = dir.path().join("a.txt");
+        fs::write(&absolute, "// cspell:dictionaries words\nhello world").unwrap();
+        let job = FileJob {
+            path: PathBuf::from("a.txt"),
+            absolute,
+            settings,
+            text: None,
+        };
+
+        let mut cache = ResultCache::load(&dir.path().join(".cspellcache"), CacheStrategy::Content);
+        let check = |cache: &ResultCache| {
+            let checker = Checker {
+                dictionaries: DictionaryLoader::default(),
+                collections: Mutex::new(HashMap::new()),
+                patterns: Mutex::new(PatternLoader::default()),
+                extra: vec![],
+                extra_paths: &[],
+                cache: Some(cache),
+                suggest: false,
+            };
+            checker.check(&job).unwrap()
+        };
+
+        let result = check(&cache);
+        let words_of = |issues: &[Issue]| -> Vec<String> {
+            issues.iter().map(|issue| issue.word.clone()).collect()
+        };
+        assert_eq!(vec!["world"], words_of(&result.issues));
+        let (file_key, config_key) = result.cache_keys.unwrap();
+        cache.set(&job.absolute, file_key, config_key, result.issues);
+        assert!(check(&cache).cache_keys.is_none());
+
+        // The dictionary enabled by the directive changed, the cached result no longer holds.
+        fs::write(&words, "hello\nworld").unwrap();
+        let result = check(&cache);
+        assert!(result.cache_keys.is_some());
+        assert!(result.issues.is_empty());
+    }
+}
@@ -0,0 +1,689 @@
+use std::{
+    collections::HashMap,
+    env,
+    error::Error,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::Arc,
+};
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+        Notification as _, PublishDiagnostics,
+    },
+    request::{CodeActionRequest, ExecuteCommand, Request as _},
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams,
+    CodeActionProviderCapability, Command, Diagnostic, DiagnosticSeverity,
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DidSaveTextDocumentParams, ExecuteCommandOptions, ExecuteCommandParams, InitializeParams,
+    Position, PublishDiagnosticsParams, Range, ServerCapabilities, TextDocumentSyncCapability,
+    TextDocumentSyncKind, TextEdit, Url, WorkspaceEdit,
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::json;
+use spellrs_filetype::find_matching_file_types;
+use spellrs_url::{file_url_to_path, is_file_url, url_basename, StUrl};
+
+use crate::{
+    config::{add_word, ConfigLoader, GlobMatcher, Settings, CONFIG_FILE_NAMES},
+    dictionary::{DictionaryLoader, SpellingDictionary},
+    directives::{directive_settings, parse_directives, Directive},
+    lint::{check_text, CheckOptions, Issue, IssueKind},
+    patterns::PatternLoader,
+};
+
+/// The `source` of the diagnostics.
+const SOURCE: &str = "spellrs";
+
+/// Adds a word to the `words` of the configuration of a document. The arguments are the URI
+/// of the document and the word.
+const ADD_TO_WORKSPACE: &str = "spellrs.addWordToWorkspace";
+
+/// Adds a word to the user dictionary. The arguments are the URI of the document and the word.
+const ADD_TO_USER_DICTIONARY: &str = "spellrs.addWordToUserDictionary";
+
+/// The `initializationOptions` of the client.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct InitializationOptions {
+    /// The word list of the user dictionary. Defaults to `spellrs/words.txt` in the user's
+    /// configuration directory.
+    user_dictionary: Option<PathBuf>,
+}
+
+/// Runs `spellrs lsp`, a language server on stdin and stdout.
+pub fn run() -> Result<ExitCode, Box<dyn Error>> {
+    let (connection, io_threads) = Connection::stdio();
+    serve(&connection)?;
+    drop(connection);
+    io_threads.join()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Handles the messages of a client until it shuts the server down.
+fn serve(connection: &Connection) -> Result<(), Box<dyn Error>> {
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        execute_command_provider: Some(ExecuteCommandOptions {
+            commands: vec![ADD_TO_WORKSPACE.into(), ADD_TO_USER_DICTIONARY.into()],
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let params = connection.initialize(serde_json::to_value(capabilities)?)?;
+    let mut server = Server::new(connection, serde_json::from_value(params)?);
+
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    break;
+                }
+                server.handle_request(request)?;
+            }
+            Message::Notification(notification) => server.handle_notification(notification)?,
+            Message::Response(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Checks the open documents. Configurations and dictionaries are loaded once and reloaded
+/// when a configuration is saved or a word is added.
+struct Server<'a> {
+    connection: &'a Connection,
+    /// The workspace directory, where a configuration is created for documents without one.
+    root: PathBuf,
+    user_dictionary: Option<PathBuf>,
+    /// The words of the user dictionary.
+    user_words: Vec<String>,
+    /// The text of the open documents.
+    documents: HashMap<Url, String>,
+    configs: ConfigLoader,
+    dictionaries: DictionaryLoader,
+    patterns: PatternLoader,
+}
+
+impl<'a> Server<'a> {
+    fn new(connection: &'a Connection, params: InitializeParams) -> Self {
+        #[allow(deprecated)]
+        let root = params
+            .workspace_folders
+            .iter()
+            .flatten()
+            .map(|folder| &folder.uri)
+            .chain(&params.root_uri)
+            .find_map(|uri| file_url_to_path(uri.as_str()).ok())
+            .or_else(|| env::current_dir().ok())
+            .unwrap_or_default();
+        let options: InitializationOptions = params
+            .initialization_options
+            .and_then(|options| serde_json::from_value(options).ok())
+            .unwrap_or_default();
+        let user_dictionary = options.user_dictionary.or_else(default_user_dictionary);
+
+        let mut server = Self {
+            connection,
+            root,
+            user_words: vec![],
+            user_dictionary,
+            documents: HashMap::new(),
+            configs: ConfigLoader::default(),
+            dictionaries: DictionaryLoader::default(),
+            patterns: PatternLoader::default(),
+        };
+        server.user_words = server.read_user_words();
+        server
+    }
+
+    fn handle_request(&mut self, request: Request) -> Result<(), Box<dyn Error>> {
+        let Request { id, method, params } = request;
+        let response = match method.as_str() {
+            CodeActionRequest::METHOD => match serde_json::from_value(params) {
+                Ok(params) => Response::new_ok(id, self.code_actions(params)),
+                Err(e) => invalid_params(id, &method, e),
+            },
+            ExecuteCommand::METHOD => match serde_json::from_value(params) {
+                Ok(params) => match self.execute(params) {
+                    Ok(()) => Response::new_ok(id, ()),
+                    Err(e) => Response::new_err(id, ErrorCode::RequestFailed as i32, e),
+                },
+                Err(e) => invalid_params(id, &method, e),
+            },
+            method => Response::new_err(
+                id,
+                ErrorCode::MethodNotFound as i32,
+                format!("unknown request {method}"),
+            ),
+        };
+        self.connection.sender.send(response.into())?;
+        Ok(())
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> Result<(), Box<dyn Error>> {
+        let Notification { method, params } = notification;
+        match method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let Some(params): Option<DidOpenTextDocumentParams> =
+                    notification_params(&method, params)
+                else {
+                    return Ok(());
+                };
+                let document = params.text_document;
+                self.documents.insert(document.uri.clone(), document.text);
+                self.publish(&document.uri)?;
+            }
+            DidChangeTextDocument::METHOD => {
+                let Some(params): Option<DidChangeTextDocumentParams> =
+                    notification_params(&method, params)
+                else {
+                    return Ok(());
+                };
+                // Changes are always the whole text, see `TextDocumentSyncKind::FULL`.
+                let Some(change) = params.content_changes.into_iter().last() else {
+                    return Ok(());
+                };
+                let uri = params.text_document.uri;
+                self.documents.insert(uri.clone(), change.text);
+                self.publish(&uri)?;
+            }
+            DidCloseTextDocument::METHOD => {
+                let Some(params): Option<DidCloseTextDocumentParams> =
+                    notification_params(&method, params)
+                else {
+                    return Ok(());
+                };
+                let uri = params.text_document.uri;
+                self.documents.remove(&uri);
+                self.send_diagnostics(uri, vec![])?;
+            }
+            DidSaveTextDocument::METHOD => {
+                let Some(params): Option<DidSaveTextDocumentParams> =
+                    notification_params(&method, params)
+                else {
+                    return Ok(());
+                };
+                let path = file_url_to_path(params.text_document.uri.as_str());
+                let is_config = path
+                    .is_ok_and(|path| CONFIG_FILE_NAMES.iter().any(|name| path.ends_with(name)));
+                if is_config {
+                    self.reload()?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Returns the settings of a document, including its directives, or `None` when it is not
+    /// checked.
+    fn settings(&mut self, uri: &Url, directives: &[Directive]) -> Option<Settings> {
+        let path = self.path(uri);
+        let dir = path.as_deref().and_then(Path::parent).unwrap_or(&self.root);
+        let settings = match self.configs.search(dir) {
+            Some(config) => self.configs.load(&config),
+            None => Arc::new(Settings::default()),
+        };
+        let settings = match &path {
+            Some(path) => settings.for_file(path),
+            None => (*settings).clone(),
+        };
+        let file_types = find_matching_file_types(&url_basename(&StUrl::Url(uri.clone())));
+        let settings = settings.for_language(&file_types).merge(&Settings {
+            user_words: self.user_words.clone(),
+            ..Default::default()
+        });
+
+        let ignored = path.as_ref().is_some_and(|path| {
+            GlobMatcher::new(&settings.ignore_paths).is_ok_and(|m| m.is_match(path))
+        });
+        if settings.enabled == Some(false) || ignored {
+            return None;
+        }
+        Some(settings.merge(&directive_settings(directives)))
+    }
+
+    /// The path of a `file:` URI.
+    fn path(&self, uri: &Url) -> Option<PathBuf> {
+        match is_file_url(&StUrl::Url(uri.clone())) {
+            true => file_url_to_path(uri.as_str()).ok(),
+            false => None,
+        }
+    }
+
+    /// Checks a document and sends its diagnostics.
+    fn publish(&mut self, uri: &Url) -> Result<(), Box<dyn Error>> {
+        let Some(text) = self.documents.get(uri).cloned() else {
+            return Ok(());
+        };
+        let directives = parse_directives(&text);
+        let issues = match self.settings(uri, &directives) {
+            Some(settings) => {
+                let dictionary = self.dictionaries.for_settings(&settings, &[]);
+                let patterns = self.patterns.for_settings(&settings);
+                let options = CheckOptions::new(&settings, patterns);
+                check_text(&text, &directives, &dictionary, &options)
+            }
+            None => vec![],
+        };
+        self.log_errors();
+        self.send_diagnostics(uri.clone(), issues.iter().map(diagnostic).collect())
+    }
+
+    fn send_diagnostics(
+        &self,
+        uri: Url,
+        diagnostics: Vec<Diagnostic>,
+    ) -> Result<(), Box<dyn Error>> {
+        let params = PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version: None,
+        };
+        let notification = Notification::new(PublishDiagnostics::METHOD.into(), params);
+        self.connection.sender.send(notification.into())?;
+        Ok(())
+    }
+
+    /// Offers to replace each unknown word with a suggestion, or to add it to the workspace
+    /// words or the user dictionary.
+    fn code_actions(&mut self, params: CodeActionParams) -> Vec<CodeActionOrCommand> {
+        let uri = params.text_document.uri;
+        let Some(text) = self.documents.get(&uri).cloned() else {
+            return vec![];
+        };
+        let Some(settings) = self.settings(&uri, &parse_directives(&text)) else {
+            return vec![];
+        };
+        let dictionary = self.dictionaries.for_settings(&settings, &[]);
+        let options = settings.suggest_options();
+
+        let mut actions = vec![];
+        for diagnostic in &params.context.diagnostics {
+            let word = diagnostic
+                .data
+                .as_ref()
+                .and_then(|data| data["word"].as_str());
+            let Some(word) = word.filter(|_| diagnostic.source.as_deref() == Some(SOURCE)) else {
+                continue;
+            };
+
+            let action = |title: String| CodeAction {
+                title,
+                kind: Some(CodeActionKind::QUICKFIX),
+                diagnostics: Some(vec![diagnostic.clone()]),
+                ..Default::default()
+            };
+            for (i, suggestion) in dictionary.suggest(word, &options).into_iter().enumerate() {
+                let edit = TextEdit::new(diagnostic.range, suggestion.word.clone());
+                actions.push(CodeAction {
+                    edit: Some(WorkspaceEdit {
+                        changes: Some(HashMap::from([(uri.clone(), vec![edit])])),
+                        ..Default::default()
+                    }),
+                    is_preferred: Some(i == 0),
+                    ..action(format!("Replace with `{}`", suggestion.word))
+                });
+            }
+            let commands = [
+                (
+                    ADD_TO_WORKSPACE,
+                    format!("Add `{word}` to the workspace words"),
+                ),
+                (
+                    ADD_TO_USER_DICTIONARY,
+                    format!("Add `{word}` to the user dictionary"),
+                ),
+            ];
+            for (command, title) in commands {
+                actions.push(CodeAction {
+                    command: Some(Command {
+                        title: title.clone(),
+                        command: command.into(),
+                        arguments: Some(vec![json!(uri), json!(word)]),
+                    }),
+                    ..action(title)
+                });
+            }
+        }
+
+        self.log_errors();
+        actions
+            .into_iter()
+            .map(CodeActionOrCommand::CodeAction)
+            .collect()
+    }
+
+    fn execute(&mut self, params: ExecuteCommandParams) -> Result<(), String> {
+        let (uri, word): (Url, String) =
+            serde_json::from_value(serde_json::Value::Array(params.arguments))
+                .map_err(|e| format!("invalid arguments for {}: {e}", params.command))?;
+
+        match params.command.as_str() {
+            ADD_TO_WORKSPACE => {
+                let path = self.path(&uri);
+                let dir = path.as_deref().and_then(Path::parent).unwrap_or(&self.root);
+                let config = self
+                    .configs
+                    .search(dir)
+                    .unwrap_or_else(|| self.root.join("cspell.json"));
+                add_word(&config, &word)?;
+            }
+            ADD_TO_USER_DICTIONARY => {
+                let path = self
+                    .user_dictionary
+                    .as_ref()
+                    .ok_or("no user dictionary, set `userDictionary`")?;
+                append_word(path, &word).map_err(|e| format!("{}: {e}", path.display()))?;
+            }
+            command => return Err(format!("unknown command {command}")),
+        }
+        self.reload().map_err(|e| e.to_string())
+    }
+
+    /// Drops the loaded configurations and dictionaries and checks the open documents again.
+    fn reload(&mut self) -> Result<(), Box<dyn Error>> {
+        self.configs = ConfigLoader::default();
+        self.dictionaries = DictionaryLoader::default();
+        self.patterns = PatternLoader::default();
+        self.user_words = self.read_user_words();
+
+        let mut uris: Vec<Url> = self.documents.keys().cloned().collect();
+        uris.sort();
+        for uri in uris {
+            self.publish(&uri)?;
+        }
+        Ok(())
+    }
+
+    fn read_user_words(&self) -> Vec<String> {
+        let text = self
+            .user_dictionary
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .unwrap_or_default();
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(String::from)
+            .collect()
+    }
+
+    /// Writes the loading errors to stderr, which clients show in their logs.
+    fn log_errors(&mut self) {
+        for error in self.configs.take_errors() {
+            eprintln!("spellrs: Configuration Error: {error}");
+        }
+        for error in self.patterns.take_errors() {
+            eprintln!("spellrs: Configuration Error: {error}");
+        }
+        for error in self.dictionaries.take_errors() {
+            eprintln!("spellrs: Dictionary Error: {error}");
+        }
+    }
+}
+
+/// `$XDG_CONFIG_HOME/spellrs/words.txt`, or `~/.config/spellrs/words.txt`.
+fn default_user_dictionary() -> Option<PathBuf> {
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config_dir.join("spellrs").join("words.txt"))
+}
+
+/// Appends a word to a word list, creating the list if needed.
+fn append_word(path: &Path, word: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let existing = fs::read_to_string(path).unwrap_or_default();
+    let separator = match existing.is_empty() || existing.ends_with('\n') {
+        true => "",
+        false => "\n",
+    };
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{separator}{word}")
+}
+
+/// The error response to a request with params that can't be read.
+fn invalid_params(id: RequestId, method: &str, error: serde_json::Error) -> Response {
+    let message = format!("invalid params for {method}: {error}");
+    Response::new_err(id, ErrorCode::InvalidParams as i32, message)
+}
+
+/// Reads the params of a notification. Invalid params are logged, since there is no response
+/// to report them in, and the notification is ignored.
+fn notification_params<T: DeserializeOwned>(method: &str, params: serde_json::Value) -> Option<T> {
+    match serde_json::from_value(params) {
+        Ok(params) => Some(params),
+        Err(e) => {
+            eprintln!("spellrs: invalid params for {method}: {e}");
+            None
+        }
+    }
+}
+
+/// The diagnostic of an issue. Lines and columns are 0 based and count UTF-16 code units.
+fn diagnostic(issue: &Issue) -> Diagnostic {
+    let start = Position::new(issue.line as u32 - 1, issue.col as u32 - 1);
+    let end = Position::new(
+        start.line,
+        start.character + issue.word.encode_utf16().count() as u32,
+    );
+    let data = match issue.kind {
+        IssueKind::UnknownWord => Some(json!({ "word": issue.word })),
+        IssueKind::Directive(_) => None,
+    };
+
+    Diagnostic {
+        range: Range::new(start, end),
+        severity: Some(DiagnosticSeverity::INFORMATION),
+        source: Some(SOURCE.into()),
+        message: issue.message(),
+        data,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::thread;
+
+    use lsp_server::ResponseError;
+    use lsp_types::{
+        notification::{Exit, Initialized},
+        request::{Initialize, Shutdown},
+        CodeActionContext, InitializedParams, TextDocumentIdentifier, TextDocumentItem,
+    };
+
+    use super::*;
+
+    struct Client {
+        connection: Connection,
+        next_id: i32,
+    }
+
+    impl Client {
+        /// Sends a request and returns its result, and the diagnostics published before it.
+        fn request(
+            &mut self,
+            method: &str,
+            params: serde_json::Value,
+        ) -> (serde_json::Value, Vec<PublishDiagnosticsParams>) {
+            let (result, published) = self.response(method, params);
+            (result.map_err(|e| e.message).expect(method), published)
+        }
+
+        /// Like [`Client::request`], but returns the error of a failed request.
+        fn response(
+            &mut self,
+            method: &str,
+            params: serde_json::Value,
+        ) -> (
+            Result<serde_json::Value, ResponseError>,
+            Vec<PublishDiagnosticsParams>,
+        ) {
+            self.next_id += 1;
+            let request = Request::new(self.next_id.into(), method.into(), params);
+            self.connection.sender.send(request.into()).unwrap();
+
+            let mut published = vec![];
+            loop {
+                match self.connection.receiver.recv().unwrap() {
+                    Message::Response(response) => return (response.response_result, published),
+                    Message::Notification(notification) => {
+                        published.push(serde_json::from_value(notification.params).unwrap())
+                    }
+                    Message::Request(_) => {}
+                }
+            }
+        }
+
+        fn notify(&self, method: &str, params: impl serde::Serialize) {
+            let notification = Notification::new(method.into(), params);
+            self.connection.sender.send(notification.into()).unwrap();
+        }
+
+        fn diagnostics(&self) -> Vec<Diagnostic> {
+            let message = self.connection.receiver.recv().unwrap();
+            let Message::Notification(notification) = message else {
+                panic!("expected diagnostics, got {message:?}");
+            };
+            let params: PublishDiagnosticsParams =
+                serde_json::from_value(notification.params).unwrap();
+            params.diagnostics
+        }
+    }
+
+    fn words(diagnostics: &[Diagnostic]) -> Vec<(&str, Range)> {
+        diagnostics
+            .iter()
+            .map(|d| (d.data.as_ref().unwrap()["word"].as_str().unwrap(), d.range))
+            .collect()
+    }
+
+    fn range(line: u32, start: u32, end: u32) -> Range {
+        Range::new(Position::new(line, start), Position::new(line, end))
+    }
+
+    #[test]
+    fn test_server() {
+        let dir = tempfile::tempdir().unwrap();
+        let dic = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("spellrs_hunspell")
+            .join("testdata")
+            .join("en_sample.dic");
+        let config = dir.path().join("cspell.json");
+        let config_json = json!({
+            "dictionaries": ["en"],
+            "dictionaryDefinitions": [{ "name": "en", "path": dic }],
+        });
+        fs::write(&config, config_json.to_string()).unwrap();
+        let user_dictionary = dir.path().join("user").join("words.txt");
+
+        let (server, connection) = Connection::memory();
+        let server = thread::spawn(move || serve(&server).map_err(|e| e.to_string()));
+        let mut client = Client {
+            connection,
+            next_id: 0,
+        };
+        let params = json!({
+            "capabilities": {},
+            "rootUri": Url::from_directory_path(dir.path()).unwrap(),
+            "initializationOptions": { "userDictionary": user_dictionary },
+        });
+        client.request(Initialize::METHOD, params);
+        client.notify(Initialized::METHOD, InitializedParams {});
+
+        // Columns count UTF-16 code units.
+        let uri = Url::from_file_path(dir.path().join("notes.md")).unwrap();
+        let text = "𝄞 hello helo\nwrold";
+        client.notify(
+            DidOpenTextDocument::METHOD,
+            DidOpenTextDocumentParams {
+                text_document: TextDocumentItem::new(
+                    uri.clone(),
+                    "markdown".into(),
+                    1,
+                    text.into(),
+                ),
+            },
+        );
+        let diagnostics = client.diagnostics();
+        assert_eq!(
+            vec![("helo", range(0, 9, 13)), ("wrold", range(1, 0, 5))],
+            words(&diagnostics)
+        );
+
+        let params = CodeActionParams {
+            text_document: TextDocumentIdentifier::new(uri.clone()),
+            range: diagnostics[0].range,
+            context: CodeActionContext {
+                diagnostics: vec![diagnostics[0].clone()],
+                ..Default::default()
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let (actions, _) = client.request(CodeActionRequest::METHOD, json!(params));
+        let actions: Vec<CodeAction> = serde_json::from_value(actions).unwrap();
+        let titles: Vec<&str> = actions.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!("Replace with `hello`", titles[0]);
+        assert_eq!(
+            [
+                "Add `helo` to the workspace words",
+                "Add `helo` to the user dictionary"
+            ],
+            titles[titles.len() - 2..]
+        );
+        let edit = TextEdit::new(range(0, 9, 13), "hello".into());
+        let changes = actions[0].edit.as_ref().unwrap().changes.as_ref().unwrap();
+        assert_eq!(vec![edit], changes[&uri]);
+
+        let execute =
+            |command: &str, word: &str| json!({ "command": command, "arguments": [uri, word] });
+        let (_, published) =
+            client.request(ExecuteCommand::METHOD, execute(ADD_TO_WORKSPACE, "wrold"));
+        assert_eq!(
+            vec![("helo", range(0, 9, 13))],
+            words(&published[0].diagnostics)
+        );
+        let config: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&config).unwrap()).unwrap();
+        assert_eq!(json!(["wrold"]), config["words"]);
+
+        let (_, published) = client.request(
+            ExecuteCommand::METHOD,
+            execute(ADD_TO_USER_DICTIONARY, "helo"),
+        );
+        assert_eq!(Vec::<Diagnostic>::new(), published[0].diagnostics);
+        assert_eq!("helo\n", fs::read_to_string(&user_dictionary).unwrap());
+
+        client.notify(
+            DidChangeTextDocument::METHOD,
+            json!({
+                "textDocument": { "uri": uri, "version": 2 },
+                "contentChanges": [{ "text": "wrold hellos helllo" }],
+            }),
+        );
+        assert_eq!(
+            vec![("helllo", range(0, 13, 19))],
+            words(&client.diagnostics())
+        );
+
+        // Invalid params fail the request, or are ignored for a notification. Either way the
+        // server keeps running.
+        client.notify(DidOpenTextDocument::METHOD, json!({ "textDocument": 42 }));
+        let (result, _) = client.response(CodeActionRequest::METHOD, json!({ "range": 42 }));
+        assert_eq!(ErrorCode::InvalidParams as i32, result.unwrap_err().code);
+
+        client.request(Shutdown::METHOD, json!(null));
+        client.notify(Exit::METHOD, json!(null));
+        assert_eq!(Ok(()), server.join().unwrap());
+    }
+}
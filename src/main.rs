@@ -1,3 +1,38 @@
-fn main() {
-    todo!();
+mod cache;
+mod cli;
+mod config;
+mod dictionary;
+mod directives;
+mod fix;
+mod git;
+mod lint;
+mod lsp;
+mod patterns;
+mod reporter;
+mod suggest;
+mod text;
+mod trace;
+
+use std::process::ExitCode;
+
+use clap::Parser;
+use cli::{Cli, Command};
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Command::Lint(args) => lint::run(&args),
+        Command::Suggest(args) => suggest::run(&args),
+        Command::Lsp(_) => lsp::run(),
+        Command::Trace(args) => trace::run(&args),
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("spellrs: {e}");
+            ExitCode::FAILURE
+        }
+    }
 }
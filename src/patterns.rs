@@ -0,0 +1,350 @@
+use std::{collections::HashMap, mem, ops::Range, sync::Arc};
+
+use fancy_regex::Regex;
+
+use crate::config::{PatternDefinition, Settings};
+
+const SPELL_CHECKER_DISABLE_BLOCK: &str =
+    r"/(\bc?spell(?:-?checker)?::?)\s*disable(?!-line|-next)\b[\s\S]*?((?:\1\s*enable\b)|$)/gi";
+const SPELL_CHECKER_DISABLE_LINE: &str = r"/^.*\bc?spell(?:-?checker)?::?\s*disable-line\b.*/gim";
+const SPELL_CHECKER_DISABLE_NEXT: &str =
+    r"/\bc?spell(?:-?checker)?::?\s*disable-next\b.*\s\s?.*/gi";
+
+/// The patterns cspell predefines, by name. A name can stand for several regular expressions.
+const PREDEFINED_PATTERNS: [(&str, &[&str]); 15] = [
+    ("Urls", &[r#"/(?:https?|ftp):\/\/[^\s"]+/gi"#]),
+    (
+        "Email",
+        &[r"/<?\b[\w.\-+]{1,128}@\w{1,63}(\.\w{1,63}){1,4}\b>?/gi"],
+    ),
+    (
+        "HexValues",
+        &[r"/(?:#[0-9a-f]{3,8})|(?:0x[0-9a-f]+)|(?:\\u[0-9a-f]{4})|(?:\\x\{[0-9a-f]{4}\})/gi"],
+    ),
+    ("CStyleHexValue", &[r"/\b0x[0-9a-f_]+\b/gi"]),
+    ("CSSHexValue", &[r"/#[0-9a-f]{3,8}\b/gi"]),
+    ("CommitHash", &[r"/\b(?![a-f]+\b)(?:0x)?[0-9a-f]{7,}\b/gi"]),
+    (
+        "Base64",
+        &[
+            r"/(?<![A-Za-z0-9/+])(?:[A-Za-z0-9/+]{40,})(?:\s^\s*[A-Za-z0-9/+]{40,})*(?:\s^\s*[A-Za-z0-9/+]+=*)?(?![A-Za-z0-9/+=])/gm",
+            r"/(?<![A-Za-z0-9/+])(?=[A-Za-z0-9/+]*[0-9])[A-Za-z0-9/+]{40,}={0,3}(?![A-Za-z0-9/+=])/g",
+        ],
+    ),
+    (
+        "UUID",
+        &[r"/\b[0-9a-fx]{8}-[0-9a-fx]{4}-[0-9a-fx]{4}-[0-9a-fx]{4}-[0-9a-fx]{12}\b/gi"],
+    ),
+    (
+        "CStyleComment",
+        &[r"/(?<!\w:)(?:\/\/.*)|(?:\/\*[\s\S]*?\*\/)/g"],
+    ),
+    (
+        "SpellCheckerDisable",
+        &[
+            SPELL_CHECKER_DISABLE_BLOCK,
+            SPELL_CHECKER_DISABLE_LINE,
+            SPELL_CHECKER_DISABLE_NEXT,
+        ],
+    ),
+    ("SpellCheckerDisableBlock", &[SPELL_CHECKER_DISABLE_BLOCK]),
+    ("SpellCheckerDisableLine", &[SPELL_CHECKER_DISABLE_LINE]),
+    ("SpellCheckerDisableNext", &[SPELL_CHECKER_DISABLE_NEXT]),
+    (
+        "SpellCheckerIgnoreInDocSetting",
+        &[r"/\bc?spell(?:-?checker)?::?\s*ignoreRegExp.*/gim"],
+    ),
+    ("Everything", &[r"/.*/g"]),
+];
+
+/// The patterns that are always ignored, before `ignoreRegExpList`. This is the cspell default.
+const DEFAULT_IGNORE_PATTERNS: [&str; 8] = [
+    "SpellCheckerDisable",
+    "SpellCheckerIgnoreInDocSetting",
+    "Urls",
+    "Email",
+    "Base64",
+    "CommitHash",
+    "HexValues",
+    "UUID",
+];
+
+/// Compiles a regular expression written the JavaScript way, i.e. `/0x[0-9a-f]+/gi`.
+///
+/// The `i`, `m` and `s` flags are kept, the others have no meaning here. Strings without the
+/// slashes are compiled as if they had the `gimu` flags, like cspell does.
+pub fn parse_reg_exp(src: &str) -> Result<Regex, String> {
+    let (pattern, flags) = match src.strip_prefix('/').and_then(|rest| rest.rsplit_once('/')) {
+        Some((pattern, flags)) if flags.chars().all(|c| c.is_ascii_alphabetic()) => {
+            (pattern, flags)
+        }
+        _ => (src, "gimu"),
+    };
+
+    let inline: String = flags.chars().filter(|c| "ims".contains(*c)).collect();
+    let pattern = match inline.is_empty() {
+        true => pattern.to_string(),
+        false => format!("(?{inline}){pattern}"),
+    };
+    Regex::new(&pattern).map_err(|e| format!("invalid pattern `{src}`: {e}"))
+}
+
+/// The regular expressions of the text to skip and the text to check in a file.
+#[derive(Debug, Default, Clone)]
+pub struct TextPatterns {
+    ignore: Vec<Arc<Regex>>,
+    include: Vec<Arc<Regex>>,
+}
+
+impl TextPatterns {
+    /// Finds the byte ranges of `text` that are not checked, sorted and without overlaps.
+    ///
+    /// These are the matches of the ignore patterns and, when there are include patterns,
+    /// everything outside of their matches.
+    pub fn excluded_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        let mut ranges = match_ranges(text, &self.ignore);
+        if !self.include.is_empty() {
+            let mut start = 0;
+            for included in merge_ranges(match_ranges(text, &self.include)) {
+                if included.start > start {
+                    ranges.push(start..included.start);
+                }
+                start = included.end;
+            }
+            if start < text.len() {
+                ranges.push(start..text.len());
+            }
+        }
+
+        merge_ranges(ranges)
+    }
+}
+
+fn match_ranges(text: &str, patterns: &[Arc<Regex>]) -> Vec<Range<usize>> {
+    patterns
+        .iter()
+        .flat_map(|regex| regex.find_iter(text).map_while(Result::ok))
+        .filter(|m| !m.range().is_empty())
+        .map(|m| m.range())
+        .collect()
+}
+
+/// Sorts the ranges and merges the ones that overlap or touch.
+pub fn merge_ranges(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.sort_by_key(|r| r.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// Checks if `range` overlaps any of the sorted `ranges`.
+pub fn overlaps(ranges: &[Range<usize>], range: &Range<usize>) -> bool {
+    let i = ranges.partition_point(|r| r.end <= range.start);
+    ranges.get(i).is_some_and(|r| r.start < range.end)
+}
+
+/// Compiles the patterns of the settings, keeping them so that each is only compiled once.
+#[derive(Default)]
+pub struct PatternLoader {
+    compiled: HashMap<String, Option<Arc<Regex>>>,
+    errors: Vec<String>,
+}
+
+impl PatternLoader {
+    /// Builds the patterns for a file from its settings.
+    ///
+    /// Each entry of `ignoreRegExpList` and `includeRegExpList` is the name of a pattern from
+    /// `patterns`, a predefined pattern like `Urls`, or else a regular expression. Errors
+    /// compiling the patterns are kept for [`PatternLoader::take_errors`].
+    pub fn for_settings(&mut self, settings: &Settings) -> TextPatterns {
+        let ignore = DEFAULT_IGNORE_PATTERNS
+            .into_iter()
+            .chain(settings.ignore_reg_exp_list.iter().map(String::as_str));
+        let include = settings.include_reg_exp_list.iter().map(String::as_str);
+
+        TextPatterns {
+            ignore: ignore
+                .flat_map(|name| self.resolve(name, &settings.patterns))
+                .collect(),
+            include: include
+                .flat_map(|name| self.resolve(name, &settings.patterns))
+                .collect(),
+        }
+    }
+
+    fn resolve(&mut self, name: &str, patterns: &[PatternDefinition]) -> Vec<Arc<Regex>> {
+        let sources: Vec<&str> = match patterns.iter().rev().find(|def| def.name == name) {
+            Some(def) => def.pattern.iter().map(String::as_str).collect(),
+            None => match PREDEFINED_PATTERNS.iter().find(|(n, _)| *n == name) {
+                Some((_, sources)) => sources.to_vec(),
+                None => vec![name],
+            },
+        };
+
+        sources
+            .into_iter()
+            .filter_map(|src| self.compile(src))
+            .collect()
+    }
+
+    fn compile(&mut self, src: &str) -> Option<Arc<Regex>> {
+        if !self.compiled.contains_key(src) {
+            let regex = parse_reg_exp(src);
+            if let Err(e) = &regex {
+                self.errors.push(e.clone());
+            }
+            self.compiled
+                .insert(src.to_string(), regex.ok().map(Arc::new));
+        }
+        self.compiled[src].clone()
+    }
+
+    /// Returns the errors found since the last call.
+    pub fn take_errors(&mut self) -> Vec<String> {
+        mem::take(&mut self.errors)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// The text of the excluded ranges.
+    fn excluded<'a>(patterns: &TextPatterns, text: &'a str) -> Vec<&'a str> {
+        patterns
+            .excluded_ranges(text)
+            .into_iter()
+            .map(|r| &text[r])
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_reg_exp() {
+        let cases = [
+            ("/todo/", "TODO todo", vec!["todo"]),
+            ("/todo/gi", "TODO todo", vec!["TODO", "todo"]),
+            ("todo", "TODO todo", vec!["TODO", "todo"]),
+            ("/^x/gm", "x\nx", vec!["x", "x"]),
+            ("/^x/g", "x\nx", vec!["x"]),
+            (r"/a\/b/", "a/b", vec!["a/b"]),
+            ("/a.b/s", "a\nb", vec!["a\nb"]),
+        ];
+
+        for (src, text, expected) in cases {
+            let regex = parse_reg_exp(src).unwrap();
+            let found: Vec<&str> = regex.find_iter(text).map(|m| m.unwrap().as_str()).collect();
+            assert_eq!(expected, found, "{src}");
+        }
+        assert!(parse_reg_exp("/(unclosed/").is_err());
+    }
+
+    #[test]
+    fn test_predefined_patterns() {
+        let cases = [
+            (
+                "Urls",
+                "see https://example.com/abc?q=1 now",
+                vec!["https://example.com/abc?q=1"],
+            ),
+            ("Email", "mail me@example.com", vec!["me@example.com"]),
+            (
+                "HexValues",
+                "color: #ff00ff, 0xdeadbeef",
+                vec!["#ff00ff", "0xdeadbeef"],
+            ),
+            (
+                "CommitHash",
+                "fixed in 9fceb02 and abcdefab",
+                vec!["9fceb02"],
+            ),
+            (
+                "Base64",
+                "key: TWFuIGlzIGRpc3Rpbmd1aXNoZWQsIG5vdCBvbmx5IGJ5IGhpcw== end",
+                vec!["TWFuIGlzIGRpc3Rpbmd1aXNoZWQsIG5vdCBvbmx5IGJ5IGhpcw=="],
+            ),
+            (
+                "UUID",
+                "id 123e4567-e89b-12d3-a456-426614174000.",
+                vec!["123e4567-e89b-12d3-a456-426614174000"],
+            ),
+            (
+                "CStyleComment",
+                "code // note\nmore /* block\ncomment */ http://x",
+                vec!["// note", "/* block\ncomment */"],
+            ),
+            (
+                "SpellCheckerDisable",
+                "a\n// cspell:disable\nxyz\n// cspell:enable\nb",
+                vec!["cspell:disable\nxyz\n// cspell:enable"],
+            ),
+            (
+                "SpellCheckerDisable",
+                "a\nbad word // cspell:disable-line\nb",
+                vec!["bad word // cspell:disable-line"],
+            ),
+            (
+                "SpellCheckerDisable",
+                "// spell-checker:disable-next-line\nbad word\nb",
+                vec!["spell-checker:disable-next-line\nbad word"],
+            ),
+            (
+                "SpellCheckerDisable",
+                "/* cspell:disable */ rest of file",
+                vec!["cspell:disable */ rest of file"],
+            ),
+        ];
+
+        for (name, text, expected) in cases {
+            let mut loader = PatternLoader::default();
+            let patterns = TextPatterns {
+                ignore: loader.resolve(name, &[]),
+                include: vec![],
+            };
+            assert_eq!(expected, excluded(&patterns, text), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_for_settings() {
+        let settings: Settings = serde_json::from_value(serde_json::json!({
+            "ignoreRegExpList": ["Todo", "/skip\\w+/", "/(bad/"],
+            "includeRegExpList": ["CStyleComment"],
+            "patterns": [{ "name": "Todo", "pattern": ["TODO\\(\\w+\\)", "FIXME"] }],
+        }))
+        .unwrap();
+
+        let mut loader = PatternLoader::default();
+        let patterns = loader.for_settings(&settings);
+        let text = "code\n// TODO(roy) check https://x.io skipThis\n/* FIXME ok */";
+        assert_eq!(
+            vec!["code\n", "TODO(roy)", "https://x.io", "skipThis\n", "FIXME",],
+            excluded(&patterns, text)
+        );
+        assert_eq!(1, loader.take_errors().len());
+
+        loader.for_settings(&settings);
+        assert!(loader.take_errors().is_empty());
+    }
+
+    #[test]
+    fn test_overlaps() {
+        let ranges = [2..4, 8..10];
+        let cases = [
+            (0..2, false),
+            (0..3, true),
+            (3..5, true),
+            (4..8, false),
+            (9..12, true),
+            (10..12, false),
+        ];
+
+        for (range, expected) in cases {
+            assert_eq!(expected, overlaps(&ranges, &range), "{range:?}");
+        }
+    }
+}
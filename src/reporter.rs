@@ -0,0 +1,387 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use serde::Serialize;
+use serde_json::json;
+use url::Url;
+
+use crate::{
+    cli::Reporter,
+    lint::{Issue, IssueKind},
+};
+
+/// The issues of a checked file.
+#[derive(Debug)]
+pub struct FileReport {
+    /// Relative to the current directory.
+    pub path: PathBuf,
+    pub url: Url,
+    pub issues: Vec<Issue>,
+}
+
+/// Writes the issues of the checked files in the format of the reporter. `root` is the URL of
+/// the directory the paths are relative to.
+pub fn write_report(
+    out: &mut impl Write,
+    reporter: Reporter,
+    root: &Url,
+    files: &[FileReport],
+) -> io::Result<()> {
+    match reporter {
+        Reporter::Default => write_default(out, files),
+        Reporter::Json => write_json(out, files),
+        Reporter::Sarif => write_sarif(out, root, files),
+        Reporter::Junit => write_junit(out, files),
+        Reporter::Github => write_github(out, files),
+    }
+}
+
+/// The rule id and description of a kind of issue, i.e. for SARIF.
+fn rule(kind: &IssueKind) -> (&'static str, &'static str) {
+    match kind {
+        IssueKind::UnknownWord => ("unknown-word", "Unknown word"),
+        IssueKind::Directive(_) => ("directive", "Invalid cspell directive"),
+    }
+}
+
+/// The message of an issue followed by its suggestions, if any.
+fn describe(issue: &Issue) -> String {
+    match issue.suggestions.is_empty() {
+        true => issue.message(),
+        false => format!(
+            "{}, suggestions: {}",
+            issue.message(),
+            issue.suggestions.join(", ")
+        ),
+    }
+}
+
+/// The column after the word, in UTF-16 code units like [`Issue::col`].
+fn end_col(issue: &Issue) -> usize {
+    issue.col + issue.word.encode_utf16().count()
+}
+
+fn write_default(out: &mut impl Write, files: &[FileReport]) -> io::Result<()> {
+    for file in files {
+        for issue in &file.issues {
+            writeln!(
+                out,
+                "{}:{}:{} - {}",
+                file.path.display(),
+                issue.line,
+                issue.col,
+                issue.message()
+            )?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonReport<'a> {
+    files: usize,
+    files_with_issues: usize,
+    issues: Vec<JsonIssue<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonIssue<'a> {
+    file: String,
+    url: &'a str,
+    line: usize,
+    col: usize,
+    offset: usize,
+    word: &'a str,
+    rule: &'static str,
+    message: String,
+    suggestions: &'a [String],
+}
+
+fn write_json(out: &mut impl Write, files: &[FileReport]) -> io::Result<()> {
+    let issues = files
+        .iter()
+        .flat_map(|file| file.issues.iter().map(move |issue| (file, issue)))
+        .map(|(file, issue)| JsonIssue {
+            file: file.path.to_string_lossy().into_owned(),
+            url: file.url.as_str(),
+            line: issue.line,
+            col: issue.col,
+            offset: issue.offset,
+            word: &issue.word,
+            rule: rule(&issue.kind).0,
+            message: issue.message(),
+            suggestions: &issue.suggestions,
+        })
+        .collect();
+    let report = JsonReport {
+        files: files.len(),
+        files_with_issues: files.iter().filter(|f| !f.issues.is_empty()).count(),
+        issues,
+    };
+
+    serde_json::to_writer_pretty(&mut *out, &report)?;
+    writeln!(out)
+}
+
+/// Writes a SARIF 2.1.0 log with a run of spellrs. The files are relative to `%SRCROOT%`,
+/// which is `root`.
+fn write_sarif(out: &mut impl Write, root: &Url, files: &[FileReport]) -> io::Result<()> {
+    let rules = [IssueKind::UnknownWord, IssueKind::Directive(String::new())].map(|kind| {
+        let (id, description) = rule(&kind);
+        json!({ "id": id, "shortDescription": { "text": description } })
+    });
+
+    let results: Vec<serde_json::Value> = files
+        .iter()
+        .flat_map(|file| file.issues.iter().map(move |issue| (file, issue)))
+        .map(|(file, issue)| {
+            let uri = root
+                .make_relative(&file.url)
+                .unwrap_or_else(|| file.url.to_string());
+            json!({
+                "ruleId": rule(&issue.kind).0,
+                "level": "warning",
+                "message": { "text": issue.message() },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": uri, "uriBaseId": "%SRCROOT%" },
+                        "region": {
+                            "startLine": issue.line,
+                            "startColumn": issue.col,
+                            "endColumn": end_col(issue),
+                        },
+                    },
+                }],
+                "properties": { "word": issue.word, "suggestions": issue.suggestions },
+            })
+        })
+        .collect();
+
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "spellrs",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "originalUriBaseIds": { "%SRCROOT%": { "uri": root.as_str() } },
+            "columnKind": "utf16CodeUnits",
+            "results": results,
+        }],
+    });
+
+    serde_json::to_writer_pretty(&mut *out, &log)?;
+    writeln!(out)
+}
+
+/// Writes a test suite with a test case per file, which fails when the file has issues.
+fn write_junit(out: &mut impl Write, files: &[FileReport]) -> io::Result<()> {
+    let failures = files.iter().filter(|f| !f.issues.is_empty()).count();
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<testsuites name="spellrs" tests="{}" failures="{failures}">"#,
+        files.len()
+    )?;
+    writeln!(
+        out,
+        r#"  <testsuite name="spellrs" tests="{}" failures="{failures}">"#,
+        files.len()
+    )?;
+    for file in files {
+        let path = xml_escape(&file.path.to_string_lossy());
+        write!(
+            out,
+            r#"    <testcase classname="spellrs" name="{path}" file="{path}""#
+        )?;
+        if file.issues.is_empty() {
+            writeln!(out, " />")?;
+            continue;
+        }
+
+        let details: Vec<String> = file
+            .issues
+            .iter()
+            .map(|issue| format!("{}:{} - {}", issue.line, issue.col, describe(issue)))
+            .collect();
+        writeln!(out, ">")?;
+        writeln!(
+            out,
+            r#"      <failure message="Issues found: {}" type="spelling">{}</failure>"#,
+            file.issues.len(),
+            xml_escape(&details.join("\n"))
+        )?;
+        writeln!(out, "    </testcase>")?;
+    }
+    writeln!(out, "  </testsuite>")?;
+    writeln!(out, "</testsuites>")
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Writes a `::warning` workflow command per issue.
+fn write_github(out: &mut impl Write, files: &[FileReport]) -> io::Result<()> {
+    for file in files {
+        let path = github_escape(&file.path.to_string_lossy(), true);
+        for issue in &file.issues {
+            writeln!(
+                out,
+                "::warning file={path},line={},col={},endColumn={},title={}::{}",
+                issue.line,
+                issue.col,
+                end_col(issue),
+                github_escape(rule(&issue.kind).1, true),
+                github_escape(&describe(issue), false)
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Escapes the value of a workflow command, or of one of its properties.
+fn github_escape(text: &str, property: bool) -> String {
+    let escaped = text
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A");
+    match property {
+        true => escaped.replace(':', "%3A").replace(',', "%2C"),
+        false => escaped,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn files() -> Vec<FileReport> {
+        let issue = |word: &str, line, col, suggestions: &[&str]| Issue {
+            word: word.to_string(),
+            offset: 0,
+            line,
+            col,
+            kind: IssueKind::UnknownWord,
+            suggestions: suggestions.iter().map(|s| s.to_string()).collect(),
+            fix: None,
+        };
+        vec![
+            FileReport {
+                path: "a b.md".into(),
+                url: Url::parse("file:///project/a%20b.md").unwrap(),
+                issues: vec![
+                    issue("helo", 1, 1, &["hello", "help"]),
+                    issue("<wrold>", 3, 7, &[]),
+                ],
+            },
+            FileReport {
+                path: "src/main.rs".into(),
+                url: Url::parse("file:///project/src/main.rs").unwrap(),
+                issues: vec![],
+            },
+        ]
+    }
+
+    fn report(reporter: Reporter) -> String {
+        let root = Url::parse("file:///project/").unwrap();
+        let mut out = vec![];
+        write_report(&mut out, reporter, &root, &files()).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_text_reporters() {
+        let cases = [
+            (
+                Reporter::Default,
+                "a b.md:1:1 - Unknown word (helo)\n\
+                 a b.md:3:7 - Unknown word (<wrold>)\n",
+            ),
+            (
+                Reporter::Github,
+                "::warning file=a b.md,line=1,col=1,endColumn=5,title=Unknown word::\
+                 Unknown word (helo), suggestions: hello, help\n\
+                 ::warning file=a b.md,line=3,col=7,endColumn=14,title=Unknown word::\
+                 Unknown word (<wrold>)\n",
+            ),
+            (
+                Reporter::Junit,
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="spellrs" tests="2" failures="1">
+  <testsuite name="spellrs" tests="2" failures="1">
+    <testcase classname="spellrs" name="a b.md" file="a b.md">
+      <failure message="Issues found: 2" type="spelling">1:1 - Unknown word (helo), suggestions: hello, help&#10;3:7 - Unknown word (&lt;wrold&gt;)</failure>
+    </testcase>
+    <testcase classname="spellrs" name="src/main.rs" file="src/main.rs" />
+  </testsuite>
+</testsuites>
+"#,
+            ),
+        ];
+
+        for (reporter, expected) in cases {
+            assert_eq!(expected, report(reporter), "{reporter:?}");
+        }
+    }
+
+    #[test]
+    fn test_json_reporters() {
+        let json: serde_json::Value = serde_json::from_str(&report(Reporter::Json)).unwrap();
+        assert_eq!(2, json["files"]);
+        assert_eq!(1, json["filesWithIssues"]);
+        assert_eq!(
+            json!({
+                "file": "a b.md",
+                "url": "file:///project/a%20b.md",
+                "line": 1,
+                "col": 1,
+                "offset": 0,
+                "word": "helo",
+                "rule": "unknown-word",
+                "message": "Unknown word (helo)",
+                "suggestions": ["hello", "help"],
+            }),
+            json["issues"][0]
+        );
+
+        let sarif: serde_json::Value = serde_json::from_str(&report(Reporter::Sarif)).unwrap();
+        let run = &sarif["runs"][0];
+        assert_eq!("2.1.0", sarif["version"]);
+        assert_eq!(
+            "file:///project/",
+            run["originalUriBaseIds"]["%SRCROOT%"]["uri"]
+        );
+        assert_eq!(2, run["results"].as_array().unwrap().len());
+        let location = &run["results"][1]["locations"][0]["physicalLocation"];
+        assert_eq!("a%20b.md", location["artifactLocation"]["uri"]);
+        assert_eq!(
+            json!({ "startLine": 3, "startColumn": 7, "endColumn": 14 }),
+            location["region"]
+        );
+        assert_eq!(
+            json!(["hello", "help"]),
+            run["results"][0]["properties"]["suggestions"]
+        );
+    }
+}
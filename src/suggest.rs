@@ -0,0 +1,75 @@
+use std::{
+    env,
+    error::Error,
+    io::{self, Write},
+    process::ExitCode,
+    sync::Arc,
+    time::Duration,
+};
+
+use spellrs_filetype::find_matching_file_types;
+use spellrs_trie::SuggestOptions;
+
+use crate::{
+    cli::SuggestArgs,
+    config::{ConfigLoader, Settings},
+    dictionary::{DictionaryLoader, SpellingDictionary},
+};
+
+/// Runs `spellrs suggest`, printing the suggestions for each word on its own line.
+pub fn run(args: &SuggestArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let cwd = env::current_dir()?;
+    let file = args.file.as_ref().map(|file| cwd.join(file));
+    let mut configs = ConfigLoader::default();
+    let config = match &args.config {
+        Some(path) => Some(path.clone()),
+        None => configs.search(file.as_ref().and_then(|f| f.parent()).unwrap_or(&cwd)),
+    };
+    let settings = match &config {
+        Some(path) => configs.load(path),
+        None => Arc::new(Settings::default()),
+    };
+    let settings = match &file {
+        Some(file) => settings
+            .for_file(file)
+            .for_language(&find_matching_file_types(&file.to_string_lossy())),
+        None => settings.for_language(&[]),
+    };
+
+    let mut dictionaries = DictionaryLoader::default();
+    let mut extra = vec![];
+    for path in &args.dictionaries {
+        extra.push(dictionaries.load(path)?);
+    }
+    let dictionary = dictionaries.for_settings(&settings, &extra);
+
+    let defaults = settings.suggest_options();
+    let options = SuggestOptions {
+        num_suggestions: args.num_suggestions.unwrap_or(defaults.num_suggestions),
+        num_changes: args.num_changes.unwrap_or(defaults.num_changes),
+        timeout: args.timeout.map_or(defaults.timeout, Duration::from_millis),
+    };
+
+    let mut out = io::stdout().lock();
+    for word in &args.words {
+        let suggestions: Vec<String> = dictionary
+            .suggest(word, &options)
+            .into_iter()
+            .map(|s| match args.verbose {
+                true => format!("{} ({})", s.word, s.cost),
+                false => s.word,
+            })
+            .collect();
+        writeln!(out, "{word}: {}", suggestions.join(", "))?;
+    }
+    out.flush()?;
+
+    for error in configs.take_errors() {
+        eprintln!("spellrs: Configuration Error: {error}");
+    }
+    for error in dictionaries.take_errors() {
+        eprintln!("spellrs: Dictionary Error: {error}");
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,270 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Runs of characters that can make up an identifier, i.e. `parseHTTPResponse2xx` or `don't`.
+static TOKEN_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"[\p{L}\p{M}\p{Nd}_'’]+").unwrap());
+
+/// Letters that follow a `\` in an escape sequence, i.e. the `n` in `\nHello`.
+const ESCAPE_CHARS: [char; 7] = ['a', 'b', 'f', 'n', 'r', 't', 'v'];
+
+/// Letters after a number up to this length are a unit or suffix, i.e. `2xx`, `10px` or `1st`.
+const MAX_NUMBER_SUFFIX: usize = 3;
+
+/// A piece of text and its offset in the document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextOffset<'a> {
+    pub text: &'a str,
+    /// Byte offset of the text.
+    pub offset: usize,
+    /// Offset of the text in UTF-16 code units, as used by editors and the LSP.
+    pub utf16_offset: usize,
+}
+
+/// Finds the words in a document the way cspell does.
+///
+/// Identifiers are split into words, so `parseHTTPResponse2xx` is `parse`, `HTTP` and
+/// `Response`. Numbers, hex literals like `0xff` or `a3f9c2e` and the letters right after a
+/// number, i.e. `2xx`, are skipped. Apostrophes are kept inside words like `don't`.
+pub fn extract_words(text: &str) -> impl Iterator<Item = TextOffset<'_>> {
+    let mut last_offset = 0;
+    let mut last_utf16_offset = 0;
+
+    TOKEN_REGEX
+        .find_iter(text)
+        .filter(|token| !is_hex_literal(token.as_str()))
+        .flat_map(move |token| {
+            last_utf16_offset += text[last_offset..token.start()].encode_utf16().count();
+            last_offset = token.start();
+
+            let mut start = 0;
+            let escaped = text[..token.start()].ends_with('\\');
+            match token.as_str().chars().next() {
+                Some(c) if escaped && ESCAPE_CHARS.contains(&c) => start = 1,
+                _ => {}
+            }
+
+            let token_utf16_offset = last_utf16_offset;
+            split_identifier(&token.as_str()[start..]).into_iter().map(
+                move |(word_start, word_end)| {
+                    let (word_start, word_end) = (start + word_start, start + word_end);
+                    TextOffset {
+                        text: &token.as_str()[word_start..word_end],
+                        offset: token.start() + word_start,
+                        utf16_offset: token_utf16_offset
+                            + token.as_str()[..word_start].encode_utf16().count(),
+                    }
+                },
+            )
+        })
+}
+
+/// Checks for hex numbers like `0xff` and hashes or colors like `a3f9c2e` and `ff00ff`.
+fn is_hex_literal(token: &str) -> bool {
+    let is_hex = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_hexdigit() || c == '_');
+
+    match token
+        .strip_prefix("0x")
+        .or_else(|| token.strip_prefix("0X"))
+    {
+        Some(digits) => is_hex(digits),
+        None => {
+            token.len() >= 6
+                && token.chars().all(|c| c.is_ascii_hexdigit())
+                && token.chars().any(|c| c.is_ascii_digit())
+                && token.chars().any(|c| c.is_ascii_alphabetic())
+        }
+    }
+}
+
+/// Splits an identifier into the byte ranges of its words.
+///
+/// Words are separated by digits and `_`, and by a change of case: `camelCase`, `HTTPResponse`.
+/// A plural of an upper case word, i.e. `URLs`, is the word without the `s`.
+fn split_identifier(token: &str) -> Vec<(usize, usize)> {
+    let mut words = vec![];
+    let mut after_digit = false;
+
+    for segment in token.split_inclusive(|c: char| c.is_numeric() || c == '_') {
+        let offset = segment.as_ptr() as usize - token.as_ptr() as usize;
+        let letters = segment.trim_end_matches(|c: char| c.is_numeric() || c == '_');
+        let leading = letters.len() - letters.trim_start_matches(['\'', '’']).len();
+        let letters = letters.trim_matches(['\'', '’']);
+
+        for (i, (start, end)) in split_case(letters).into_iter().enumerate() {
+            let word = &letters[start..end];
+            let is_suffix = i == 0
+                && after_digit
+                && word.chars().count() <= MAX_NUMBER_SUFFIX
+                && word.chars().all(|c| !c.is_uppercase());
+            if !is_suffix {
+                words.push((offset + leading + start, offset + leading + end));
+            }
+        }
+
+        after_digit = segment.ends_with(|c: char| c.is_numeric());
+    }
+
+    words
+}
+
+/// Splits letters into words where the case changes.
+fn split_case(letters: &str) -> Vec<(usize, usize)> {
+    let chars: Vec<(usize, char)> = letters.char_indices().collect();
+    let mut words = vec![];
+    let mut start = 0;
+    // The last letter with a case, so that accents and apostrophes do not break words.
+    let mut prev: Option<char> = None;
+
+    for (i, &(offset, c)) in chars.iter().enumerate() {
+        let after_apostrophe = i > 0 && matches!(chars[i - 1].1, '\'' | '’');
+        let next = chars.get(i + 1).map(|&(_, c)| c);
+        let is_plural = next == Some('s') && i + 2 == chars.len();
+        let is_boundary = match prev {
+            _ if after_apostrophe || !c.is_uppercase() => false,
+            Some(p) if p.is_lowercase() => true,
+            Some(p) if p.is_uppercase() => next.is_some_and(char::is_lowercase) && !is_plural,
+            _ => false,
+        };
+
+        if is_boundary && offset > start {
+            words.push((start, offset));
+            start = offset;
+        }
+        if c.is_uppercase() || c.is_lowercase() {
+            prev = Some(c);
+        }
+    }
+
+    if start < letters.len() {
+        let word = &letters[start..];
+        let end = match word.strip_suffix('s') {
+            Some(plural)
+                if plural.chars().count() > 1 && plural.chars().all(char::is_uppercase) =>
+            {
+                letters.len() - 1
+            }
+            _ => letters.len(),
+        };
+        words.push((start, end));
+    }
+
+    words
+}
+
+/// A 1 based line and column. Columns count UTF-16 code units, like cspell does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub col: usize,
+}
+
+/// Maps byte offsets in a document to lines and columns.
+#[derive(Debug)]
+pub struct LineIndex<'a> {
+    text: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(text: &'a str) -> Self {
+        let line_starts = [0]
+            .into_iter()
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { text, line_starts }
+    }
+
+    pub fn position(&self, offset: usize) -> Position {
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let start = self.line_starts[line];
+        Position {
+            line: line + 1,
+            col: self.text[start..offset].encode_utf16().count() + 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_extract_words() {
+        let cases = [
+            ("hello world", vec![("hello", 0), ("world", 6)]),
+            ("don't stop", vec![("don't", 0), ("stop", 6)]),
+            ("x = café_42;", vec![("x", 0), ("café", 4)]),
+            ("'quoted'", vec![("quoted", 1)]),
+            ("", vec![]),
+            (
+                "parseHTTPResponse2xx",
+                vec![("parse", 0), ("HTTP", 5), ("Response", 9)],
+            ),
+            (
+                "snake_case_NAME",
+                vec![("snake", 0), ("case", 6), ("NAME", 11)],
+            ),
+            (
+                "utf8Decoder base64encode",
+                vec![("utf", 0), ("Decoder", 4), ("base", 12), ("encode", 18)],
+            ),
+            ("10px 3rd 1e10 42", vec![]),
+            ("0xFF00 #a3f9c2 deadbeef", vec![("deadbeef", 15)]),
+            (
+                "O'Reilly can’t Bob's",
+                vec![("O'Reilly", 0), ("can’t", 9), ("Bob's", 17)],
+            ),
+            ("getURLs IDs", vec![("get", 0), ("URL", 3), ("ID", 8)]),
+            ("naïveÉcole", vec![("naïve", 0), ("École", 6)]),
+            (
+                r#""line\nNext\tab""#,
+                vec![("line", 1), ("Next", 7), ("ab", 13)],
+            ),
+        ];
+
+        for (text, expected) in cases {
+            let words: Vec<(&str, usize)> =
+                extract_words(text).map(|w| (w.text, w.offset)).collect();
+            assert_eq!(expected, words, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_utf16_offsets() {
+        let text = "😀 héllo\nwörld 𝒳 parseWord";
+        let cases = [
+            ("héllo", 5, 3),
+            ("wörld", 12, 9),
+            ("𝒳", 19, 15),
+            ("parse", 24, 18),
+            ("Word", 29, 23),
+        ];
+
+        let words: Vec<TextOffset> = extract_words(text).collect();
+        assert_eq!(cases.len(), words.len());
+        for (word, (text, offset, utf16_offset)) in words.iter().zip(cases) {
+            assert_eq!(text, word.text);
+            assert_eq!(offset, word.offset, "{text}");
+            assert_eq!(utf16_offset, word.utf16_offset, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_line_index() {
+        let text = "one\ntwo 😀 three\n\nfour";
+        let index = LineIndex::new(text);
+        let cases = [
+            (0, (1, 1)),
+            (2, (1, 3)),
+            (4, (2, 1)),
+            (8, (2, 5)),
+            (13, (2, 8)),
+            (19, (3, 1)),
+            (20, (4, 1)),
+        ];
+
+        for (offset, (line, col)) in cases {
+            assert_eq!(Position { line, col }, index.position(offset), "{offset}");
+        }
+    }
+}
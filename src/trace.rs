@@ -0,0 +1,429 @@
+use std::{
+    collections::HashMap,
+    env,
+    error::Error,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::Arc,
+};
+
+use spellrs_filetype::{find_matching_file_types, FileTypeId};
+
+use crate::{
+    cli::TraceArgs,
+    config::{merge_sources, ConfigLoader, GlobMatcher, LanguageSetting, Settings},
+    dictionary::{DictionaryLoader, SpellingDictionary, WordListDictionary},
+    lint::MIN_WORD_LENGTH,
+};
+
+/// A dictionary a word is checked against, and where it comes from.
+struct TracedDictionary {
+    name: String,
+    /// `None` when the dictionary has no definition or fails to load.
+    dictionary: Option<Arc<dyn SpellingDictionary>>,
+    ignore_case: bool,
+    /// The settings that enabled the dictionary, i.e. `cspell.json languageSettings[0]`.
+    enabled_by: Vec<String>,
+}
+
+/// Runs `spellrs trace`, printing the dictionaries each word is checked against.
+pub fn run(args: &TraceArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let cwd = env::current_dir()?;
+    let file = args.file.as_ref().map(|file| cwd.join(file));
+    let mut configs = ConfigLoader::default();
+    let config = match &args.config {
+        Some(path) => Some(path.clone()),
+        None => configs.search(file.as_ref().and_then(|f| f.parent()).unwrap_or(&cwd)),
+    };
+    // The imports are only loaded once, for both the settings and where they come from.
+    let sources = match &config {
+        Some(path) => configs.sources(path),
+        None => vec![],
+    };
+    let settings = merge_sources(&sources);
+    let file_types = file
+        .as_ref()
+        .map(|file| find_matching_file_types(&file.to_string_lossy()))
+        .unwrap_or_default();
+    let file_settings = match &file {
+        Some(file) => settings.for_file(file),
+        None => settings,
+    };
+    let settings = file_settings.for_language(&file_types);
+    let ignore_case = !settings.case_sensitive.unwrap_or(false);
+
+    let mut dictionaries = DictionaryLoader::default();
+    let mut traced = vec![];
+    for path in &args.dictionaries {
+        traced.push(TracedDictionary {
+            name: path.to_string_lossy().into_owned(),
+            dictionary: Some(dictionaries.load(path)?),
+            ignore_case,
+            enabled_by: vec!["--dictionary".to_string()],
+        });
+    }
+
+    let mut enabled = enabled_by(&sources, file.as_deref(), &file_settings, &file_types, &cwd);
+    for name in settings.enabled_dictionaries() {
+        let def = settings.dictionary_definition(name);
+        traced.push(TracedDictionary {
+            name: name.to_string(),
+            dictionary: def.and_then(|def| dictionaries.load_definition(def)),
+            ignore_case: def
+                .and_then(|def| def.case_sensitive)
+                .map_or(ignore_case, |sensitive| !sensitive),
+            enabled_by: enabled
+                .remove(name)
+                .unwrap_or_else(|| vec!["?".to_string()]),
+        });
+    }
+    let words = settings.words.iter().chain(&settings.user_words);
+    traced.push(TracedDictionary {
+        name: "[words]".to_string(),
+        dictionary: Some(Arc::new(WordListDictionary::new(
+            words,
+            &settings.forbidden_words(),
+        ))),
+        ignore_case,
+        enabled_by: vec!["words, userWords and flagWords".to_string()],
+    });
+    traced.push(TracedDictionary {
+        name: "[ignoreWords]".to_string(),
+        dictionary: Some(Arc::new(WordListDictionary::new(
+            &settings.ignore_words,
+            [],
+        ))),
+        ignore_case,
+        enabled_by: vec!["ignoreWords".to_string()],
+    });
+
+    let mut out = io::stdout().lock();
+    if let Some(config) = &config {
+        writeln!(out, "Configuration: {}", relative(config, &cwd))?;
+    }
+    if let Some(file) = &args.file {
+        writeln!(
+            out,
+            "File: {} ({})",
+            file.display(),
+            settings
+                .language_id
+                .as_ref()
+                .unwrap_or(&file_types.join(", "))
+        )?;
+    }
+    let min_word_length = settings.min_word_length.unwrap_or(MIN_WORD_LENGTH);
+    for word in &args.words {
+        writeln!(out)?;
+        write_trace(&mut out, word, &traced, min_word_length)?;
+    }
+    out.flush()?;
+
+    for error in configs.take_errors() {
+        eprintln!("spellrs: Configuration Error: {error}");
+    }
+    for error in dictionaries.take_errors() {
+        eprintln!("spellrs: Dictionary Error: {error}");
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// The settings that enable each dictionary for a file, by dictionary name, i.e.
+/// `cspell.json dictionaries` or `cspell.json languageSettings[0] (languageId: rust)`.
+///
+/// `sources` are the configuration files from [`ConfigLoader::sources`] and `settings` are the
+/// merged settings of the file, before its `languageSettings` are applied. The configuration
+/// files are shown relative to `cwd`.
+fn enabled_by(
+    sources: &[(PathBuf, Settings)],
+    file: Option<&Path>,
+    settings: &Settings,
+    file_types: &[FileTypeId],
+    cwd: &Path,
+) -> HashMap<String, Vec<String>> {
+    let mut enabled_by = HashMap::new();
+    for (path, source) in sources {
+        let config = relative(path, cwd);
+        add_names(&mut enabled_by, &source.dictionaries, || {
+            format!("{config} dictionaries")
+        });
+
+        let mut blocks: Vec<(String, &LanguageSetting)> = source
+            .language_settings
+            .iter()
+            .enumerate()
+            .map(|(i, block)| (format!("languageSettings[{i}]"), block))
+            .collect();
+        for (i, o) in source.overrides.iter().enumerate() {
+            let matches = file
+                .is_some_and(|file| GlobMatcher::new(&o.filename).is_ok_and(|m| m.is_match(file)));
+            if !matches {
+                continue;
+            }
+            add_names(&mut enabled_by, &o.settings.dictionaries, || {
+                format!("{config} overrides[{i}]")
+            });
+            blocks.extend(
+                o.settings
+                    .language_settings
+                    .iter()
+                    .enumerate()
+                    .map(|(j, block)| (format!("overrides[{i}].languageSettings[{j}]"), block)),
+            );
+        }
+
+        for (key, block) in blocks {
+            if block.applies_to(settings, file_types) {
+                add_names(&mut enabled_by, &block.settings.dictionaries, || {
+                    format!("{config} {key} ({})", describe_block(block))
+                });
+            }
+        }
+    }
+    enabled_by
+}
+
+/// Adds the label of a `dictionaries` list to the dictionaries it adds. Removals with a `!`
+/// are skipped.
+fn add_names(
+    enabled_by: &mut HashMap<String, Vec<String>>,
+    names: &[String],
+    label: impl Fn() -> String,
+) {
+    for name in names {
+        let name = name.strip_prefix("!!").unwrap_or(name);
+        if !name.starts_with('!') {
+            enabled_by
+                .entry(name.to_string())
+                .or_default()
+                .push(label());
+        }
+    }
+}
+
+/// Describes what a `languageSettings` block matches, i.e. `languageId: rust, locale: en`.
+fn describe_block(block: &LanguageSetting) -> String {
+    let mut matches = vec![];
+    if !block.language_id.is_empty() {
+        matches.push(format!("languageId: {}", block.language_id.join(",")));
+    }
+    if !block.locale.is_empty() {
+        matches.push(format!("locale: {}", block.locale.join(",")));
+    }
+    match matches.is_empty() {
+        true => "*".to_string(),
+        false => matches.join(", "),
+    }
+}
+
+/// The path relative to `dir` when it is inside it.
+fn relative(path: &Path, dir: &Path) -> String {
+    path.strip_prefix(dir)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Writes a table of the dictionaries with whether each one has or forbids the word, followed
+/// by whether the word is accepted.
+fn write_trace(
+    out: &mut impl Write,
+    word: &str,
+    traced: &[TracedDictionary],
+    min_word_length: usize,
+) -> io::Result<()> {
+    let yes_no = |value: bool| match value {
+        true => "yes",
+        false => "no",
+    };
+    let mut rows = vec![[
+        "Dictionary".to_string(),
+        "Found".to_string(),
+        "Forbidden".to_string(),
+        "Ignore case".to_string(),
+        "Enabled by".to_string(),
+    ]];
+    let mut found_in = vec![];
+    let mut forbidden_by = vec![];
+    for traced in traced {
+        let (found, forbidden) = match &traced.dictionary {
+            Some(dictionary) => {
+                let found = dictionary.has(word, traced.ignore_case);
+                let forbidden = dictionary.is_forbidden(word);
+                if found {
+                    found_in.push(traced.name.as_str());
+                }
+                if forbidden {
+                    forbidden_by.push(traced.name.as_str());
+                }
+                (yes_no(found), yes_no(forbidden))
+            }
+            None => ("not loaded", "-"),
+        };
+        rows.push([
+            traced.name.clone(),
+            found.to_string(),
+            forbidden.to_string(),
+            yes_no(traced.ignore_case).to_string(),
+            traced.enabled_by.join(", "),
+        ]);
+    }
+
+    writeln!(out, "Word: {word}")?;
+    let widths: Vec<usize> = (0..4)
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    for [name, found, forbidden, ignore_case, enabled_by] in &rows {
+        writeln!(
+            out,
+            "  {name:<0$}  {found:<1$}  {forbidden:<2$}  {ignore_case:<3$}  {enabled_by}",
+            widths[0], widths[1], widths[2], widths[3]
+        )?;
+    }
+
+    match (found_in.is_empty(), forbidden_by.is_empty()) {
+        _ if word.chars().count() < min_word_length => writeln!(
+            out,
+            "`{word}` is not checked, it is shorter than minWordLength ({min_word_length})"
+        ),
+        (_, false) => writeln!(
+            out,
+            "`{word}` is flagged, it is forbidden by {}",
+            forbidden_by.join(", ")
+        ),
+        (false, true) => writeln!(out, "`{word}` is accepted by {}", found_in.join(", ")),
+        (true, true) => writeln!(out, "`{word}` is flagged, no dictionary has it"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_enabled_by() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("base.json"),
+            r#"{ "dictionaries": ["base", "removed"] }"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("cspell.json"),
+            r#"{
+                "import": ["./base.json"],
+                "dictionaries": ["!removed", "main"],
+                "languageSettings": [
+                    { "languageId": "rust", "dictionaries": ["rust"] },
+                    { "languageId": "python", "dictionaries": ["python"] },
+                    { "languageId": "rust", "locale": "fr", "dictionaries": ["french"] }
+                ],
+                "overrides": [
+                    {
+                        "filename": "src/**",
+                        "dictionaries": ["src"],
+                        "languageSettings": [{ "dictionaries": ["any"] }]
+                    },
+                    { "filename": "docs/**", "dictionaries": ["docs"] }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let mut configs = ConfigLoader::default();
+        let config = root.join("cspell.json");
+        let sources = configs.sources(&config);
+        let file = root.join("src/main.rs");
+        let settings = configs.load(&config).for_file(&file);
+        let file_types = ["rust".to_string()];
+        let enabled = enabled_by(&sources, Some(&file), &settings, &file_types, root);
+
+        let label = |path: &str, key: &str| format!("{path} {key}");
+        let expected: HashMap<String, Vec<String>> = HashMap::from([
+            ("base", vec![label("base.json", "dictionaries")]),
+            ("removed", vec![label("base.json", "dictionaries")]),
+            ("main", vec![label("cspell.json", "dictionaries")]),
+            (
+                "rust",
+                vec![label(
+                    "cspell.json",
+                    "languageSettings[0] (languageId: rust)",
+                )],
+            ),
+            ("src", vec![label("cspell.json", "overrides[0]")]),
+            (
+                "any",
+                vec![label("cspell.json", "overrides[0].languageSettings[0] (*)")],
+            ),
+        ])
+        .into_iter()
+        .map(|(name, labels)| (name.to_string(), labels))
+        .collect();
+        assert_eq!(expected, enabled);
+    }
+
+    #[test]
+    fn test_write_trace() {
+        let words = ["hello".to_string(), "Paris".to_string()];
+        let forbidden = ["helo".to_string()];
+        let traced = [
+            TracedDictionary {
+                name: "en".to_string(),
+                dictionary: Some(Arc::new(WordListDictionary::new(&words, []))),
+                ignore_case: false,
+                enabled_by: vec!["cspell.json dictionaries".to_string()],
+            },
+            TracedDictionary {
+                name: "missing".to_string(),
+                dictionary: None,
+                ignore_case: true,
+                enabled_by: vec!["?".to_string()],
+            },
+            TracedDictionary {
+                name: "[words]".to_string(),
+                dictionary: Some(Arc::new(WordListDictionary::new(&words, &forbidden))),
+                ignore_case: true,
+                enabled_by: vec!["words, userWords and flagWords".to_string()],
+            },
+        ];
+
+        let mut out = vec![];
+        write_trace(&mut out, "hello", &traced, 4).unwrap();
+        let expected = "\
+Word: hello
+  Dictionary  Found       Forbidden  Ignore case  Enabled by
+  en          yes         no         no           cspell.json dictionaries
+  missing     not loaded  -          yes          ?
+  [words]     yes         no         yes          words, userWords and flagWords
+`hello` is accepted by en, [words]
+";
+        assert_eq!(expected, String::from_utf8(out).unwrap());
+
+        let cases = [
+            ("paris", "`paris` is accepted by [words]"),
+            ("helo", "`helo` is flagged, it is forbidden by [words]"),
+            ("wrold", "`wrold` is flagged, no dictionary has it"),
+            (
+                "hi",
+                "`hi` is not checked, it is shorter than minWordLength (4)",
+            ),
+        ];
+
+        for (word, expected) in cases {
+            let mut out = vec![];
+            write_trace(&mut out, word, &traced, 4).unwrap();
+            let out = String::from_utf8(out).unwrap();
+            assert_eq!(Some(expected), out.lines().last(), "{word}");
+        }
+    }
+}